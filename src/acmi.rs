@@ -0,0 +1,137 @@
+//! Tacview ACMI flight-recording of the telemetry stream.
+//!
+//! Every GPS/IMU update is serialized as a timestamped ACMI frame so a mission
+//! can be replayed and scrubbed offline. The file is the text ACMI flavour: a
+//! global header block followed by `#<seconds>` time lines, each carrying one
+//! object line of the form
+//! `<id>,T=<lon>|<lat>|<alt>,Roll=<r>,Pitch=<p>,Heading=<h>` plus custom numeric
+//! properties for the power, temperature and weather channels the UI tracks.
+//!
+//! [`load`] reads such a file back into a list of `(seconds, packets)` frames,
+//! where `packets` are the legacy telemetry strings understood by
+//! `TelemetryUi::handle_packet`, so a recorded run can drive the live GUI.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+/// The single object id the rover is recorded under.
+const ROVER_ID: &'static str = "101";
+
+/// One telemetry snapshot to serialize as an ACMI frame.
+pub struct Frame {
+    pub lon: f64,
+    pub lat: f64,
+    pub alt: f64,
+    pub roll: f64,
+    pub pitch: f64,
+    pub heading: f64,
+    pub speed: f64,
+    /// Extra `Name=value` numeric channels (e.g. `H48V`, `LMotorTemp`).
+    pub props: Vec<(&'static str, f64)>,
+}
+
+/// Serializes telemetry frames to a text ACMI file.
+pub struct AcmiRecorder {
+    out: BufWriter<File>,
+}
+
+impl AcmiRecorder {
+    /// Create the file and write the global header block. `reference_time` is an
+    /// ISO-8601 stamp for the mission start.
+    pub fn new(path: &str, reference_time: &str) -> Option<AcmiRecorder> {
+        let file = match File::create(path) {
+            Ok(f) => f,
+            Err(_) => return None,
+        };
+        let mut out = BufWriter::new(file);
+        writeln!(&mut out, "FileType=text/acmi/tacview").ok();
+        writeln!(&mut out, "FileVersion=2.1").ok();
+        writeln!(&mut out, "0,ReferenceTime={}", reference_time).ok();
+        writeln!(&mut out, "0,ReferenceLongitude=0").ok();
+        writeln!(&mut out, "0,ReferenceLatitude=0").ok();
+        Some(AcmiRecorder { out: out })
+    }
+
+    /// Emit one timestamped frame at `secs` seconds since the reference time.
+    pub fn record(&mut self, secs: f64, frame: &Frame) {
+        writeln!(&mut self.out, "#{:.2}", secs).ok();
+        write!(&mut self.out, "{},T={}|{}|{},Roll={:.2},Pitch={:.2},Heading={:.2},Speed={:.2}",
+               ROVER_ID, frame.lon, frame.lat, frame.alt, frame.roll, frame.pitch,
+               frame.heading, frame.speed).ok();
+        for &(name, value) in &frame.props {
+            write!(&mut self.out, ",{}={:.3}", name, value).ok();
+        }
+        writeln!(&mut self.out, "").ok();
+    }
+}
+
+/// Load an ACMI recording into `(seconds, packets)` frames. Each returned packet
+/// is a legacy telemetry string ready to hand to `handle_packet`.
+pub fn load(path: &str) -> Vec<(f64, Vec<String>)> {
+    let mut frames = Vec::new();
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return frames,
+    };
+
+    let mut time = 0.0;
+    for line in BufReader::new(file).lines() {
+        let line = match line { Ok(l) => l, Err(_) => continue };
+        let line = line.trim();
+        if line.starts_with('#') {
+            time = line[1..].parse().unwrap_or(time);
+        } else if line.starts_with(ROVER_ID) && line.contains("T=") {
+            frames.push((time, frame_to_packets(line)));
+        }
+    }
+    frames
+}
+
+/// Reconstruct the legacy telemetry strings carried by one ACMI object line.
+fn frame_to_packets(line: &str) -> Vec<String> {
+    let mut t = (0.0, 0.0, 0.0); // lon, lat, alt
+    let mut roll = 0.0;
+    let mut pitch = 0.0;
+    let mut heading = 0.0;
+    let mut speed = 0.0;
+    let mut props: Vec<(String, f64)> = Vec::new();
+
+    for field in line.split(',').skip(1) {
+        let mut kv = field.splitn(2, '=');
+        let (key, val) = match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) => (k, v),
+            _ => continue,
+        };
+        match key {
+            "T" => {
+                let parts: Vec<f64> = val.split('|').map(|p| p.parse().unwrap_or(0.0)).collect();
+                if parts.len() >= 3 { t = (parts[0], parts[1], parts[2]); }
+            },
+            "Roll" => roll = val.parse().unwrap_or(0.0),
+            "Pitch" => pitch = val.parse().unwrap_or(0.0),
+            "Heading" => heading = val.parse().unwrap_or(0.0),
+            "Speed" => speed = val.parse().unwrap_or(0.0),
+            _ => { if let Ok(v) = val.parse::<f64>() { props.push((key.to_string(), v)); } },
+        }
+    }
+
+    let mut packets = Vec::new();
+    // GPS: id:lat:lon:speed:alt:angle
+    packets.push(format!("GPS:{}:{}:{}:{}:{}", t.1, t.0, speed, t.2, heading));
+    // Attitude, reusing the MAVLink attitude path which takes pitch/roll/heading.
+    packets.push(format!("MAV_ATT:{}:{}:{}", pitch, roll, heading));
+
+    // Power and temperature channels, grouped back into their native packets.
+    let get = |name: &str| props.iter().find(|&&(ref k, _)| k == name).map(|&(_, v)| v);
+    if let (Some(h48), Some(h24), Some(p12e), Some(p12pl)) =
+        (get("H48V"), get("H24V"), get("P12EV"), get("P12PLV")) {
+        packets.push(format!("VOLT:{}:{}:{}:{}", h48, h24, p12e, p12pl));
+    }
+    if let Some(v) = get("LMotorTemp") { packets.push(format!("L_MOTOR_TEMP:{}", v)); }
+    if let Some(v) = get("RMotorTemp") { packets.push(format!("R_MOTOR_TEMP:{}", v)); }
+    if let Some(v) = get("UprATemp") { packets.push(format!("UPR_A_TEMP:{}", v)); }
+    if let Some(v) = get("LwrATemp") { packets.push(format!("LWR_A_TEMP:{}", v)); }
+    if let Some(v) = get("WindSpeed") { packets.push(format!("W_WND_SPD:{}", v)); }
+
+    packets
+}
@@ -0,0 +1,106 @@
+use graphics::{Context, Graphics};
+use graphics::character::CharacterCache;
+
+/// HUD-style artificial horizon. Driven by the fused `(pitch, roll, heading)`
+/// estimate, it draws a rotating/translating horizon line, a fixed aircraft
+/// reference symbol, a scrolling pitch ladder labeled in degrees and a compass
+/// strip along the top. When no attitude is available it shows a caged state
+/// rather than a misleading level horizon.
+pub struct AttitudeIndicator {
+    pub size: (f64, f64),
+    // Vertical scale of the pitch ladder and horizon travel.
+    pixels_per_degree: f64,
+}
+
+impl AttitudeIndicator {
+    pub fn new(size: (f64, f64)) -> AttitudeIndicator {
+        AttitudeIndicator { size: size, pixels_per_degree: 2.0 }
+    }
+
+    pub fn draw<G: Graphics, C>(&self, attitude: Option<(f64, f64, f64)>,
+                                c: Context, g: &mut G, character_cache: &mut C)
+                                where C: CharacterCache<Texture=G::Texture> {
+        use graphics::*;
+
+        let (w, h) = self.size;
+        let (cx, cy) = (w / 2.0, h / 2.0);
+
+        // Instrument background.
+        Rectangle::new([0.1, 0.1, 0.15, 1.0])
+            .draw([0.0, 0.0, w, h], &c.draw_state, c.transform, g);
+
+        let (pitch, roll, heading) = match attitude {
+            Some(prh) => prh,
+            None => {
+                // Caged / invalid: a crossed-out face with a label.
+                Line::new([1.0, 0.2, 0.2, 1.0], 2.0)
+                    .draw([0.0, 0.0, w, h], &c.draw_state, c.transform, g);
+                Line::new([1.0, 0.2, 0.2, 1.0], 2.0)
+                    .draw([0.0, h, w, 0.0], &c.draw_state, c.transform, g);
+                let c = c.trans(cx - 22.0, cy - 4.0);
+                Text::new_color([1.0, 0.2, 0.2, 1.0], 12)
+                    .draw("CAGED", character_cache, &c.draw_state, c.transform, g);
+                return;
+            },
+        };
+
+        // Horizon and pitch ladder, drawn in a frame rolled and pitched with
+        // the rover so the ground/sky reference moves under the fixed symbol.
+        {
+            let c = c.trans(cx, cy).rot_deg(roll).trans(0.0, pitch * self.pixels_per_degree);
+
+            // Horizon line.
+            Line::new([1.0, 1.0, 1.0, 1.0], 1.5)
+                .draw([-w, 0.0, w, 0.0], &c.draw_state, c.transform, g);
+
+            // Pitch ladder: a labeled tick every ten degrees either side.
+            for step in -9..10 {
+                if step == 0 { continue; }
+                let deg = (step * 10) as f64;
+                let y = -deg * self.pixels_per_degree;
+                let half = 20.0;
+                Line::new([1.0, 1.0, 1.0, 1.0], 1.0)
+                    .draw([-half, y, half, y], &c.draw_state, c.transform, g);
+                let c = c.trans(half + 2.0, y + 4.0);
+                Text::new_color([1.0, 1.0, 1.0, 1.0], 10)
+                    .draw(format!("{}", deg.abs() as i32).as_str(),
+                          character_cache, &c.draw_state, c.transform, g);
+            }
+        }
+
+        // Fixed aircraft reference symbol at the instrument center.
+        Line::new([1.0, 1.0, 0.0, 1.0], 2.0)
+            .draw([cx - 20.0, cy, cx - 6.0, cy], &c.draw_state, c.transform, g);
+        Line::new([1.0, 1.0, 0.0, 1.0], 2.0)
+            .draw([cx + 6.0, cy, cx + 20.0, cy], &c.draw_state, c.transform, g);
+        Line::new([1.0, 1.0, 0.0, 1.0], 2.0)
+            .draw([cx, cy - 3.0, cx, cy + 3.0], &c.draw_state, c.transform, g);
+
+        // Compass heading strip across the top: tick marks every 30 degrees
+        // scrolling with heading, plus the numeric heading centered.
+        {
+            let strip_h = 14.0;
+            Rectangle::new([0.0, 0.0, 0.0, 0.6])
+                .draw([0.0, 0.0, w, strip_h], &c.draw_state, c.transform, g);
+            let pixels_per_deg = w / 90.0; // ±45° visible
+            for tick in 0..12 {
+                let tick_deg = (tick * 30) as f64;
+                let mut delta = tick_deg - heading;
+                while delta > 180.0 { delta -= 360.0; }
+                while delta < -180.0 { delta += 360.0; }
+                let x = cx + delta * pixels_per_deg;
+                if x < 0.0 || x > w { continue; }
+                Line::new([1.0, 1.0, 1.0, 1.0], 1.0)
+                    .draw([x, 0.0, x, strip_h], &c.draw_state, c.transform, g);
+                let c = c.trans(x - 8.0, strip_h - 3.0);
+                Text::new_color([1.0, 1.0, 1.0, 1.0], 9)
+                    .draw(format!("{}", tick_deg as i32).as_str(),
+                          character_cache, &c.draw_state, c.transform, g);
+            }
+            let c = c.trans(cx - 12.0, strip_h + 12.0);
+            Text::new_color([0.0, 1.0, 0.0, 1.0], 12)
+                .draw(format!("{:03}", heading as i32).as_str(),
+                      character_cache, &c.draw_state, c.transform, g);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Two-way Opus voice intercom, independent of the video pipeline.
+//!
+//! The operator holds push-to-talk to capture microphone input, which is
+//! encoded into 20 ms Opus frames, tagged with a sequence number, and sent on a
+//! dedicated UDP port. The receive side decodes the rover's microphone feed
+//! through a small jitter buffer that reorders by sequence, drops late frames,
+//! and conceals a single lost frame. A failed camera never touches this path.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+use cpal;
+use opus;
+
+/// 48 kHz mono, the Opus internal rate we run the intercom at.
+const SAMPLE_RATE: u32 = 48_000;
+/// 20 ms at 48 kHz.
+const FRAME_SAMPLES: usize = 960;
+/// How many frames we hold before playing, to ride out reordering.
+const JITTER_DEPTH: u16 = 3;
+
+/// A duplex Opus voice channel bound to its own UDP port.
+pub struct VoiceChannel {
+    talking: Arc<AtomicBool>,
+    /// Smoothed capture level in [0, 1] for the VU meter overlay.
+    vu_level: Arc<Mutex<f32>>,
+    _tx_thread: thread::JoinHandle<()>,
+    _rx_thread: thread::JoinHandle<()>,
+}
+
+impl VoiceChannel {
+    /// Open the intercom. `socket` should be bound to the dedicated voice port;
+    /// frames are sent to `peer`.
+    pub fn new(socket: UdpSocket, peer: (String, u16)) -> VoiceChannel {
+        let talking = Arc::new(AtomicBool::new(false));
+        let vu_level = Arc::new(Mutex::new(0.0));
+
+        let tx_thread = Self::spawn_tx(socket.try_clone().unwrap(), peer,
+                                       talking.clone(), vu_level.clone());
+        let rx_thread = Self::spawn_rx(socket);
+
+        VoiceChannel {
+            talking: talking,
+            vu_level: vu_level,
+            _tx_thread: tx_thread,
+            _rx_thread: rx_thread,
+        }
+    }
+
+    /// Engage / release push-to-talk.
+    pub fn set_talking(&self, talking: bool) {
+        self.talking.store(talking, Ordering::SeqCst);
+    }
+
+    /// Current smoothed capture level for the VU meter.
+    pub fn vu_level(&self) -> f32 {
+        *self.vu_level.lock().unwrap()
+    }
+
+    fn spawn_tx(socket: UdpSocket, peer: (String, u16),
+                talking: Arc<AtomicBool>, vu_level: Arc<Mutex<f32>>) -> thread::JoinHandle<()> {
+        thread::Builder::new().name("voice_tx".to_string()).spawn(move || {
+            let mut encoder = opus::Encoder::new(SAMPLE_RATE, opus::Channels::Mono,
+                                                 opus::Application::Voip).unwrap();
+            let (pcm_t, pcm_r) = channel::<Vec<i16>>();
+            let _stream = capture_stream(pcm_t);
+
+            let mut seq: u16 = 0;
+            while let Ok(frame) = pcm_r.recv() {
+                // Track level even when muted so the meter reacts immediately
+                let level = frame.iter().map(|s| (*s as f32 / 32768.0).abs()).fold(0.0, f32::max);
+                {
+                    let mut vu = vu_level.lock().unwrap();
+                    *vu = (*vu * 0.8).max(level);
+                }
+                if !talking.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let mut packet = vec![(seq >> 8) as u8, seq as u8];
+                let encoded = encoder.encode_vec(&frame, FRAME_SAMPLES).unwrap();
+                packet.extend_from_slice(&encoded);
+                socket.send_to(&packet, (peer.0.as_str(), peer.1)).ok();
+                seq = seq.wrapping_add(1);
+            }
+        }).unwrap()
+    }
+
+    fn spawn_rx(socket: UdpSocket) -> thread::JoinHandle<()> {
+        thread::Builder::new().name("voice_rx".to_string()).spawn(move || {
+            let mut decoder = opus::Decoder::new(SAMPLE_RATE, opus::Channels::Mono).unwrap();
+            let (pcm_t, pcm_r) = channel::<Vec<i16>>();
+            let _stream = playback_stream(pcm_r);
+
+            let mut jitter: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+            let mut next_seq: Option<u16> = None;
+            let mut buf = [0u8; 1500];
+
+            loop {
+                let n = match socket.recv_from(&mut buf) {
+                    Ok((n, _)) if n > 2 => n,
+                    _ => continue,
+                };
+                let seq = ((buf[0] as u16) << 8) | (buf[1] as u16);
+                let start = next_seq.unwrap_or(seq);
+                // Drop frames that arrive after we've already played past them
+                if seq.wrapping_sub(start) > 0x8000 {
+                    continue;
+                }
+                jitter.insert(seq, buf[2..n].to_vec());
+
+                while jitter.len() as u16 > JITTER_DEPTH {
+                    let want = next_seq.unwrap_or_else(|| *jitter.keys().next().unwrap());
+                    let pcm = match jitter.remove(&want) {
+                        Some(encoded) => {
+                            let mut out = vec![0i16; FRAME_SAMPLES];
+                            decoder.decode(&encoded, &mut out, false).ok();
+                            out
+                        },
+                        None => {
+                            // One missing frame: ask Opus to conceal it
+                            let mut out = vec![0i16; FRAME_SAMPLES];
+                            decoder.decode(&[], &mut out, false).ok();
+                            out
+                        },
+                    };
+                    pcm_t.send(pcm).ok();
+                    next_seq = Some(want.wrapping_add(1));
+                }
+            }
+        }).unwrap()
+    }
+}
+
+/// Open the default input device and forward 20 ms PCM frames to `sink`.
+///
+/// cpal's event loop blocks forever once started, so it gets its own thread;
+/// the handle is only kept by the caller to pin the thread's lifetime to the
+/// `VoiceChannel`, the same role `_tx_thread`/`_rx_thread` play on `self`.
+fn capture_stream(sink: Sender<Vec<i16>>) -> thread::JoinHandle<()> {
+    thread::Builder::new().name("voice_capture".to_string()).spawn(move || {
+        let device = cpal::default_input_device().expect("no input device");
+        let format = device.default_input_format().unwrap();
+        let event_loop = cpal::EventLoop::new();
+        let stream_id = event_loop.build_input_stream(&device, &format).unwrap();
+        event_loop.play_stream(stream_id);
+
+        // Accumulate samples into FRAME_SAMPLES-sized chunks regardless of the
+        // device's native sample format, so the Opus encoder always sees i16.
+        let mut pending: Vec<i16> = Vec::with_capacity(FRAME_SAMPLES);
+        event_loop.run(move |_id, data| {
+            let buffer = match data {
+                Ok(cpal::StreamData::Input { buffer }) => buffer,
+                _ => return,
+            };
+            match buffer {
+                cpal::UnknownTypeInputBuffer::I16(buffer) => {
+                    push_frames(&mut pending, buffer.iter().cloned(), &sink);
+                },
+                cpal::UnknownTypeInputBuffer::U16(buffer) => {
+                    push_frames(&mut pending, buffer.iter().map(|&s| (s as i32 - 32768) as i16), &sink);
+                },
+                cpal::UnknownTypeInputBuffer::F32(buffer) => {
+                    push_frames(&mut pending, buffer.iter().map(|&s| (s * 32767.0) as i16), &sink);
+                },
+            }
+        });
+    }).unwrap()
+}
+
+/// Feed `samples` into `pending`, dispatching a full `FRAME_SAMPLES` frame to
+/// `sink` each time it fills.
+fn push_frames<I: Iterator<Item = i16>>(pending: &mut Vec<i16>, samples: I, sink: &Sender<Vec<i16>>) {
+    for sample in samples {
+        pending.push(sample);
+        if pending.len() == FRAME_SAMPLES {
+            sink.send(pending.clone()).ok();
+            pending.clear();
+        }
+    }
+}
+
+/// Open the default output device and play PCM frames pulled from `source`.
+fn playback_stream(source: std::sync::mpsc::Receiver<Vec<i16>>) -> thread::JoinHandle<()> {
+    thread::Builder::new().name("voice_playback".to_string()).spawn(move || {
+        let device = cpal::default_output_device().expect("no output device");
+        let format = device.default_output_format().unwrap();
+        let event_loop = cpal::EventLoop::new();
+        let stream_id = event_loop.build_output_stream(&device, &format).unwrap();
+        event_loop.play_stream(stream_id);
+
+        // Decoded frames arrive in bursts from the jitter buffer; queue them
+        // and drain sample-by-sample so the device callback is never starved
+        // mid-frame. Silence fills an empty queue rather than blocking.
+        let mut pending: VecDeque<i16> = VecDeque::new();
+        event_loop.run(move |_id, data| {
+            let buffer = match data {
+                Ok(cpal::StreamData::Output { buffer }) => buffer,
+                _ => return,
+            };
+            match buffer {
+                cpal::UnknownTypeOutputBuffer::I16(mut buffer) => {
+                    for sample in buffer.iter_mut() {
+                        *sample = next_sample(&mut pending, &source);
+                    }
+                },
+                cpal::UnknownTypeOutputBuffer::U16(mut buffer) => {
+                    for sample in buffer.iter_mut() {
+                        *sample = (next_sample(&mut pending, &source) as i32 + 32768) as u16;
+                    }
+                },
+                cpal::UnknownTypeOutputBuffer::F32(mut buffer) => {
+                    for sample in buffer.iter_mut() {
+                        *sample = next_sample(&mut pending, &source) as f32 / 32767.0;
+                    }
+                },
+            }
+        });
+    }).unwrap()
+}
+
+/// Pop the next playback sample, pulling a fresh decoded frame from `source`
+/// once the queue runs dry.
+fn next_sample(pending: &mut VecDeque<i16>, source: &std::sync::mpsc::Receiver<Vec<i16>>) -> i16 {
+    if pending.is_empty() {
+        if let Ok(frame) = source.try_recv() {
+            pending.extend(frame);
+        }
+    }
+    pending.pop_front().unwrap_or(0)
+}
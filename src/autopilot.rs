@@ -0,0 +1,238 @@
+//! GPS waypoint autopilot with heading-hold differential steering.
+//!
+//! This is the notecard-driven rover autopilot from the original Second Life
+//! control script brought into the ground station: a queue of WGS84 waypoints
+//! is driven one leg at a time, steering the two tracks differentially to hold
+//! the great-circle bearing to the active target. On each tick it derives the
+//! initial bearing and haversine range to the head of the queue, forms a
+//! wrapped heading error, and mixes a proportional differential into a base
+//! forward speed. The base speed is faired between legs with a cubic Hermite
+//! blend so the rover doesn't jerk when a waypoint is reached and the next leg
+//! begins.
+//!
+//! The autopilot owns no socket; `update` returns the command the UI should
+//! emit so the same dead-banded setpoint path (`try_update_*`) and `send_brake`
+//! that carry the manual drive also carry the autopilot.
+
+use std::collections::VecDeque;
+
+/// Proportional steering gain: commanded RPM spread per degree of heading error.
+const STEER_GAIN: f32 = 1.5;
+
+/// Default arrival radius (metres) before a waypoint is considered reached.
+const ARRIVAL_RADIUS_M: f64 = 3.0;
+
+/// Hold the last command this long after losing the GPS fix, then brake.
+const FIX_TIMEOUT_SECS: f64 = 1.5;
+
+/// Mean Earth radius, metres.
+const EARTH_RADIUS_M: f64 = 6371000.0;
+
+/// What the autopilot wants the UI to do this tick.
+pub enum AutopilotCmd {
+    /// Not engaged, or holding with nothing to do.
+    Idle,
+    /// Drive the tracks at these left/right RPMs.
+    Drive(f32, f32),
+    /// Zero the motors and send a brake (arrived, queue empty, or fix lost).
+    Brake,
+}
+
+/// Queue of target fixes plus the running state of the current leg.
+pub struct Autopilot {
+    waypoints: VecDeque<(f64, f64)>,
+    arrival_radius: f64,
+    engaged: bool,
+
+    /// Base speed (RPM) carried into the current leg, faired toward the leg's
+    /// cruise target by the Hermite profile.
+    entry_speed: f32,
+    /// Range (metres) to the active waypoint when the leg began, for progress.
+    leg_start_dist: f64,
+    /// Set when a fresh waypoint becomes active so the next tick re-bases the leg.
+    new_leg: bool,
+
+    /// Seconds since the last valid GPS fix, for the hold-then-brake timeout.
+    fix_lost_secs: f64,
+    /// Last drive command, replayed while holding through a brief fix dropout.
+    last_cmd: (f32, f32),
+}
+
+impl Autopilot {
+    pub fn new() -> Autopilot {
+        Autopilot {
+            waypoints: VecDeque::new(),
+            arrival_radius: ARRIVAL_RADIUS_M,
+            engaged: false,
+            entry_speed: 0.0,
+            leg_start_dist: 0.0,
+            new_leg: true,
+            fix_lost_secs: 0.0,
+            last_cmd: (0.0, 0.0),
+        }
+    }
+
+    /// Replace the waypoint queue, e.g. from a loaded route file.
+    pub fn set_waypoints(&mut self, waypoints: Vec<(f64, f64)>) {
+        self.waypoints = waypoints.into_iter().collect();
+        self.new_leg = true;
+    }
+
+    /// Load waypoints from a simple `lat,lon` per-line file, ignoring blank and
+    /// `#` comment lines, mirroring the route notecard format.
+    pub fn load_route(&mut self, text: &str) {
+        let mut route = vec![];
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split(',');
+            if let (Some(lat), Some(lon)) = (parts.next(), parts.next()) {
+                if let (Ok(lat), Ok(lon)) = (lat.trim().parse::<f64>(), lon.trim().parse::<f64>()) {
+                    route.push((lat, lon));
+                }
+            }
+        }
+        self.set_waypoints(route);
+    }
+
+    pub fn engaged(&self) -> bool {
+        self.engaged
+    }
+
+    /// Arm the autopilot. Starts the current leg fresh so the speed fairing
+    /// ramps up from the current base rather than stepping.
+    pub fn engage(&mut self) {
+        self.engaged = true;
+        self.new_leg = true;
+        self.fix_lost_secs = 0.0;
+    }
+
+    /// Disarm and forget the in-flight command. Called the moment the operator
+    /// touches a drive key so manual input always wins.
+    pub fn disengage(&mut self) {
+        self.engaged = false;
+        self.entry_speed = 0.0;
+        self.last_cmd = (0.0, 0.0);
+    }
+
+    /// Remaining waypoint count, for the engage button label.
+    pub fn remaining(&self) -> usize {
+        self.waypoints.len()
+    }
+
+    /// Step the navigation loop. `fix` is the current `(lat, lon)` and `heading`
+    /// the IMU heading in degrees, both `None` if unreported. `motor_speed` is
+    /// the UI speed scale; the leg cruises at `100 * motor_speed` RPM.
+    pub fn update(&mut self, fix: Option<(f64, f64)>, heading: Option<f64>,
+                  motor_speed: f32, dt: f64) -> AutopilotCmd {
+        if !self.engaged {
+            return AutopilotCmd::Idle;
+        }
+
+        // Losing either the fix or the heading holds the last command briefly
+        // in case it is a momentary dropout, then brakes rather than driving
+        // blind on a stale bearing.
+        let (lat, lon, heading) = match (fix, heading) {
+            (Some((lat, lon)), Some(heading)) => {
+                self.fix_lost_secs = 0.0;
+                (lat, lon, heading)
+            },
+            _ => {
+                self.fix_lost_secs += dt;
+                if self.fix_lost_secs >= FIX_TIMEOUT_SECS {
+                    // Give up on the route rather than braking blind forever;
+                    // the operator re-engages once the fix is back.
+                    self.disengage();
+                    return AutopilotCmd::Brake;
+                }
+                return AutopilotCmd::Drive(self.last_cmd.0, self.last_cmd.1);
+            },
+        };
+
+        // Retire every waypoint already inside the arrival radius; a tight
+        // cluster can clear several in one tick.
+        loop {
+            let target = match self.waypoints.front() {
+                Some(&wp) => wp,
+                None => {
+                    // Route complete.
+                    self.disengage();
+                    return AutopilotCmd::Brake;
+                },
+            };
+            let dist = haversine(lat, lon, target.0, target.1);
+            if dist < self.arrival_radius {
+                self.waypoints.pop_front();
+                self.new_leg = true;
+                continue;
+            }
+
+            // Re-base the leg when a new waypoint becomes active so the Hermite
+            // fairing blends from the speed we arrived with, not from zero.
+            if self.new_leg {
+                self.entry_speed = self.last_cmd.0.abs().max(self.last_cmd.1.abs());
+                self.leg_start_dist = dist;
+                self.new_leg = false;
+            }
+
+            let cruise = 100.0 * motor_speed;
+
+            // Leg progress 0..1, faired with h(t) = 2t^3 - 3t^2 + 1 (1 at the
+            // start of the leg, 0 at arrival) so `base` eases from the carried
+            // entry speed up to the cruise target.
+            let t = if self.leg_start_dist > 0.0 {
+                (1.0 - dist / self.leg_start_dist).max(0.0).min(1.0) as f32
+            } else {
+                1.0
+            };
+            let h = 2.0 * t * t * t - 3.0 * t * t + 1.0;
+            let base = cruise + (self.entry_speed - cruise) * h;
+
+            // Heading error wrapped to [-180, 180], applied as a proportional
+            // differential about the base speed.
+            let bearing = initial_bearing(lat, lon, target.0, target.1);
+            let error = normalize_deg(bearing - heading) as f32;
+            let spread = STEER_GAIN * error;
+            let lim = cruise;
+            let l_rpm = (base + spread).max(-lim).min(lim);
+            let r_rpm = (base - spread).max(-lim).min(lim);
+
+            self.last_cmd = (l_rpm, r_rpm);
+            return AutopilotCmd::Drive(l_rpm, r_rpm);
+        }
+    }
+}
+
+/// Great-circle distance between two fixes in metres (haversine formula).
+fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    EARTH_RADIUS_M * 2.0 * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Initial great-circle bearing from fix 1 to fix 2, degrees in `[0, 360)`.
+fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Wrap an angle in degrees to `[-180, 180]`.
+fn normalize_deg(deg: f64) -> f64 {
+    let mut d = (deg + 180.0) % 360.0;
+    if d < 0.0 {
+        d += 360.0;
+    }
+    d - 180.0
+}
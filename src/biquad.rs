@@ -0,0 +1,70 @@
+use std::f64::consts::PI;
+
+/// Second-order Butterworth low-pass filter realized as a single biquad in
+/// direct-form-II-transposed. One instance smooths one telemetry channel; the
+/// cutoff is set relative to the channel's sample rate so slow signals (e.g.
+/// temperatures) can be filtered harder than fast electrical ones. A channel
+/// with no cutoff configured runs in bypass and passes samples through
+/// unchanged.
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    d1: f64,
+    d2: f64,
+    bypass: bool,
+}
+
+impl Biquad {
+    /// Bypassed filter that passes every sample through untouched.
+    pub fn bypass() -> Biquad {
+        Biquad {
+            b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0,
+            d1: 0.0, d2: 0.0, bypass: true,
+        }
+    }
+
+    /// Butterworth low-pass with the given `cutoff` and `sample_rate` in Hz.
+    pub fn low_pass(cutoff: f64, sample_rate: f64) -> Biquad {
+        let fr = cutoff / sample_rate;
+        let ohm = (PI * fr).tan();
+        let k = 2.0 * (PI / 4.0).cos();
+        let c = 1.0 + k * ohm + ohm * ohm;
+
+        let b0 = ohm * ohm / c;
+        Biquad {
+            b0: b0,
+            b1: 2.0 * b0,
+            b2: b0,
+            a1: 2.0 * (ohm * ohm - 1.0) / c,
+            a2: (1.0 - k * ohm + ohm * ohm) / c,
+            d1: 0.0,
+            d2: 0.0,
+            bypass: false,
+        }
+    }
+
+    /// Seed the delay states so a steady `sample` input yields that sample on
+    /// the first call, keeping the filter from ramping up from zero.
+    pub fn reset(&mut self, sample: f64) {
+        if self.bypass {
+            return;
+        }
+        // Steady-state delay values for a constant input `sample`.
+        self.d2 = (self.b2 - self.a2) * sample;
+        self.d1 = (self.b1 - self.a1) * sample + self.d2;
+    }
+
+    /// Filter one sample and return the smoothed output.
+    pub fn filter(&mut self, x: f64) -> f64 {
+        if self.bypass {
+            return x;
+        }
+        let out = self.b0 * x + self.d1;
+        self.d1 = self.b1 * x - self.a1 * out + self.d2;
+        self.d2 = self.b2 * x - self.a2 * out;
+        out
+    }
+}
@@ -0,0 +1,109 @@
+//! Mission black-box recorder and replay source.
+//!
+//! The Start button only rolled video; the telemetry and the commands that
+//! produced it were lost. This writes a plain-text flight recorder to
+//! `mission_data/<folder>/blackbox.log`, one line per event tagged with the
+//! time in milliseconds since recording began:
+//!
+//! ```text
+//! 1234 IN GPS:37.1:-122.2:1.4:30:88
+//! 1250 OUT 10.10.153.8:30001 A42
+//! ```
+//!
+//! [`Player`] loads the inbound lines back so the GUI can feed them through
+//! `handle_packet` at their original relative timing, reproducing a mission
+//! with no rover connected. Outbound lines are recorded for analysis but are
+//! not replayed - replay runs the ground station in a non-transmitting state.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::time::Instant;
+
+/// File name of the black-box log inside a mission folder.
+const LOG_FILE: &'static str = "blackbox.log";
+
+fn log_path(mission_folder: &str) -> String {
+    format!("mission_data/{}/{}", mission_folder, LOG_FILE)
+}
+
+/// Appends inbound and outbound events to a mission's black-box log,
+/// timestamped relative to when it was opened.
+pub struct Recorder {
+    file: File,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Create (truncating any previous) the log in `mission_folder` and start
+    /// the relative clock. A failure is surfaced so the caller can run
+    /// unrecorded rather than crash.
+    pub fn open(mission_folder: &str) -> io::Result<Recorder> {
+        let file = File::create(log_path(mission_folder))?;
+        Ok(Recorder { file: file, start: Instant::now() })
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        let e = self.start.elapsed();
+        e.as_secs() * 1000 + e.subsec_nanos() as u64 / 1_000_000
+    }
+
+    /// Log one inbound packet string.
+    pub fn record_inbound(&mut self, packet: &str) {
+        let _ = writeln!(self.file, "{} IN {}", self.elapsed_ms(), packet);
+    }
+
+    /// Log one outbound datagram, with its destination and a lossy text view of
+    /// the payload (trailing null terminator stripped).
+    pub fn record_outbound(&mut self, data: &[u8], addr: &(String, u16)) {
+        let payload = String::from_utf8_lossy(data);
+        let payload = payload.trim_right_matches('\u{0}');
+        let _ = writeln!(self.file, "{} OUT {}:{} {}", self.elapsed_ms(), addr.0, addr.1, payload);
+    }
+}
+
+/// The recorded inbound packets of a mission, for replay.
+pub struct Player {
+    events: Vec<(u64, String)>,
+}
+
+impl Player {
+    /// Load the inbound events from `mission_folder`'s black-box log, in time
+    /// order. Outbound lines are skipped.
+    pub fn load(mission_folder: &str) -> io::Result<Player> {
+        let file = File::open(log_path(mission_folder))?;
+        let mut events = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = match line { Ok(l) => l, Err(_) => continue };
+            // `<t_ms> IN <payload>`
+            let mut parts = line.splitn(3, ' ');
+            let t_ms = match parts.next().and_then(|t| t.parse::<u64>().ok()) {
+                Some(t) => t,
+                None => continue,
+            };
+            match parts.next() {
+                Some("IN") => {},
+                _ => continue,
+            }
+            if let Some(payload) = parts.next() {
+                events.push((t_ms, payload.to_string()));
+            }
+        }
+        events.sort_by_key(|&(t, _)| t);
+        Ok(Player { events: events })
+    }
+
+    /// Number of recorded inbound events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// The `(t_ms, payload)` of the `i`th event in time order.
+    pub fn event(&self, i: usize) -> &(u64, String) {
+        &self.events[i]
+    }
+
+    /// Mission duration in milliseconds (timestamp of the last event).
+    pub fn duration_ms(&self) -> u64 {
+        self.events.last().map(|&(t, _)| t).unwrap_or(0)
+    }
+}
@@ -0,0 +1,158 @@
+//! Embedded HTTP telemetry/command endpoint.
+//!
+//! The only way to see the rover's state or stop it was to run the full
+//! OpenGL GUI on the controlling laptop. This spawns a tiny hand-rolled HTTP
+//! server on its own thread - no external crate, mirroring the scrape socket
+//! in `metrics.rs` - that answers `GET /telemetry` with the latest parsed
+//! values as JSON and accepts `POST /command` bodies forwarding blade and
+//! emergency-stop commands through the same UDP socket `BladeUi::send_blade`
+//! uses. A second laptop or a phone can then watch the rover and issue a stop
+//! without the GUI, and scripts get a plain HTTP hook for tests and logging.
+//!
+//! [`Telemetry`] is the `Arc<Mutex<_>>` shared with `BladeUi`: the render
+//! loop keeps it in sync with its own parsed fields and `self.blade`, and
+//! this module's handler reads it for `GET` and pokes the commanded blade
+//! value into it on `POST` so the two views never disagree for long.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Latest parsed telemetry and commanded blade position, refreshed by
+/// `BladeUi` and read by the `GET /telemetry` handler. Fields stay `None`
+/// until their channel has reported at least once.
+#[derive(Clone, Default)]
+pub struct Telemetry {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub velocity: Option<f64>,
+    pub roll: Option<f64>,
+    pub pitch: Option<f64>,
+    pub yaw: Option<f64>,
+    pub bus_millivolts: Option<f64>,
+    pub blade: f32,
+}
+
+impl Telemetry {
+    fn to_json(&self) -> String {
+        fn opt(v: Option<f64>) -> String {
+            match v {
+                Some(v) => format!("{}", v),
+                None => "null".to_string(),
+            }
+        }
+        format!("{{\"latitude\":{},\"longitude\":{},\"velocity\":{},\
+                  \"roll\":{},\"pitch\":{},\"yaw\":{},\
+                  \"bus_millivolts\":{},\"blade\":{}}}",
+                opt(self.latitude), opt(self.longitude), opt(self.velocity),
+                opt(self.roll), opt(self.pitch), opt(self.yaw),
+                opt(self.bus_millivolts), self.blade)
+    }
+}
+
+/// A command decoded from a `POST /command` body.
+enum Command {
+    Blade(f32),
+    EmergencyStop,
+}
+
+/// Spawn the endpoint. Binding is attempted once up front; failing to bind
+/// (port already in use) is logged and non-fatal, same as `blade_settings`
+/// falling back to defaults - the GUI still runs without remote control.
+pub fn serve(addr: String, shared: Arc<Mutex<Telemetry>>, socket: UdpSocket, rover_addr: (String, u16)) {
+    thread::Builder::new()
+        .name("http_telemetry".to_string())
+        .spawn(move || {
+            let listener = match TcpListener::bind(addr.as_str()) {
+                Ok(l) => l,
+                Err(e) => {
+                    println!("WARNING: could not bind HTTP telemetry socket {}: {}", addr, e);
+                    return;
+                },
+            };
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                handle_connection(stream, &shared, &socket, &rover_addr);
+            }
+        })
+        .unwrap();
+}
+
+/// Read one request, dispatch it, and write back a response. Each connection
+/// is handled serially and then closed - fine for the low, bursty request
+/// rate a monitoring client or test script generates.
+fn handle_connection(mut stream: TcpStream, shared: &Arc<Mutex<Telemetry>>,
+                     socket: &UdpSocket, rover_addr: &(String, u16)) {
+    let mut buf = [0u8; 2048];
+    let bytes_read = match stream.read(&mut buf) {
+        Ok(n) => n,
+        Err(_) => return,
+    };
+    let request = String::from_utf8_lossy(&buf[0..bytes_read]);
+
+    let mut request_lines = request.lines();
+    let mut request_parts = request_lines.next().unwrap_or("").split_whitespace();
+    let method = request_parts.next().unwrap_or("");
+    let path = request_parts.next().unwrap_or("");
+    let body = request.rsplit("\r\n\r\n").next().unwrap_or("");
+
+    let (status, content_type, payload) = match (method, path) {
+        ("GET", "/telemetry") => {
+            (200, "application/json", shared.lock().unwrap().to_json())
+        },
+        ("POST", "/command") => {
+            match parse_command(body) {
+                Some(cmd) => {
+                    apply_command(cmd, socket, rover_addr, shared);
+                    (200, "application/json", "{\"ok\":true}".to_string())
+                },
+                None => (400, "application/json", "{\"ok\":false,\"error\":\"bad command\"}".to_string()),
+            }
+        },
+        _ => (404, "text/plain", "not found".to_string()),
+    };
+
+    let status_line = match status {
+        200 => "200 OK",
+        400 => "400 Bad Request",
+        _ => "404 Not Found",
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_line, content_type, payload.len(), payload);
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// Parse a `key=value&key=value` form body into a single command. `stop=1`
+/// wins over a `blade` in the same body so a panicked client can't race its
+/// own stop.
+fn parse_command(body: &str) -> Option<Command> {
+    let mut blade = None;
+    for pair in body.trim().split('&') {
+        let mut kv = pair.splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some("stop"), _) => return Some(Command::EmergencyStop),
+            (Some("blade"), Some(v)) => blade = v.parse::<f32>().ok(),
+            _ => {},
+        }
+    }
+    blade.map(Command::Blade)
+}
+
+/// Forward `cmd` through `socket` exactly as `BladeUi::send_blade` would, and
+/// publish the new commanded position into `shared` so `GET /telemetry`
+/// reflects it immediately.
+fn apply_command(cmd: Command, socket: &UdpSocket, rover_addr: &(String, u16),
+                 shared: &Arc<Mutex<Telemetry>>) {
+    let blade = match cmd {
+        Command::Blade(v) => v,
+        Command::EmergencyStop => 0.0,
+    };
+    let packet = format!("F{}", blade as i32);
+    socket.send_to(packet.as_bytes(), (rover_addr.0.as_str(), rover_addr.1)).ok();
+    shared.lock().unwrap().blade = blade;
+}
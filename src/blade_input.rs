@@ -0,0 +1,124 @@
+//! Deadzone, smoothing and button debounce on top of the raw game
+//! controller. The left stick used to map straight onto blade position with
+//! no deadzone, so drift kept re-sending commands; buttons fired on every
+//! tick they read down, so a single press could double-trigger. This filters
+//! the axis and gates the buttons, with both driven by the remappable
+//! `InputBindings` table rather than hardcoded axis/button indices.
+
+use sdl2::controller;
+
+use blade_settings::InputBindings;
+
+/// One digital button's debounce state: consecutive down-ticks seen, and
+/// whether this press has already fired, so holding it down doesn't repeat.
+struct Debounce {
+    ticks: u32,
+    fired: bool,
+}
+
+impl Debounce {
+    fn new() -> Debounce {
+        Debounce { ticks: 0, fired: false }
+    }
+
+    fn update(&mut self, raw_down: bool, debounce_ticks: u32) -> bool {
+        if !raw_down {
+            self.ticks = 0;
+            self.fired = false;
+            return false;
+        }
+
+        self.ticks += 1;
+        if self.ticks >= debounce_ticks && !self.fired {
+            self.fired = true;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// This tick's filtered blade axis and edge-triggered button actions.
+pub struct Actions {
+    pub blade: f32,
+    pub mission_toggle: bool,
+    pub mission_reset: bool,
+    pub stop: bool,
+}
+
+/// Per-controller filter/debounce state, carried across ticks.
+pub struct InputState {
+    smoothed_blade: f32,
+    mission_toggle: Debounce,
+    mission_reset: Debounce,
+    stop: Debounce,
+}
+
+impl InputState {
+    pub fn new() -> InputState {
+        InputState {
+            smoothed_blade: 0.0,
+            mission_toggle: Debounce::new(),
+            mission_reset: Debounce::new(),
+            stop: Debounce::new(),
+        }
+    }
+
+    /// Read `controller` through `bindings` and return this tick's actions.
+    pub fn update(&mut self, controller: &controller::GameController, bindings: &InputBindings) -> Actions {
+        let raw = axis_value(controller, bindings.blade_axis.axis);
+        let raw = if bindings.blade_axis.invert { -raw } else { raw };
+        let gated = if raw.abs() < bindings.deadzone { 0.0 } else { raw };
+        self.smoothed_blade = self.smoothed_blade + bindings.smoothing_alpha * (gated - self.smoothed_blade);
+
+        Actions {
+            blade: self.smoothed_blade * 100.0,
+            mission_toggle: self.mission_toggle.update(
+                button_down(controller, bindings.mission_toggle_button), bindings.debounce_ticks),
+            mission_reset: self.mission_reset.update(
+                button_down(controller, bindings.mission_reset_button), bindings.debounce_ticks),
+            stop: self.stop.update(
+                button_down(controller, bindings.stop_button), bindings.debounce_ticks),
+        }
+    }
+}
+
+/// Read a stored axis index as a normalized `[-1.0, 1.0]` value.
+fn axis_value(controller: &controller::GameController, index: i32) -> f32 {
+    controller.get_axis(axis_from_index(index)).unwrap_or(0) as f32 / 32768.0
+}
+
+fn button_down(controller: &controller::GameController, index: i32) -> bool {
+    controller.get_button(button_from_index(index)).unwrap_or(false)
+}
+
+/// Map a stored axis index back to an SDL controller axis, defaulting to the
+/// right trigger for anything out of range.
+fn axis_from_index(index: i32) -> controller::Axis {
+    match index {
+        0 => controller::Axis::LeftX,
+        1 => controller::Axis::LeftY,
+        2 => controller::Axis::RightX,
+        3 => controller::Axis::RightY,
+        4 => controller::Axis::TriggerLeft,
+        _ => controller::Axis::TriggerRight,
+    }
+}
+
+/// Map a stored button index back to an SDL controller button, defaulting to
+/// `A` for anything out of range.
+fn button_from_index(index: i32) -> controller::Button {
+    match index {
+        0 => controller::Button::A,
+        1 => controller::Button::B,
+        2 => controller::Button::X,
+        3 => controller::Button::Y,
+        4 => controller::Button::Back,
+        6 => controller::Button::Start,
+        11 => controller::Button::DPadUp,
+        12 => controller::Button::DPadDown,
+        13 => controller::Button::DPadLeft,
+        14 => controller::Button::DPadRight,
+        _ => controller::Button::A,
+    }
+}
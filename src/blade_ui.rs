@@ -1,6 +1,9 @@
+use std::collections::VecDeque;
 use std::io;
 use std::net::UdpSocket;
 use std::ops::DerefMut;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::Sender;
 
 use conrod::{
     Background,
@@ -24,31 +27,327 @@ use opengl_graphics::glyph_cache::GlyphCache;
 use piston::input;
 use time;
 
+use blackbox::{Player, Recorder};
+use blade_http::Telemetry;
+use gauge::Gauge;
+use line_graph::LineGraph;
+use video_stream::{RecordMode, VideoMsg};
+
 enum MissionTime {
     Paused(time::Duration),
     Running(time::Tm, time::Duration),
 }
 
+// Labels blank while a field has never been reported, then flag it stale once
+// this long has passed since its last packet rather than silently going wrong.
+const STALE_TIMEOUT_MS: i64 = 2000;
+
+// Strip chart history depth. One sample is taken per `update()` tick, so at
+// the 20 updates per second the event loop runs this is 30 seconds of
+// scrollback, which is plenty to spot a trend during a run.
+const STRIP_CAPACITY: usize = 600;
+
 pub struct BladeUi {
     bg_color: Color,
-    
+
     mission_time: MissionTime,
-    
+
     pub blade: f32,
-    
+
+    // Telemetry parsed out of handle_packet, grouped the way it arrives on
+    // the wire: G:lat:lon, V:mps, I:roll:pitch:yaw, B:millivolts. Each group
+    // keeps its own last-seen time so one dead sensor doesn't mark the others
+    // stale.
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    gps_time: Option<time::Tm>,
+
+    velocity: Option<f64>,
+    velocity_time: Option<time::Tm>,
+
+    roll: Option<f64>,
+    pitch: Option<f64>,
+    yaw: Option<f64>,
+    imu_time: Option<time::Tm>,
+
+    bus_millivolts: Option<f64>,
+    bus_time: Option<time::Tm>,
+
+    // Strip-chart scrollback, keyed by mission-elapsed seconds so the axis
+    // keeps meaning across a Start/Pause cycle. Graph x/y intervals are
+    // resynced to the buffer's range each tick rather than relying on
+    // LineGraph::add_point's sequential-index windowing.
+    voltage_history: VecDeque<(f64, f64)>,
+    velocity_history: VecDeque<(f64, f64)>,
+    blade_history: VecDeque<(f64, f64)>,
+    voltage_graph: LineGraph,
+    velocity_graph: LineGraph,
+    blade_graph: LineGraph,
+
+    // Instrument panel: denser than the old flat IMU readout, one dial each
+    // for blade position, heading and bus voltage.
+    blade_gauge: Gauge,
+    heading_gauge: Gauge,
+    voltage_gauge: Gauge,
+
     socket: UdpSocket,
+    rover_addr: (String, u16),
+    blade_send_threshold: f32,
+
+    // Mission black box: `recorder` captures inbound/outbound traffic while
+    // the mission clock runs; `player`, when set, replays a recorded mission
+    // through `handle_packet` instead. In playback `transmit` is false so
+    // `send_blade` never touches the socket, and `playback_time`/
+    // `playback_speed`/`playback_cursor` drive the scrub.
+    mission_folder: String,
+    recorder: Option<Recorder>,
+    player: Option<Player>,
+    transmit: bool,
+    playback_time: f64,
+    playback_speed: f32,
+    playback_cursor: usize,
+
+    // Mission video recording: started/stopped alongside the black box by
+    // `toggle_mission`. `overlay_text` is refreshed with the mission-time
+    // string every `update()` tick so the video thread always burns in a
+    // current timestamp, whether or not a recording is actually running.
+    video_t: Sender<VideoMsg>,
+    overlay_text: Arc<Mutex<String>>,
+    recording: bool,
+
+    // Telemetry/command state shared with the embedded HTTP endpoint
+    // (`blade_http`), refreshed on every parsed packet and every tick so a
+    // remote client never reads more than one frame stale.
+    shared_telemetry: Arc<Mutex<Telemetry>>,
 }
 
 impl BladeUi {
-    pub fn new(socket: UdpSocket) -> BladeUi {
+    pub fn new(socket: UdpSocket, rover_ip: String, rover_port: u16, blade_send_threshold: f32,
+               mission_folder: String, video_t: Sender<VideoMsg>,
+               overlay_text: Arc<Mutex<String>>,
+               shared_telemetry: Arc<Mutex<Telemetry>>) -> BladeUi {
         BladeUi {
             bg_color: rgb(0.2, 0.35, 0.45),
-            
+
             mission_time: MissionTime::Paused(time::Duration::zero()),
-            
+
             blade: 0.0,
-            
+
+            latitude: None,
+            longitude: None,
+            gps_time: None,
+
+            velocity: None,
+            velocity_time: None,
+
+            roll: None,
+            pitch: None,
+            yaw: None,
+            imu_time: None,
+
+            bus_millivolts: None,
+            bus_time: None,
+
+            voltage_history: VecDeque::with_capacity(STRIP_CAPACITY),
+            velocity_history: VecDeque::with_capacity(STRIP_CAPACITY),
+            blade_history: VecDeque::with_capacity(STRIP_CAPACITY),
+            voltage_graph: LineGraph::new((200.0, 100.0), (0.0, 60.0), (0.0, 20.0),
+                                          vec![[0.2, 0.9, 0.3, 1.0]]),
+            velocity_graph: LineGraph::new((200.0, 100.0), (0.0, 60.0), (-1.0, 1.0),
+                                           vec![[0.9, 0.7, 0.2, 1.0]]),
+            blade_graph: LineGraph::new((200.0, 100.0), (0.0, 60.0), (-100.0, 100.0),
+                                        vec![[0.9, 0.3, 0.3, 1.0]]),
+
+            blade_gauge: Gauge::new((90.0, 90.0), -100.0, 100.0, "BLADE"),
+            heading_gauge: Gauge::new((90.0, 90.0), 0.0, 360.0, "HEADING"),
+            voltage_gauge: Gauge::new((90.0, 90.0), 0.0, 60.0, "BUS V"),
+
             socket: socket,
+            rover_addr: (rover_ip, rover_port),
+            blade_send_threshold: blade_send_threshold,
+
+            mission_folder: mission_folder,
+            recorder: None,
+            player: None,
+            transmit: true,
+            playback_time: 0.0,
+            playback_speed: 1.0,
+            playback_cursor: 0,
+
+            video_t: video_t,
+            overlay_text: overlay_text,
+            recording: false,
+
+            shared_telemetry: shared_telemetry,
+        }
+    }
+
+    /// Publish the fields the HTTP endpoint exposes into `shared_telemetry`.
+    /// Called after every parsed packet and every tick so a remote client
+    /// sees both fresh sensor values and the current commanded blade
+    /// position, whichever changed last.
+    fn sync_shared_telemetry(&self) {
+        let mut shared = self.shared_telemetry.lock().unwrap();
+        shared.latitude = self.latitude;
+        shared.longitude = self.longitude;
+        shared.velocity = self.velocity;
+        shared.roll = self.roll;
+        shared.pitch = self.pitch;
+        shared.yaw = self.yaw;
+        shared.bus_millivolts = self.bus_millivolts;
+        shared.blade = self.blade;
+    }
+
+    /// Load `mission_folder`'s black box and start replaying it through
+    /// `handle_packet` instead of driving live, e.g. after startup selects
+    /// playback mode. Recording is disabled for the rest of the run.
+    pub fn load_replay(&mut self, mission_folder: &str) {
+        match Player::load(mission_folder) {
+            Ok(player) => {
+                self.player = Some(player);
+                self.transmit = false;
+                self.recorder = None;
+                self.playback_time = 0.0;
+                self.playback_cursor = 0;
+            },
+            Err(e) => println!("WARNING: could not load black box {}: {}", mission_folder, e),
+        }
+    }
+
+    /// Advance the recorded timeline by `dt` seconds and feed any events whose
+    /// timestamp has come due back through `handle_packet`.
+    fn advance_replay(&mut self, dt: f64) {
+        self.playback_time += dt * 1000.0 * self.playback_speed.max(0.0) as f64;
+
+        let mut due = Vec::new();
+        if let Some(ref player) = self.player {
+            while self.playback_cursor < player.len() {
+                let &(t, ref payload) = player.event(self.playback_cursor);
+                if (t as f64) <= self.playback_time {
+                    due.push(payload.clone());
+                    self.playback_cursor += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        for payload in due {
+            self.handle_packet(payload);
+        }
+    }
+
+    /// Start the mission clock if paused, or pause it (keeping elapsed time)
+    /// if running. Shared by the Start/Pause button and its controller binding.
+    pub fn toggle_mission(&mut self) {
+        match self.mission_time {
+            MissionTime::Paused(current_time) => {
+                self.mission_time = MissionTime::Running(time::now(), current_time);
+
+                // Start the black box alongside the mission clock, unless
+                // we're replaying one.
+                if self.player.is_none() {
+                    match Recorder::open(&self.mission_folder) {
+                        Ok(recorder) => self.recorder = Some(recorder),
+                        Err(e) => println!("WARNING: could not open black box: {}", e),
+                    }
+
+                    // Record the video feed alongside it, one file per
+                    // mission, with the mission clock burned into the frames.
+                    let path = format!("mission_data/{}/blade.mp4", self.mission_folder);
+                    self.video_t.send(VideoMsg::Start(path, RecordMode::Single)).ok();
+                    self.recording = true;
+                }
+            },
+            MissionTime::Running(start_time, extra_time) => {
+                self.mission_time = MissionTime::Paused((time::now() - start_time) + extra_time);
+                self.recorder = None;
+
+                if self.recording {
+                    self.video_t.send(VideoMsg::Stop).ok();
+                    self.recording = false;
+                }
+            },
+        };
+    }
+
+    /// Zero the mission clock and pause it.
+    pub fn reset_mission(&mut self) {
+        self.mission_time = MissionTime::Paused(time::Duration::zero());
+    }
+
+    /// Zero the blade position and send it immediately, bypassing the
+    /// send threshold - the controller's emergency stop binding.
+    pub fn emergency_stop(&mut self) {
+        self.blade = 0.0;
+        self.send_blade().ok();
+    }
+
+    /// Time since the mission clock last started running, net of pauses -
+    /// the same value the mission time label computes, pulled out so the
+    /// strip charts can tag their samples with it too.
+    fn mission_elapsed(&self) -> time::Duration {
+        match self.mission_time {
+            MissionTime::Paused(t) => t,
+            MissionTime::Running(start_time, extra_time) =>
+                (time::now() - start_time) + extra_time,
+        }
+    }
+
+    /// Mission clock as `days:hours:minutes:seconds`, the same string shown
+    /// in the mission time label and burned into an in-progress recording.
+    fn mission_time_string(&self) -> String {
+        let mission_time = self.mission_elapsed();
+        let total_days = mission_time.num_days();
+        let total_hours = mission_time.num_hours();
+        let total_minutes = mission_time.num_minutes();
+        let total_seconds = mission_time.num_seconds();
+
+        let days = total_days;
+        let hours = total_hours - total_days * 24;
+        let minutes = total_minutes - total_hours * 60;
+        let seconds = total_seconds - total_minutes * 60;
+        format!("{}:{}:{}:{}", days, hours, minutes, seconds)
+    }
+
+    /// Sample the latest telemetry into the strip-chart ring buffers and
+    /// resync each LineGraph's axes and points to match. Call once per tick
+    /// from the event loop, independent of how often `draw_ui` runs. In
+    /// playback, this instead advances the recorded timeline.
+    pub fn update(&mut self, dt: f64) {
+        if self.player.is_some() {
+            self.advance_replay(dt);
+        }
+
+        // Keep the video thread's burned-in timecode current whether or not
+        // a recording is actually running.
+        *self.overlay_text.lock().unwrap() = self.mission_time_string();
+
+        // Publish the commanded blade position even between packets, so a
+        // remote client sees a controller- or keyboard-driven move promptly.
+        self.sync_shared_telemetry();
+
+        let t = self.mission_elapsed().num_milliseconds() as f64 / 1000.0;
+
+        if let Some(mv) = self.bus_millivolts {
+            push_sample(&mut self.voltage_history, (t, mv / 1000.0));
+        }
+        if let Some(v) = self.velocity {
+            push_sample(&mut self.velocity_history, (t, v));
+        }
+        push_sample(&mut self.blade_history, (t, self.blade as f64));
+
+        sync_graph(&mut self.voltage_graph, &self.voltage_history);
+        sync_graph(&mut self.velocity_graph, &self.velocity_history);
+        sync_graph(&mut self.blade_graph, &self.blade_history);
+    }
+
+    /// `""` once a field has reported, or `" (stale)"` once `STALE_TIMEOUT_MS`
+    /// has since passed with nothing newer.
+    fn staleness(last: Option<time::Tm>) -> &'static str {
+        match last {
+            Some(t) if (time::now() - t).num_milliseconds() <= STALE_TIMEOUT_MS => "",
+            Some(_) => " (stale)",
+            None => "",
         }
     }
     
@@ -75,22 +374,7 @@ impl BladeUi {
             .set(UTC_TIME, ui);
         
         // Mission time label
-        let mission_time =
-            match self.mission_time {
-                MissionTime::Paused(t) => t,
-                MissionTime::Running(start_time, extra_time) =>
-                    (time::now() - start_time) + extra_time
-            };
-        let total_days = mission_time.num_days();
-        let total_hours = mission_time.num_hours();
-        let total_minutes = mission_time.num_minutes();
-        let total_seconds = mission_time.num_seconds();
-        
-        let days = total_days;
-        let hours = total_hours - total_days*24;
-        let minutes = total_minutes - total_hours*60;
-        let seconds = total_seconds - total_minutes*60;
-        Label::new(format!("Mission Time: {}:{}:{}:{}", days, hours, minutes, seconds).as_str())
+        Label::new(format!("Mission Time: {}", self.mission_time_string()).as_str())
             .xy((-ui.win_w / 2.0) + 150.0, (ui.win_h / 2.0) - 70.0)
             .font_size(20)
             .color(self.bg_color.plain_contrast())
@@ -109,14 +393,7 @@ impl BladeUi {
             .frame(1.0)
             .label(mission_start_text)
             .react(|| {
-                match self.mission_time {
-                    MissionTime::Paused(current_time) => {
-                        self.mission_time = MissionTime::Running(time::now(), current_time);
-                    },
-                    MissionTime::Running(start_time, extra_time) => {
-                        self.mission_time = MissionTime::Paused((time::now() - start_time) + extra_time);
-                    },
-                };
+                self.toggle_mission();
             })
             .set(MISSION_START_BUTTON, ui);
         
@@ -128,10 +405,54 @@ impl BladeUi {
             .frame(1.0)
             .label("Reset")
             .react(|| {
-                self.mission_time = MissionTime::Paused(time::Duration::zero());
+                self.reset_mission();
             })
             .set(MISSION_RESET_BUTTON, ui);
-        
+
+        // Mission replay: load this mission's black box and scrub/speed its
+        // recorded timeline back through handle_packet.
+        let mission_folder = self.mission_folder.clone();
+        let replay_label = if self.player.is_some() { "Replaying" } else { "Replay" };
+        Button::new()
+            .dimensions(100.0, 30.0)
+            .xy((-ui.win_w / 2.0) + 265.0, (ui.win_h / 2.0) - 100.0)
+            .rgb(0.3, 0.6, 0.8)
+            .frame(1.0)
+            .label(replay_label)
+            .react(|| {
+                self.load_replay(&mission_folder);
+            })
+            .set(REPLAY_BUTTON, ui);
+
+        if self.player.is_some() {
+            Slider::new(self.playback_speed, 0.0, 8.0)
+                .dimensions(150.0, 20.0)
+                .xy((-ui.win_w / 2.0) + 120.0, (ui.win_h / 2.0) - 125.0)
+                .rgb(0.3, 0.6, 0.8)
+                .frame(1.0)
+                .label(format!("Speed x{:.1}", self.playback_speed).as_str())
+                .label_color(white())
+                .react(|speed| {
+                    self.playback_speed = speed;
+                })
+                .set(REPLAY_SPEED_SLIDER, ui);
+
+            let duration = self.player.as_ref().map(|p| p.duration_ms()).unwrap_or(0) as f32;
+            let playback_time = self.playback_time as f32;
+            Slider::new(playback_time, 0.0, duration.max(1.0))
+                .dimensions(300.0, 20.0)
+                .xy((-ui.win_w / 2.0) + 195.0, (ui.win_h / 2.0) - 150.0)
+                .rgb(0.3, 0.6, 0.8)
+                .frame(1.0)
+                .label(format!("{:.1}s / {:.1}s", playback_time / 1000.0, duration / 1000.0).as_str())
+                .label_color(white())
+                .react(|t| {
+                    self.playback_time = t as f64;
+                    self.playback_cursor = 0;
+                })
+                .set(REPLAY_SCRUB_SLIDER, ui);
+        }
+
         // Time delay
         Label::new("Time Delay: 0s")
             .xy((-ui.win_w / 2.0) + 70.0, (ui.win_h / 2.0) - 150.0)
@@ -139,36 +460,41 @@ impl BladeUi {
             .color(self.bg_color.plain_contrast())
             .set(TIME_DELAY, ui);
         
-        // IMU label
-        Label::new("IMU")
-            .xy((-ui.win_w / 2.0) + 100.0, (ui.win_h / 2.0) - 190.0)
-            .font_size(22)
-            .color(self.bg_color.plain_contrast())
-            .set(IMU_LABEL, ui);
-        
         // GPS label
         Label::new("GPS")
             .xy((-ui.win_w / 2.0) + 50.0, (ui.win_h / 2.0) - 400.0)
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(GPS_LABEL, ui);
-        
+
         // Longitude label
-        Label::new("19 43' 1\" N")
+        let longitude_text = match self.longitude {
+            Some(lon) => format!("{:.5} {}{}", lon.abs(), if lon >= 0.0 { "E" } else { "W" }, BladeUi::staleness(self.gps_time)),
+            None => "".to_string(),
+        };
+        Label::new(longitude_text.as_str())
             .xy((-ui.win_w / 2.0) + 50.0, (ui.win_h / 2.0) - 425.0)
             .font_size(16)
             .color(self.bg_color.plain_contrast())
             .set(LONGITUDE_LABEL, ui);
-        
+
         // Latitude label
-        Label::new("155 4' 1\" W")
+        let latitude_text = match self.latitude {
+            Some(lat) => format!("{:.5} {}{}", lat.abs(), if lat >= 0.0 { "N" } else { "S" }, BladeUi::staleness(self.gps_time)),
+            None => "".to_string(),
+        };
+        Label::new(latitude_text.as_str())
             .xy((-ui.win_w / 2.0) + 50.0, (ui.win_h / 2.0) - 445.0)
             .font_size(16)
             .color(self.bg_color.plain_contrast())
             .set(LATITUDE_LABEL, ui);
-        
+
         // Velocity label
-        Label::new("0.5 m/s")
+        let velocity_text = match self.velocity {
+            Some(v) => format!("{:.1} m/s{}", v, BladeUi::staleness(self.velocity_time)),
+            None => "".to_string(),
+        };
+        Label::new(velocity_text.as_str())
             .xy((-ui.win_w / 2.0) + 50.0, (ui.win_h / 2.0) - 465.0)
             .font_size(16)
             .color(self.bg_color.plain_contrast())
@@ -186,7 +512,25 @@ impl BladeUi {
                 self.try_update_blade(new_blade);
             })
             .set(SADL_SLIDER, ui);
-        
+
+        // Instrument panel: blade position, IMU heading and bus voltage as
+        // gauges in place of the old flat IMU text, drawn straight onto the
+        // glyph-cache Ui the same way as the strip charts below.
+        self.blade_gauge.draw(Some(self.blade),
+                               c.trans(5.0, 130.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+        let heading = if BladeUi::staleness(self.imu_time).is_empty() { self.yaw } else { None };
+        self.heading_gauge.draw(heading.map(|yaw| yaw as f32),
+                                 c.trans(105.0, 130.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+        self.voltage_gauge.draw(self.bus_millivolts.map(|mv| (mv / 1000.0) as f32),
+                                 c.trans(205.0, 130.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+
+        // Strip charts: bus voltage, velocity, blade position over mission
+        // time, drawn straight onto the glyph-cache Ui the same way nav.rs
+        // draws its voltage graph.
+        self.voltage_graph.draw(c.trans(5.0, 250.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+        self.velocity_graph.draw(c.trans(5.0, 360.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+        self.blade_graph.draw(c.trans(5.0, 470.0), gl, ui.glyph_cache.borrow_mut().deref_mut());
+
         // Left status RPM
         /*Label::new(self.l_rpm_status.as_str())
             .xy(110.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 60.0)
@@ -227,11 +571,60 @@ impl BladeUi {
     }
     
     pub fn handle_packet(&mut self, packet: String) {
+        // Append the raw packet to the black box before parsing so even one we
+        // can't decode is preserved for analysis.
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_inbound(&packet);
+        }
+
         let packet_parts: Vec<String> = packet.split(":").map(|s| s.to_string()).collect();
-        
+
         match packet_parts[0].as_str() {
+            "G" => {
+                // G:lat:lon
+                if packet_parts.len() == 3 {
+                    if let (Ok(lat), Ok(lon)) =
+                        (packet_parts[1].parse::<f64>(), packet_parts[2].parse::<f64>()) {
+                        self.latitude = Some(lat);
+                        self.longitude = Some(lon);
+                        self.gps_time = Some(time::now());
+                    }
+                }
+            },
+            "V" => {
+                // V:mps
+                if packet_parts.len() == 2 {
+                    if let Ok(mps) = packet_parts[1].parse::<f64>() {
+                        self.velocity = Some(mps);
+                        self.velocity_time = Some(time::now());
+                    }
+                }
+            },
+            "I" => {
+                // I:roll:pitch:yaw
+                if packet_parts.len() == 4 {
+                    if let (Ok(roll), Ok(pitch), Ok(yaw)) =
+                        (packet_parts[1].parse::<f64>(), packet_parts[2].parse::<f64>(), packet_parts[3].parse::<f64>()) {
+                        self.roll = Some(roll);
+                        self.pitch = Some(pitch);
+                        self.yaw = Some(yaw);
+                        self.imu_time = Some(time::now());
+                    }
+                }
+            },
+            "B" => {
+                // B:millivolts
+                if packet_parts.len() == 2 {
+                    if let Ok(mv) = packet_parts[1].parse::<f64>() {
+                        self.bus_millivolts = Some(mv);
+                        self.bus_time = Some(time::now());
+                    }
+                }
+            },
             _ => { println!("WARNING: Unknown packet ID: {}", packet_parts[0]) },
         }
+
+        self.sync_shared_telemetry();
     }
     
     pub fn on_key_pressed(&mut self, key: input::Key) {
@@ -247,29 +640,72 @@ impl BladeUi {
     }
     
     pub fn try_update_blade(&mut self, blade: f32) -> io::Result<usize> {
-        if (blade - self.blade).abs() > 1.0 || blade == -10.0 || blade == 10.0 {
+        if (blade - self.blade).abs() > self.blade_send_threshold || blade == -10.0 || blade == 10.0 {
             self.blade = blade;
             self.send_blade()
         } else {
             Ok(0)
         }
     }
-    
-    pub fn send_blade(&self) -> io::Result<usize> {
+
+    pub fn send_blade(&mut self) -> io::Result<usize> {
         let packet = format!("F{}", self.blade as i32);
-        self.socket.send_to(packet.as_bytes(), ("10.14.120.25", 30001))
+
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_outbound(packet.as_bytes(), &self.rover_addr);
+        }
+
+        if !self.transmit {
+            return Ok(0);
+        }
+        self.socket.send_to(packet.as_bytes(), (self.rover_addr.0.as_str(), self.rover_addr.1))
     }
 }
 
+// Push a sample into a ring buffer, evicting the oldest once it is full.
+fn push_sample(history: &mut VecDeque<(f64, f64)>, sample: (f64, f64)) {
+    if history.len() >= STRIP_CAPACITY {
+        history.pop_front();
+    }
+    history.push_back(sample);
+}
+
+// Resync a LineGraph's axes and points to a ring buffer: x spans the
+// buffered time range, y auto-scales to the buffered values with a little
+// headroom so a flat trace doesn't collapse onto the frame.
+fn sync_graph(graph: &mut LineGraph, history: &VecDeque<(f64, f64)>) {
+    if history.is_empty() {
+        return;
+    }
+
+    graph.x_interval = (history.front().unwrap().0, history.back().unwrap().0.max(history.front().unwrap().0 + 1.0));
+
+    let mut y_min = history[0].1;
+    let mut y_max = history[0].1;
+    for &(_, y) in history.iter() {
+        if y < y_min { y_min = y; }
+        if y > y_max { y_max = y; }
+    }
+    if (y_max - y_min).abs() < 1e-6 {
+        y_min -= 1.0;
+        y_max += 1.0;
+    }
+    graph.y_interval = (y_min, y_max);
+
+    graph.set_points(0, history.iter().cloned().collect());
+}
+
 // Widget IDs
 const LOCAL_TIME: WidgetId = 0;
 const UTC_TIME: WidgetId = LOCAL_TIME + 1;
 const MISSION_TIME_LABEL: WidgetId = UTC_TIME + 1;
 const MISSION_START_BUTTON: WidgetId = MISSION_TIME_LABEL + 1;
 const MISSION_RESET_BUTTON: WidgetId = MISSION_START_BUTTON + 1;
-const TIME_DELAY: WidgetId = MISSION_RESET_BUTTON + 1;
-const IMU_LABEL: WidgetId = TIME_DELAY + 1;
-const GPS_LABEL: WidgetId = IMU_LABEL + 1;
+const REPLAY_BUTTON: WidgetId = MISSION_RESET_BUTTON + 1;
+const REPLAY_SPEED_SLIDER: WidgetId = REPLAY_BUTTON + 1;
+const REPLAY_SCRUB_SLIDER: WidgetId = REPLAY_SPEED_SLIDER + 1;
+const TIME_DELAY: WidgetId = REPLAY_SCRUB_SLIDER + 1;
+const GPS_LABEL: WidgetId = TIME_DELAY + 1;
 const LONGITUDE_LABEL: WidgetId = GPS_LABEL + 1;
 const LATITUDE_LABEL: WidgetId = LONGITUDE_LABEL + 1;
 const VELOCITY_LABEL: WidgetId = LATITUDE_LABEL + 1;
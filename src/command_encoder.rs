@@ -0,0 +1,138 @@
+//! Pluggable encoding for outgoing control intents.
+//!
+//! `nav_ui`'s `send_*` methods used to hand-format every outgoing command as
+//! one of the crate's bespoke text packets (`A{rpm}|`, `H{l}|{r}|`, `C/D/E`)
+//! inline. `CommandEncoder` factors that formatting out so the same control
+//! intents can be serialized by a different backend - in particular
+//! `MavlinkEncoder`, which speaks to any MAVLink-speaking flight/rover
+//! controller instead of the legacy firmware - without touching the input
+//! handlers that decide *what* to send.
+
+/// The fixed set of control intents `NavigationUi` sends, independent of how
+/// the wire bytes are built.
+pub trait CommandEncoder {
+    fn set_left_rpm(&mut self, rpm: f32) -> Vec<u8>;
+    fn set_right_rpm(&mut self, rpm: f32) -> Vec<u8>;
+    fn set_lr_rpm(&mut self, l_rpm: f32, r_rpm: f32) -> Vec<u8>;
+    fn pan(&mut self, degrees: f32) -> Vec<u8>;
+    fn tilt(&mut self, degrees: f32) -> Vec<u8>;
+    fn brake(&mut self) -> Vec<u8>;
+    fn sadl(&mut self, position: f32) -> Vec<u8>;
+}
+
+/// The original text packets the legacy rover firmware expects.
+pub struct LegacyEncoder;
+
+impl CommandEncoder for LegacyEncoder {
+    fn set_left_rpm(&mut self, rpm: f32) -> Vec<u8> { format!("A{}|", rpm as i32).into_bytes() }
+    fn set_right_rpm(&mut self, rpm: f32) -> Vec<u8> { format!("B{}|", rpm as i32).into_bytes() }
+    fn set_lr_rpm(&mut self, l_rpm: f32, r_rpm: f32) -> Vec<u8> {
+        format!("H{}|{}|", l_rpm as i32, r_rpm as i32).into_bytes()
+    }
+    fn pan(&mut self, degrees: f32) -> Vec<u8> { format!("C{}|", degrees as i32).into_bytes() }
+    fn tilt(&mut self, degrees: f32) -> Vec<u8> { format!("D{}|", degrees as i32).into_bytes() }
+    fn brake(&mut self) -> Vec<u8> { vec![b'G'] }
+    fn sadl(&mut self, position: f32) -> Vec<u8> { format!("E{}|", position as i32).into_bytes() }
+}
+
+// Common-dialect message id for COMMAND_LONG, and its CRC_EXTRA seed - both
+// match the values a real MAVLink peer (e.g. PX4) expects, so this backend
+// can drive an unmodified flight/rover controller.
+const MSG_COMMAND_LONG: u8 = 76;
+const COMMAND_LONG_CRC_EXTRA: u8 = 152;
+
+// MAVLink reserves command ids 31000-31255 for user-defined commands; each
+// control intent gets one, carried in COMMAND_LONG's `command` field with the
+// setpoint(s) in param1/param2.
+const CMD_SET_LEFT_RPM: u16 = 31000;
+const CMD_SET_RIGHT_RPM: u16 = 31001;
+const CMD_SET_LR_RPM: u16 = 31002;
+const CMD_PAN: u16 = 31003;
+const CMD_TILT: u16 = 31004;
+const CMD_BRAKE: u16 = 31005;
+const CMD_SADL: u16 = 31006;
+
+/// MAVLink v1 backend: each control intent goes out as a `COMMAND_LONG`
+/// (msgid 76) frame with the standard little-endian header (magic, len, seq,
+/// sysid, compid, msgid), the command id and setpoint(s) in the payload, and
+/// a CRC-16/MCRF4XX checksum seeded with the message's `CRC_EXTRA` byte - the
+/// same frame layout `mavlink.rs` parses on the ingestion side.
+pub struct MavlinkEncoder {
+    seq: u8,
+    sysid: u8,
+    compid: u8,
+}
+
+impl MavlinkEncoder {
+    pub fn new() -> MavlinkEncoder {
+        // sysid 255 / compid 0 is the conventional ground-station identity.
+        MavlinkEncoder { seq: 0, sysid: 255, compid: 0 }
+    }
+
+    fn command_long(&mut self, command: u16, param1: f32, param2: f32) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(33);
+        push_f32(&mut payload, param1);
+        push_f32(&mut payload, param2);
+        for _ in 0..5 { push_f32(&mut payload, 0.0); } // param3..7, unused
+        payload.push((command & 0xFF) as u8);
+        payload.push((command >> 8) as u8);
+        payload.push(1); // target_system
+        payload.push(1); // target_component
+        payload.push(0); // confirmation
+        self.frame(MSG_COMMAND_LONG, payload, COMMAND_LONG_CRC_EXTRA)
+    }
+
+    fn frame(&mut self, msgid: u8, payload: Vec<u8>, crc_extra: u8) -> Vec<u8> {
+        let seq = self.seq;
+        self.seq = self.seq.wrapping_add(1);
+
+        let mut frame = Vec::with_capacity(6 + payload.len() + 2);
+        frame.push(0xFE);
+        frame.push(payload.len() as u8);
+        frame.push(seq);
+        frame.push(self.sysid);
+        frame.push(self.compid);
+        frame.push(msgid);
+        frame.extend_from_slice(&payload);
+
+        let crc = mavlink_crc(&frame[1..], crc_extra);
+        frame.push((crc & 0xFF) as u8);
+        frame.push((crc >> 8) as u8);
+        frame
+    }
+}
+
+impl CommandEncoder for MavlinkEncoder {
+    fn set_left_rpm(&mut self, rpm: f32) -> Vec<u8> { self.command_long(CMD_SET_LEFT_RPM, rpm, 0.0) }
+    fn set_right_rpm(&mut self, rpm: f32) -> Vec<u8> { self.command_long(CMD_SET_RIGHT_RPM, rpm, 0.0) }
+    fn set_lr_rpm(&mut self, l_rpm: f32, r_rpm: f32) -> Vec<u8> { self.command_long(CMD_SET_LR_RPM, l_rpm, r_rpm) }
+    fn pan(&mut self, degrees: f32) -> Vec<u8> { self.command_long(CMD_PAN, degrees, 0.0) }
+    fn tilt(&mut self, degrees: f32) -> Vec<u8> { self.command_long(CMD_TILT, degrees, 0.0) }
+    fn brake(&mut self) -> Vec<u8> { self.command_long(CMD_BRAKE, 0.0, 0.0) }
+    fn sadl(&mut self, position: f32) -> Vec<u8> { self.command_long(CMD_SADL, position, 0.0) }
+}
+
+fn push_f32(out: &mut Vec<u8>, v: f32) {
+    let bits = v.to_bits();
+    out.push((bits & 0xFF) as u8);
+    out.push(((bits >> 8) & 0xFF) as u8);
+    out.push(((bits >> 16) & 0xFF) as u8);
+    out.push(((bits >> 24) & 0xFF) as u8);
+}
+
+// MAVLink's checksum: the ITU X.25/CRC-16-CCITT variant known as
+// CRC-16/MCRF4XX, accumulated over the header (minus the magic byte) and
+// payload, then over the message's CRC_EXTRA byte.
+fn crc_accumulate(byte: u8, crc: u16) -> u16 {
+    let tmp = byte ^ (crc as u8);
+    let tmp = tmp ^ (tmp << 4);
+    (crc >> 8) ^ ((tmp as u16) << 8) ^ ((tmp as u16) << 3) ^ ((tmp as u16) >> 4)
+}
+
+fn mavlink_crc(data: &[u8], crc_extra: u8) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &b in data {
+        crc = crc_accumulate(b, crc);
+    }
+    crc_accumulate(crc_extra, crc)
+}
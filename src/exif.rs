@@ -0,0 +1,163 @@
+//! Embed mission metadata into a baseline JPEG snapshot.
+//!
+//! The operator's saved stills are more useful after the fact if they carry
+//! where the camera was pointing and where the rover was when the frame was
+//! grabbed. After the `image` crate writes a baseline JPEG we splice an EXIF
+//! `APP1` segment (time stamp plus GPS position) in right behind the `SOI`
+//! marker, and tuck the remaining rover-specific fields (pan/tilt and the
+//! fused roll/heading) into a `JPEG_COM` comment as a JSON blob, since EXIF
+//! has no natural home for them.
+
+use time;
+
+/// Everything worth recording alongside a captured frame.
+pub struct SnapshotMeta {
+    pub captured: time::Tm,
+    pub pan: f32,
+    pub tilt: f32,
+    /// Fused roll/heading in degrees, if the IMU has produced a solution.
+    pub roll: Option<f64>,
+    pub heading: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Return a new JPEG byte stream carrying `meta`, given a baseline `jpeg`.
+///
+/// The input must start with an `SOI` (`FF D8`) marker; if it does not we hand
+/// the bytes back untouched rather than produce a malformed file.
+pub fn embed(jpeg: &[u8], meta: &SnapshotMeta) -> Vec<u8> {
+    if jpeg.len() < 2 || jpeg[0] != 0xFF || jpeg[1] != 0xD8 {
+        return jpeg.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(jpeg.len() + 256);
+    out.extend_from_slice(&jpeg[0..2]); // SOI
+    push_segment(&mut out, 0xE1, &app1_exif(meta));
+    push_segment(&mut out, 0xFE, comment_json(meta).as_bytes());
+    out.extend_from_slice(&jpeg[2..]);
+    out
+}
+
+/// Append a `FF <marker>` segment whose length field covers `body`.
+fn push_segment(out: &mut Vec<u8>, marker: u8, body: &[u8]) {
+    let len = (body.len() + 2) as u16; // length field counts itself
+    out.push(0xFF);
+    out.push(marker);
+    out.push((len >> 8) as u8);
+    out.push((len & 0xFF) as u8);
+    out.extend_from_slice(body);
+}
+
+/// Build the `Exif\0\0` + little-endian TIFF payload for the `APP1` segment.
+///
+/// IFD0 carries `DateTime` and a pointer to a GPS IFD holding the latitude and
+/// longitude as the usual degree/minute/second rationals.
+fn app1_exif(meta: &SnapshotMeta) -> Vec<u8> {
+    let mut app1 = Vec::new();
+    app1.extend_from_slice(b"Exif\0\0");
+
+    // TIFF header: little-endian, magic 42, IFD0 at offset 8.
+    let tiff_start = app1.len();
+    app1.extend_from_slice(b"II");
+    push_u16(&mut app1, 0x002A);
+    push_u32(&mut app1, 8);
+
+    let datetime = format!("{}", meta.captured.strftime("%Y:%m:%d %H:%M:%S").unwrap());
+    let mut datetime = datetime.into_bytes();
+    datetime.push(0);
+
+    // Layout, as offsets from the TIFF header:
+    //   8   IFD0 (2 entries)                   -> ends at 8 + 2 + 2*12 + 4 = 38
+    //   38  GPS IFD (4 entries)                -> ends at 38 + 2 + 4*12 + 4 = 92
+    //   92  DateTime string
+    //   ..  GPS latitude/longitude rationals
+    let gps_ifd = 38u32;
+    let mut data_off = 92u32;
+    let datetime_off = data_off;
+    data_off += datetime.len() as u32;
+    let lat_off = data_off;
+    data_off += 24;
+    let lon_off = data_off;
+
+    // IFD0
+    push_u16(&mut app1, 2);
+    push_entry(&mut app1, 0x0132, 2, datetime.len() as u32, datetime_off); // DateTime
+    push_entry(&mut app1, 0x8825, 4, 1, gps_ifd); // GPSInfoIFDPointer
+    push_u32(&mut app1, 0); // no IFD1
+
+    // GPS IFD
+    let (lat_ref, lat) = meta.latitude.map_or((b'N', 0.0), |v| (if v >= 0.0 { b'N' } else { b'S' }, v.abs()));
+    let (lon_ref, lon) = meta.longitude.map_or((b'E', 0.0), |v| (if v >= 0.0 { b'E' } else { b'W' }, v.abs()));
+    push_u16(&mut app1, 4);
+    push_ascii_ref(&mut app1, 0x0001, lat_ref); // GPSLatitudeRef
+    push_entry(&mut app1, 0x0002, 5, 3, lat_off); // GPSLatitude
+    push_ascii_ref(&mut app1, 0x0003, lon_ref); // GPSLongitudeRef
+    push_entry(&mut app1, 0x0004, 5, 3, lon_off); // GPSLongitude
+    push_u32(&mut app1, 0);
+
+    // Data area
+    app1.extend_from_slice(&datetime);
+    push_dms(&mut app1, lat);
+    push_dms(&mut app1, lon);
+
+    debug_assert_eq!((app1.len() - tiff_start) as u32, data_off + 24);
+    app1
+}
+
+/// A degree value as the three `RATIONAL` pairs EXIF expects (deg, min, sec).
+fn push_dms(out: &mut Vec<u8>, value: f64) {
+    let deg = value.floor();
+    let min = ((value - deg) * 60.0).floor();
+    let sec = (value - deg - min / 60.0) * 3600.0;
+    push_u32(out, deg as u32);
+    push_u32(out, 1);
+    push_u32(out, min as u32);
+    push_u32(out, 1);
+    push_u32(out, (sec * 1000.0) as u32);
+    push_u32(out, 1000);
+}
+
+/// A two-byte ASCII tag (e.g. a GPS ref) stored inline in the value field.
+fn push_ascii_ref(out: &mut Vec<u8>, tag: u16, ch: u8) {
+    push_u16(out, tag);
+    push_u16(out, 2); // ASCII
+    push_u32(out, 2); // count (char + NUL)
+    out.push(ch);
+    out.push(0);
+    out.push(0);
+    out.push(0);
+}
+
+fn push_entry(out: &mut Vec<u8>, tag: u16, ty: u16, count: u32, value: u32) {
+    push_u16(out, tag);
+    push_u16(out, ty);
+    push_u32(out, count);
+    push_u32(out, value);
+}
+
+fn push_u16(out: &mut Vec<u8>, v: u16) {
+    out.push((v & 0xFF) as u8);
+    out.push((v >> 8) as u8);
+}
+
+fn push_u32(out: &mut Vec<u8>, v: u32) {
+    out.push((v & 0xFF) as u8);
+    out.push(((v >> 8) & 0xFF) as u8);
+    out.push(((v >> 16) & 0xFF) as u8);
+    out.push(((v >> 24) & 0xFF) as u8);
+}
+
+/// The JSON fallback stuffed into the comment marker.
+fn comment_json(meta: &SnapshotMeta) -> String {
+    let opt = |v: Option<f64>| v.map_or("null".to_string(), |x| format!("{:.4}", x));
+    format!(
+        "{{\"utc\":\"{}\",\"local\":\"{}\",\"pan\":{:.1},\"tilt\":{:.1},\
+         \"roll\":{},\"heading\":{},\"lat\":{},\"lon\":{}}}",
+        meta.captured.to_utc().strftime("%Y-%m-%dT%H:%M:%SZ").unwrap(),
+        meta.captured.strftime("%Y-%m-%dT%H:%M:%S").unwrap(),
+        meta.pan, meta.tilt,
+        opt(meta.roll), opt(meta.heading),
+        opt(meta.latitude), opt(meta.longitude),
+    )
+}
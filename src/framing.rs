@@ -0,0 +1,140 @@
+//! Reliable framing for the outbound command queue.
+//!
+//! The plain queue fires every datagram once and forgets it, which is fine for
+//! camera pan spam but dangerous for a motor-stop or brake that the rover never
+//! hears over a lossy Wi-Fi link. Borrowing the CRC-protected framing PX4 uses
+//! on its I2C/serial buses, every queued packet is wrapped as
+//!
+//! ```text
+//! [seq] [payload...] [crc8]
+//! ```
+//!
+//! where `seq` is a monotonically increasing one-byte sequence number and
+//! `crc8` is a CRC-8 (polynomial `0x07`, init `0x00`) over `seq` and the
+//! payload. Outstanding frames are held until the rover echoes `ACK<seq>`; any
+//! still unacked after the retransmit timeout are resent, up to a per-frame
+//! retry budget, before being surfaced to the operator as a lost command.
+//! Safety-critical frames (brake, zero-RPM stop) retransmit on a tighter
+//! timeout and give up sooner so a failure is reported fast.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Retransmit timeout for an ordinary unacked frame.
+const RTO_MS: u64 = 200;
+/// Tighter retransmit timeout for a safety-critical frame.
+const SAFETY_RTO_MS: u64 = 100;
+/// Retries before an ordinary frame is declared lost.
+const MAX_RETRIES: u32 = 8;
+/// Retries before a safety-critical frame is declared lost (reported sooner).
+const SAFETY_MAX_RETRIES: u32 = 4;
+
+/// CRC-8 with polynomial `0x07` and init `0x00`, matching the PX4 bus framing.
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            if crc & 0x80 != 0 {
+                crc = (crc << 1) ^ 0x07;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+    crc
+}
+
+/// A framed command awaiting its `ACK`.
+struct InFlight {
+    frame: Vec<u8>,
+    addr: (String, u16),
+    last_sent: Instant,
+    tries: u32,
+    rto: Duration,
+    max_tries: u32,
+}
+
+/// Sequence-number allocator and in-flight table for the reliable layer.
+pub struct ReliableTracker {
+    next_seq: u8,
+    in_flight: HashMap<u8, InFlight>,
+}
+
+impl ReliableTracker {
+    pub fn new() -> ReliableTracker {
+        ReliableTracker {
+            next_seq: 0,
+            in_flight: HashMap::new(),
+        }
+    }
+
+    /// Allocate the next sequence number, wrapping at 256.
+    pub fn next_seq(&mut self) -> u8 {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        seq
+    }
+
+    /// Record a framed packet as outstanding. `frame` is the fully assembled
+    /// wire bytes (seq, payload, crc and any trailing terminator) so a resend
+    /// is byte-identical to the first transmission. `safety` selects the
+    /// tighter timeout and retry budget.
+    pub fn register(&mut self, seq: u8, frame: Vec<u8>, addr: (String, u16), safety: bool) {
+        let (rto, max_tries) = if safety {
+            (Duration::from_millis(SAFETY_RTO_MS), SAFETY_MAX_RETRIES)
+        } else {
+            (Duration::from_millis(RTO_MS), MAX_RETRIES)
+        };
+        self.in_flight.insert(seq, InFlight {
+            frame: frame,
+            addr: addr,
+            last_sent: Instant::now(),
+            tries: 1,
+            rto: rto,
+            max_tries: max_tries,
+        });
+    }
+
+    /// Drop a frame the rover has acknowledged.
+    pub fn ack(&mut self, seq: u8) {
+        self.in_flight.remove(&seq);
+    }
+
+    /// Advance the retransmit clock. Returns the frames to put back on the wire
+    /// this tick, and the sequence numbers that have now exhausted their retry
+    /// budget (removed from the table so the caller can report them lost).
+    pub fn tick(&mut self, now: Instant) -> (Vec<(Vec<u8>, (String, u16))>, Vec<u8>) {
+        let mut resend = vec![];
+        let mut lost = vec![];
+
+        for (&seq, cmd) in self.in_flight.iter_mut() {
+            if now.duration_since(cmd.last_sent) < cmd.rto {
+                continue;
+            }
+            // `tries` counts the first send, so this allows `max_tries` resends.
+            if cmd.tries > cmd.max_tries {
+                lost.push(seq);
+                continue;
+            }
+            resend.push((cmd.frame.clone(), cmd.addr.clone()));
+            cmd.last_sent = now;
+            cmd.tries += 1;
+        }
+
+        for seq in &lost {
+            self.in_flight.remove(seq);
+        }
+        (resend, lost)
+    }
+}
+
+/// Parse an `ACK<seq>` datagram (the four bytes `A C K <seq>`), returning the
+/// acknowledged sequence number. Anything else is telemetry and returns `None`.
+pub fn parse_ack(buf: &[u8]) -> Option<u8> {
+    if buf.len() == 4 && &buf[0..3] == b"ACK" {
+        Some(buf[3])
+    } else {
+        None
+    }
+}
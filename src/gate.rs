@@ -0,0 +1,46 @@
+use time::{self, Duration, Tm};
+
+/// Sanity gate for a single telemetry channel. It rejects readings that fall
+/// outside the channel's physical bounds or that jump more than a configured
+/// delta away from the current filtered value in one interval, and it records
+/// when the last good sample arrived so the UI can fall back to "NO DATA" when
+/// a sensor goes quiet.
+pub struct Gate {
+    min: f64,
+    max: f64,
+    max_delta: f64,
+    last_accepted: Option<Tm>,
+}
+
+impl Gate {
+    /// Gate accepting values in `[min, max]` that move at most `max_delta` from
+    /// the previous filtered value between samples.
+    pub fn new(min: f64, max: f64, max_delta: f64) -> Gate {
+        Gate { min: min, max: max, max_delta: max_delta, last_accepted: None }
+    }
+
+    /// Decide whether `value` is a plausible update. `reference` is the current
+    /// filtered value for the channel, or `None` before the first sample. On
+    /// acceptance the arrival time is stamped.
+    pub fn accept(&mut self, value: f64, reference: Option<f64>) -> bool {
+        if value < self.min || value > self.max {
+            return false;
+        }
+        if let Some(r) = reference {
+            if (value - r).abs() > self.max_delta {
+                return false;
+            }
+        }
+        self.last_accepted = Some(time::now());
+        true
+    }
+
+    /// True when no sample has been accepted within `timeout`, including before
+    /// the first one ever arrives.
+    pub fn is_stale(&self, timeout: Duration) -> bool {
+        match self.last_accepted {
+            Some(t) => time::now() - t > timeout,
+            None => true,
+        }
+    }
+}
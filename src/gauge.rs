@@ -0,0 +1,97 @@
+//! Reusable circular instrument: a needle over an arc spanning `[min, max]`,
+//! with a colored warning zone near each limit. Drawn the same way as
+//! `LineGraph` and `AttitudeIndicator` - raw graphics primitives straight into
+//! the `draw_ui` pass, not a conrod widget - so it drops into a dashboard
+//! alongside the existing strip charts with no extra plumbing. Denser than a
+//! `Label` and a linear `Slider` for an at-a-glance read of blade position,
+//! heading or bus voltage.
+
+use graphics::{Context, Graphics};
+use graphics::character::CharacterCache;
+
+// Sweep geometry: 0 degrees points straight up, positive rotates clockwise.
+// A 240-degree sweep centered on top leaves a 120-degree gap at the bottom,
+// the usual analog-gauge layout.
+const START_DEG: f64 = -120.0;
+const SWEEP_DEG: f64 = 240.0;
+
+/// Fraction of the range, from each end, painted in the warning color.
+const WARN_FRACTION: f32 = 0.15;
+
+pub struct Gauge {
+    pub size: (f64, f64),
+    pub min: f32,
+    pub max: f32,
+    pub label: &'static str,
+}
+
+impl Gauge {
+    pub fn new(size: (f64, f64), min: f32, max: f32, label: &'static str) -> Gauge {
+        Gauge { size: size, min: min, max: max, label: label }
+    }
+
+    /// Draw the dial at `value`, or a caged needle and `"--"` readout if the
+    /// channel has never reported.
+    pub fn draw<G: Graphics, C>(&self, value: Option<f32>,
+                                c: Context, g: &mut G, character_cache: &mut C)
+                                where C: CharacterCache<Texture=G::Texture> {
+        use graphics::*;
+
+        let (w, h) = self.size;
+        let (cx, cy) = (w / 2.0, h / 2.0);
+        let radius = (w.min(h) / 2.0) - 4.0;
+
+        // Face.
+        Ellipse::new([0.1, 0.1, 0.15, 1.0])
+            .draw([cx - radius, cy - radius, radius * 2.0, radius * 2.0],
+                  &c.draw_state, c.transform, g);
+
+        // Arc ticks every 10% of range, red within `WARN_FRACTION` of either
+        // end so a value nearing its limit stands out before it gets there.
+        let steps = 10;
+        for step in 0..(steps + 1) {
+            let frac = step as f64 / steps as f64;
+            let in_warn = frac <= WARN_FRACTION as f64 || frac >= 1.0 - WARN_FRACTION as f64;
+            let color = if in_warn { [0.9, 0.2, 0.2, 1.0] } else { [0.75, 0.75, 0.75, 1.0] };
+            let (dx, dy) = angle_vector(START_DEG + frac * SWEEP_DEG);
+            Line::new(color, 2.0)
+                .draw([cx + dx * (radius - 8.0), cy + dy * (radius - 8.0),
+                       cx + dx * radius, cy + dy * radius],
+                      &c.draw_state, c.transform, g);
+        }
+
+        // Needle. An unreported channel points dead center rather than
+        // resting at the bottom of the range, which would read as a real
+        // (and alarming) minimum value.
+        let frac = match value {
+            Some(v) => (((v - self.min) / (self.max - self.min)) as f64).max(0.0).min(1.0),
+            None => 0.5,
+        };
+        let (dx, dy) = angle_vector(START_DEG + frac * SWEEP_DEG);
+        let needle_color = if value.is_some() { [1.0, 0.85, 0.1, 1.0] } else { [0.4, 0.4, 0.4, 1.0] };
+        Line::new(needle_color, 2.0)
+            .draw([cx, cy, cx + dx * (radius - 12.0), cy + dy * (radius - 12.0)],
+                  &c.draw_state, c.transform, g);
+
+        // Label, above the dial.
+        let label_c = c.trans(cx - (self.label.len() as f64 * 3.0), cy - radius - 4.0);
+        Text::new_color([1.0, 1.0, 1.0, 1.0], 12)
+            .draw(self.label, character_cache, &label_c.draw_state, label_c.transform, g);
+
+        // Numeric readout, centered under the needle pivot.
+        let value_text = match value {
+            Some(v) => format!("{:.0}", v),
+            None => "--".to_string(),
+        };
+        let value_c = c.trans(cx - (value_text.len() as f64 * 3.5), cy + radius + 12.0);
+        Text::new_color([1.0, 1.0, 1.0, 1.0], 14)
+            .draw(value_text.as_str(), character_cache, &value_c.draw_state, value_c.transform, g);
+    }
+}
+
+/// Unit displacement for a needle/tick at `deg`, measured clockwise from
+/// straight up.
+fn angle_vector(deg: f64) -> (f64, f64) {
+    let theta = deg.to_radians();
+    (theta.sin(), -theta.cos())
+}
@@ -0,0 +1,90 @@
+//! WGS84 ground-track geodesy.
+//!
+//! Adopting paparazzi's move to a genuine lat/lon representation, this tracks
+//! the rover as a sequence of fixes and derives N/S-E/W hemisphere labels,
+//! great-circle segment distance (haversine), initial bearing, and a running
+//! total path length. Segments shorter than a threshold are ignored so a
+//! stationary rover jittering on GPS noise doesn't inflate the odometry.
+
+/// Mean Earth radius, metres.
+const EARTH_RADIUS_M: f64 = 6371000.0;
+
+/// Ignore segments shorter than this (metres) when accumulating distance.
+const MIN_SEGMENT_M: f64 = 1.0;
+
+/// Accumulated ground-track statistics over a stream of WGS84 fixes.
+pub struct GroundTrack {
+    last: Option<(f64, f64)>,
+    total_distance: f64,
+    bearing: Option<f64>,
+    min_segment: f64,
+}
+
+impl GroundTrack {
+    pub fn new() -> GroundTrack {
+        GroundTrack {
+            last: None,
+            total_distance: 0.0,
+            bearing: None,
+            min_segment: MIN_SEGMENT_M,
+        }
+    }
+
+    /// Feed a new fix. Segments shorter than the threshold update neither the
+    /// total distance nor the bearing, so a parked rover stays put.
+    pub fn add_fix(&mut self, lat: f64, lon: f64) {
+        if let Some((plat, plon)) = self.last {
+            let d = haversine(plat, plon, lat, lon);
+            if d >= self.min_segment {
+                self.total_distance += d;
+                self.bearing = Some(initial_bearing(plat, plon, lat, lon));
+                self.last = Some((lat, lon));
+            }
+        } else {
+            self.last = Some((lat, lon));
+        }
+    }
+
+    /// Total path length accumulated so far, metres.
+    pub fn total_distance(&self) -> f64 {
+        self.total_distance
+    }
+
+    /// Initial bearing of the most recent counted segment, degrees in `[0, 360)`.
+    pub fn bearing(&self) -> Option<f64> {
+        self.bearing
+    }
+}
+
+/// Great-circle distance between two fixes in metres (haversine formula).
+pub fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    EARTH_RADIUS_M * 2.0 * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Initial bearing from fix 1 to fix 2, degrees in `[0, 360)`.
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Format a latitude with a hemisphere suffix from its sign.
+pub fn format_lat(lat: f64) -> String {
+    format!("{:.5} {}", lat.abs(), if lat >= 0.0 { "N" } else { "S" })
+}
+
+/// Format a longitude with a hemisphere suffix from its sign.
+pub fn format_lon(lon: f64) -> String {
+    format!("{:.5} {}", lon.abs(), if lon >= 0.0 { "E" } else { "W" })
+}
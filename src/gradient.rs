@@ -0,0 +1,64 @@
+//! Scalar-to-colour gradient mapping.
+//!
+//! Telemetry used to flip between a flat green and a flat red, which hides how
+//! close a value is to its limit. A `Gradient` holds ascending `(value,
+//! colour)` stops and interpolates between them, so a reading shades smoothly
+//! green -> amber -> red as it approaches an out-of-range band and the
+//! operator sees trouble coming without reading the digits.
+
+use std::cmp::Ordering;
+
+use conrod::Color;
+use conrod::color::rgba;
+
+/// An ascending list of `(value, colour)` stops.
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Build a gradient from its stops; they are sorted ascending by value so
+    /// callers can list them in any order.
+    pub fn new(stops: Vec<(f32, Color)>) -> Gradient {
+        let mut stops = stops;
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Gradient { stops: stops }
+    }
+
+    /// Map `input` to an interpolated colour. Scan the stops for the first
+    /// whose value exceeds `input`: if that is the first stop clamp to its
+    /// colour, if none exceed clamp to the last, otherwise lerp between the
+    /// bracketing neighbours.
+    pub fn sample(&self, input: f32) -> Color {
+        if self.stops.is_empty() {
+            return rgba(1.0, 1.0, 1.0, 1.0);
+        }
+
+        let upper = self.stops.iter().position(|&(v, _)| v > input);
+        match upper {
+            None => self.stops[self.stops.len() - 1].1,
+            Some(0) => self.stops[0].1,
+            Some(i) => {
+                let (lv, lc) = self.stops[i - 1];
+                let (rv, rc) = self.stops[i];
+                let a = if rv > lv { (input - lv) / (rv - lv) } else { 0.0 };
+                lerp(lc, rc, a)
+            }
+        }
+    }
+
+    /// Like `sample`, but as a raw RGBA array for the `graphics` gauges.
+    pub fn sample_rgba(&self, input: f32) -> [f32; 4] {
+        self.sample(input).to_fsa()
+    }
+}
+
+/// Component-wise `left*(1-a) + right*a`.
+fn lerp(left: Color, right: Color, a: f32) -> Color {
+    let l = left.to_fsa();
+    let r = right.to_fsa();
+    rgba(l[0] * (1.0 - a) + r[0] * a,
+         l[1] * (1.0 - a) + r[1] * a,
+         l[2] * (1.0 - a) + r[2] * a,
+         l[3] * (1.0 - a) + r[3] * a)
+}
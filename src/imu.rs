@@ -1,4 +1,87 @@
 use graphics::{Context, Graphics};
+use time;
+
+// Complementary attitude filter
+//
+// The raw IMU gives an accel/mag reference that is stable but noisy and a gyro
+// that is smooth but drifts. A complementary filter fuses them: the gyro is
+// integrated for the high-frequency response and slowly pulled back toward the
+// accel/mag solution for the low-frequency reference. All angles are degrees.
+
+/// Weight given to the integrated-gyro term; the remainder tracks accel/mag.
+const ALPHA: f64 = 0.98;
+
+/// Persistent pitch/roll/heading estimate with the timestamp of the last packet.
+pub struct ComplementaryFilter {
+    estimate: Option<(f64, f64, f64)>,
+    last_time: Option<time::Tm>,
+}
+
+impl ComplementaryFilter {
+    pub fn new() -> ComplementaryFilter {
+        ComplementaryFilter { estimate: None, last_time: None }
+    }
+
+    /// Fuse one IMU sample. `accel`/`gyro`/`mag` are the raw `(x, y, z)` triples
+    /// as they arrive on the wire; `gyro` is in degrees per second. Returns the
+    /// fused `(pitch, roll, heading)` in degrees.
+    pub fn update(&mut self, accel: (f64, f64, f64), gyro: (f64, f64, f64),
+                  mag: (f64, f64, f64)) -> (f64, f64, f64) {
+        let (pa, ra, ha) = reference(accel, mag);
+
+        let now = time::now();
+        let dt = match self.last_time {
+            Some(last) => (now - last).num_milliseconds() as f64 / 1000.0,
+            None => 0.0,
+        };
+        self.last_time = Some(now);
+
+        let (gx, gy, gz) = gyro;
+        let fused = match self.estimate {
+            // First sample: no dt yet, so seed straight from the reference.
+            None => (pa, ra, ha),
+            Some((pp, rp, hp)) => {
+                let roll = ALPHA * (rp + gx * dt) + (1.0 - ALPHA) * ra;
+                let pitch = ALPHA * (pp + gy * dt) + (1.0 - ALPHA) * pa;
+                let heading = blend_heading(hp + gz * dt, ha);
+                (pitch, roll, heading)
+            },
+        };
+        self.estimate = Some(fused);
+        fused
+    }
+}
+
+/// The accel-only pitch/roll and tilt-compensated magnetic heading, in degrees,
+/// following the historical axis convention of the telemetry UI.
+fn reference(accel: (f64, f64, f64), mag: (f64, f64, f64)) -> (f64, f64, f64) {
+    let (ax, ay, az) = (accel.1, -accel.2, accel.0);
+    let (mx, my, mz) = (mag.1, -mag.2, mag.0);
+
+    let roll = f64::atan2(ay, az);
+    let pitch = f64::atan2(-ax, ay * f64::sin(roll) + az * f64::cos(roll));
+    let heading = f64::atan2(mz * f64::sin(roll) - my * f64::cos(roll),
+                             mx * f64::cos(pitch) + my * f64::sin(pitch) * f64::sin(roll)
+                                 + mz * f64::sin(pitch) * f64::cos(roll));
+
+    let mut heading = heading.to_degrees();
+    let mut roll = roll.to_degrees() + 180.0;
+    let pitch = pitch.to_degrees();
+    if heading < 0.0 { heading += 360.0; }
+    if roll >= 180.0 { roll -= 360.0; }
+    heading = 360.0 - heading;
+    (pitch, roll, heading)
+}
+
+/// Blend a gyro-propagated heading toward the reference on the shortest arc so
+/// the filter does not lurch across the 0/360 boundary. Result in `[0, 360)`.
+fn blend_heading(gyro_heading: f64, reference: f64) -> f64 {
+    let mut diff = (reference - gyro_heading) % 360.0;
+    if diff > 180.0 { diff -= 360.0; }
+    if diff < -180.0 { diff += 360.0; }
+    let blended = gyro_heading + (1.0 - ALPHA) * diff;
+    ((blended % 360.0) + 360.0) % 360.0
+}
 
 // Roll
 
@@ -13,9 +96,9 @@ impl Roll {
         }
     }
     
-    pub fn draw<G: Graphics>(&self, c: Context, g: &mut G) {
+    pub fn draw<G: Graphics>(&self, c: Context, g: &mut G, pointer_color: [f32; 4]) {
         use graphics::*;
-        
+
         // Draw background rectangle
         Rectangle::new([0.3, 0.3, 1.0, 1.0])
             .draw([0.0, 0.0, 120.0, 120.0],
@@ -28,11 +111,11 @@ impl Roll {
                   &c.draw_state, c.transform,
                   g);
 
-        // Draw rotator line
+        // Draw rotator line - reddens as the gauge nears a tip-over angle.
         {
             let c = c.trans(60.0, 60.0); // Center the pointer in the circle
             let c = c.rot_deg(self.angle);
-            Line::new([1.0, 0.0, 0.0, 1.0], 1.0)
+            Line::new(pointer_color, 1.0)
                 .draw([-60.0, 0.0, 60.0, 0.0],
                       &c.draw_state, c.transform,
                       g);
@@ -42,6 +125,11 @@ impl Roll {
     pub fn set_angle(&mut self, angle: f64) {
         self.angle = angle;
     }
+
+    /// The current roll angle in degrees, for drivers like the video OSD.
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
 }
 
 // Heading
@@ -86,4 +174,9 @@ impl Heading {
     pub fn set_angle(&mut self, angle: f64) {
         self.angle = angle;
     }
+
+    /// The current heading in degrees, for drivers like the video OSD.
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
 }
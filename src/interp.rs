@@ -0,0 +1,176 @@
+//! Motion-compensated frame interpolation for the low-frame-rate feed.
+//!
+//! The cameras arrive pinned near 10 fps, so even though the GUI redraws at
+//! 60 Hz the video looks choppy. This synthesizes intermediate frames between
+//! two consecutive decoded frames `a` and `b`: a coarse block-based optical
+//! flow maps pixels from `a` to `b`, and for a phase `t` in `(0, 1)` we
+//! forward-warp `a` along `t * flow` and `b` along `-(1 - t) * flow`, then
+//! blend the two warped results weighted by `(1 - t)` and `t`. Holes left by
+//! disocclusion are filled from whichever source landed there.
+//!
+//! On a scene cut (a flow field with a large residual) we give up on warping
+//! and just show `b`, since ghosting looks far worse than a single hard step.
+
+use image::RgbaImage;
+
+/// Side length of a flow block, in pixels.
+const BLOCK: u32 = 16;
+/// Half-width of the block-matching search window, in pixels.
+const SEARCH: i32 = 12;
+/// Mean per-pixel block residual above which we treat the pair as a scene cut.
+const SCENE_CUT_RESIDUAL: u32 = 48;
+
+/// A per-block motion vector plus the pair's overall matching residual.
+pub struct Flow {
+    width: u32,
+    height: u32,
+    cols: u32,
+    rows: u32,
+    vectors: Vec<(i32, i32)>,
+    /// Mean per-pixel residual of the best matches; high means scene cut.
+    residual: u32,
+}
+
+impl Flow {
+    /// Estimate block motion from `a` to `b`; both must share dimensions.
+    pub fn estimate(a: &RgbaImage, b: &RgbaImage) -> Flow {
+        let (width, height) = a.dimensions();
+        let cols = (width + BLOCK - 1) / BLOCK;
+        let rows = (height + BLOCK - 1) / BLOCK;
+        let mut vectors = Vec::with_capacity((cols * rows) as usize);
+        let mut total_residual: u64 = 0;
+
+        for by in 0..rows {
+            for bx in 0..cols {
+                let ox = bx * BLOCK;
+                let oy = by * BLOCK;
+                let mut best = (0i32, 0i32);
+                let mut best_cost = u32::max_value();
+                for dy in -SEARCH..SEARCH + 1 {
+                    for dx in -SEARCH..SEARCH + 1 {
+                        let cost = block_sad(a, b, ox, oy, dx, dy, best_cost);
+                        if cost < best_cost {
+                            best_cost = cost;
+                            best = (dx, dy);
+                        }
+                    }
+                }
+                vectors.push(best);
+                // Normalize by block area so the scene-cut threshold is a
+                // per-pixel quantity independent of BLOCK.
+                total_residual += (best_cost / (BLOCK * BLOCK)) as u64;
+            }
+        }
+
+        let residual = (total_residual / (cols * rows) as u64) as u32;
+        Flow { width: width, height: height, cols: cols, rows: rows, vectors: vectors, residual: residual }
+    }
+
+    /// Whether this pair looks like a cut rather than continuous motion.
+    pub fn is_scene_cut(&self) -> bool {
+        self.residual >= SCENE_CUT_RESIDUAL
+    }
+
+    /// Flow vector covering pixel `(x, y)`.
+    fn at(&self, x: u32, y: u32) -> (i32, i32) {
+        let bx = (x / BLOCK).min(self.cols - 1);
+        let by = (y / BLOCK).min(self.rows - 1);
+        self.vectors[(by * self.cols + bx) as usize]
+    }
+}
+
+/// Build the interpolated frame at phase `t` between `a` and `b`.
+///
+/// Returns a clone of `b` unchanged when the flow indicates a scene cut.
+pub fn interpolate(a: &RgbaImage, b: &RgbaImage, flow: &Flow, t: f32) -> RgbaImage {
+    if flow.is_scene_cut() {
+        return b.clone();
+    }
+
+    let (width, height) = (flow.width, flow.height);
+    let mut out = RgbaImage::new(width, height);
+    // Track which output pixels a source actually reached, so we can fill the
+    // disocclusion holes afterwards from the other source.
+    let mut have_a = vec![false; (width * height) as usize];
+    let mut have_b = vec![false; (width * height) as usize];
+    let mut warped_a = RgbaImage::new(width, height);
+    let mut warped_b = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let (fx, fy) = flow.at(x, y);
+            // a advances toward b by t; b retreats toward a by (1 - t).
+            let ax = clamp(x as i32 + (fx as f32 * t) as i32, width);
+            let ay = clamp(y as i32 + (fy as f32 * t) as i32, height);
+            splat(&mut warped_a, &mut have_a, ax, ay, a.get_pixel(x, y).data, width);
+
+            let bx = clamp(x as i32 - (fx as f32 * (1.0 - t)) as i32, width);
+            let by = clamp(y as i32 - (fy as f32 * (1.0 - t)) as i32, height);
+            splat(&mut warped_b, &mut have_b, bx, by, b.get_pixel(x, y).data, width);
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = (y * width + x) as usize;
+            let pa = warped_a.get_pixel(x, y).data;
+            let pb = warped_b.get_pixel(x, y).data;
+            let pixel = match (have_a[idx], have_b[idx]) {
+                (true, true) => blend(pa, pb, t),
+                (true, false) => pa,
+                (false, true) => pb,
+                // Hole in both warps: fall back to the plain cross-dissolve.
+                (false, false) => blend(a.get_pixel(x, y).data, b.get_pixel(x, y).data, t),
+            };
+            out.put_pixel(x, y, image::Rgba { data: pixel });
+        }
+    }
+
+    out
+}
+
+/// Sum of absolute R, G, B and A differences for a block shifted by `(dx, dy)`,
+/// abandoning early once the running cost passes `ceiling`.
+fn block_sad(a: &RgbaImage, b: &RgbaImage, ox: u32, oy: u32, dx: i32, dy: i32, ceiling: u32) -> u32 {
+    let (width, height) = a.dimensions();
+    let mut cost = 0u32;
+    for y in 0..BLOCK {
+        let ay = oy + y;
+        if ay >= height { break; }
+        let by = ay as i32 + dy;
+        if by < 0 || by >= height as i32 { return u32::max_value(); }
+        for x in 0..BLOCK {
+            let ax = ox + x;
+            if ax >= width { break; }
+            let bx = ax as i32 + dx;
+            if bx < 0 || bx >= width as i32 { return u32::max_value(); }
+            let pa = a.get_pixel(ax, ay).data;
+            let pb = b.get_pixel(bx as u32, by as u32).data;
+            for c in 0..4 {
+                cost += (pa[c] as i32 - pb[c] as i32).abs() as u32;
+            }
+        }
+        if cost >= ceiling { return cost; }
+    }
+    cost
+}
+
+/// Write a pixel into a warp buffer and mark its slot as covered.
+fn splat(dst: &mut RgbaImage, have: &mut [bool], x: u32, y: u32, px: [u8; 4], width: u32) {
+    dst.put_pixel(x, y, image::Rgba { data: px });
+    have[(y * width + x) as usize] = true;
+}
+
+/// Linear cross-dissolve of two RGBA pixels, weighted by `(1 - t)` and `t`.
+fn blend(pa: [u8; 4], pb: [u8; 4], t: f32) -> [u8; 4] {
+    let mut out = [0u8; 4];
+    for c in 0..4 {
+        out[c] = (pa[c] as f32 * (1.0 - t) + pb[c] as f32 * t) as u8;
+    }
+    out
+}
+
+/// Clamp a warped coordinate to `[0, extent)`.
+fn clamp(v: i32, extent: u32) -> u32 {
+    if v < 0 { 0 } else if v >= extent as i32 { extent - 1 } else { v as u32 }
+}
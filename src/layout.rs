@@ -0,0 +1,179 @@
+//! Resolution-independent video/widget layout.
+//!
+//! The render code used to hardcode the 1280x700 startup size, magic offsets
+//! like `1280.0 - 700.0 - 5.0`, and a fixed `scale(700/450, 400/450)` that
+//! stretched the camera frames. This computes every video rectangle from the
+//! live window size instead, letterboxes each feed to its source aspect rather
+//! than distorting it, and offers a few selectable arrangements. The swap
+//! hit-boxes are read back from the same `Layout` so click-to-swap stays
+//! correct at any resolution or mode.
+
+/// How widget coordinates tuned for the reference design size are mapped onto
+/// the live window, borrowed from the re3 frontend's sprite-scaling option.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ScalePolicy {
+    /// Independent x/y scaling - fills the window but changes aspect ratio.
+    Stretch,
+    /// Uniform scaling by the smaller axis - keeps aspect, letterboxing the
+    /// rest.
+    Letterbox,
+    /// No scaling - design pixels are kept 1:1, centered.
+    PixelExact,
+}
+
+impl ScalePolicy {
+    /// Cycle to the next policy, for a runtime toggle.
+    pub fn next(self) -> ScalePolicy {
+        match self {
+            ScalePolicy::Stretch => ScalePolicy::Letterbox,
+            ScalePolicy::Letterbox => ScalePolicy::PixelExact,
+            ScalePolicy::PixelExact => ScalePolicy::Stretch,
+        }
+    }
+
+    /// Short human-readable name for the toggle button.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            ScalePolicy::Stretch => "Stretch",
+            ScalePolicy::Letterbox => "Letterbox",
+            ScalePolicy::PixelExact => "1:1",
+        }
+    }
+}
+
+/// Per-axis scale factors mapping the reference design space onto the live
+/// window under a chosen [`ScalePolicy`]. Widget coordinates are authored in
+/// the centered reference space (origin at screen center, `REF_W`x`REF_H`) and
+/// routed through `x`/`y`/`w`/`h` so they track any resolution.
+#[derive(Copy, Clone)]
+pub struct UiScale {
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+impl UiScale {
+    /// Reference design size the hardcoded offsets were tuned against.
+    pub const REF_W: f64 = 1280.0;
+    pub const REF_H: f64 = 1024.0;
+
+    /// Derive the scale factors for a `win_w` x `win_h` window under `policy`.
+    pub fn compute(win_w: f64, win_h: f64, policy: ScalePolicy) -> UiScale {
+        let sx = win_w / UiScale::REF_W;
+        let sy = win_h / UiScale::REF_H;
+        match policy {
+            ScalePolicy::Stretch => UiScale { scale_x: sx, scale_y: sy },
+            ScalePolicy::Letterbox => {
+                let s = sx.min(sy);
+                UiScale { scale_x: s, scale_y: s }
+            },
+            ScalePolicy::PixelExact => UiScale { scale_x: 1.0, scale_y: 1.0 },
+        }
+    }
+
+    /// Map a reference-space x coordinate (or width) onto the window.
+    pub fn x(&self, v: f64) -> f64 { v * self.scale_x }
+    /// Map a reference-space y coordinate (or height) onto the window.
+    pub fn y(&self, v: f64) -> f64 { v * self.scale_y }
+    /// Map a reference-space width onto the window.
+    pub fn w(&self, v: f64) -> f64 { v * self.scale_x }
+    /// Map a reference-space height onto the window.
+    pub fn h(&self, v: f64) -> f64 { v * self.scale_y }
+}
+
+/// A rectangle in window pixels.
+#[derive(Copy, Clone)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Rect {
+    /// Whether the point `(px, py)` falls inside the rectangle.
+    pub fn contains(&self, px: f64, py: f64) -> bool {
+        px >= self.x && px <= self.x + self.w && py >= self.y && py <= self.y + self.h
+    }
+
+    /// The largest sub-rectangle of `self` with aspect `src_w:src_h`, centered
+    /// so a non-matching source is pillar-/letter-boxed instead of stretched.
+    pub fn letterbox(&self, src_w: f64, src_h: f64) -> Rect {
+        if src_w <= 0.0 || src_h <= 0.0 {
+            return *self;
+        }
+        let scale = (self.w / src_w).min(self.h / src_h);
+        let w = src_w * scale;
+        let h = src_h * scale;
+        Rect { x: self.x + (self.w - w) / 2.0, y: self.y + (self.h - h) / 2.0, w: w, h: h }
+    }
+}
+
+/// How the three camera feeds are arranged on screen.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum DisplayMode {
+    /// One large main feed with the other two stacked beneath it (the classic
+    /// arrangement).
+    SideBySide,
+    /// Only the main feed, filling the whole video region.
+    Single,
+    /// Main feed full-size with the secondary feeds as small corner insets.
+    Pip,
+}
+
+impl DisplayMode {
+    /// Cycle to the next mode, for a runtime toggle key.
+    pub fn next(self) -> DisplayMode {
+        match self {
+            DisplayMode::SideBySide => DisplayMode::Single,
+            DisplayMode::Single => DisplayMode::Pip,
+            DisplayMode::Pip => DisplayMode::SideBySide,
+        }
+    }
+}
+
+/// Computed slot rectangles for the current window and mode. `main` holds the
+/// primary feed; `secondary` holds the hit-boxes for the other feeds, index
+/// `i` swapping with `vid_displays[i + 1]`.
+pub struct Layout {
+    pub main: Rect,
+    pub secondary: Vec<Rect>,
+}
+
+impl Layout {
+    /// Lay out the video region for a `win_w` x `win_h` window. The gauges keep
+    /// the left edge; video takes the right ~55%, matching the original split
+    /// but scaling with the window.
+    pub fn compute(win_w: f64, win_h: f64, mode: DisplayMode) -> Layout {
+        let margin = win_w * 0.004;
+        let region_w = win_w * 0.55;
+        let region = Rect {
+            x: win_w - region_w - margin,
+            y: margin,
+            w: region_w,
+            h: win_h - 2.0 * margin,
+        };
+
+        match mode {
+            DisplayMode::Single => Layout { main: region, secondary: vec![] },
+            DisplayMode::Pip => {
+                // Two stacked insets in the bottom-right corner of the region.
+                let inset_w = region.w * 0.28;
+                let inset_h = inset_w * 9.0 / 16.0;
+                let x = region.x + region.w - inset_w - margin;
+                let s0 = Rect { x: x, y: region.y + region.h - 2.0 * inset_h - 2.0 * margin, w: inset_w, h: inset_h };
+                let s1 = Rect { x: x, y: region.y + region.h - inset_h - margin, w: inset_w, h: inset_h };
+                Layout { main: region, secondary: vec![s0, s1] }
+            },
+            DisplayMode::SideBySide => {
+                // Main on top, two equal feeds beneath.
+                let bottom_h = region.h * 0.34;
+                let main = Rect { x: region.x, y: region.y, w: region.w, h: region.h - bottom_h - margin };
+                let sub_w = (region.w - margin) / 2.0;
+                let sub_y = region.y + region.h - bottom_h;
+                let s0 = Rect { x: region.x, y: sub_y, w: sub_w, h: bottom_h };
+                let s1 = Rect { x: region.x + sub_w + margin, y: sub_y, w: sub_w, h: bottom_h };
+                Layout { main: main, secondary: vec![s0, s1] }
+            },
+        }
+    }
+}
@@ -98,4 +98,31 @@ impl LineGraph {
     pub fn num_points(&self, line_index: usize) -> usize {
         self.lines[line_index].points.len()
     }
+
+    /// Replace a line's entire point buffer outright, e.g. when the caller
+    /// keeps its own ring buffer and wants the graph to mirror it exactly
+    /// rather than relying on `add_point`'s count-based windowing (which
+    /// assumes sequential-index x values, not a time axis).
+    pub fn set_points(&mut self, line_index: usize, points: Vec<(f64, f64)>) {
+        self.lines[line_index].points = points;
+    }
+
+    /// Retune the upper bound of the y axis, keeping accumulated points. Used
+    /// when the graph scale is reloaded live from the parameter file.
+    pub fn set_y_max(&mut self, y_max: f64) {
+        self.y_interval.1 = y_max;
+    }
+
+    /// Recolour a line, e.g. to track an alarm band. Out-of-range indices are
+    /// ignored.
+    pub fn set_color(&mut self, line_index: usize, color: [f32; 4]) {
+        if let Some(line) = self.lines.get_mut(line_index) {
+            line.color = color;
+        }
+    }
+
+    /// The buffered series as `(color, points)` pairs, for offline plotting.
+    pub fn series(&self) -> Vec<([f32; 4], &Vec<(f64, f64)>)> {
+        self.lines.iter().map(|l| (l.color, &l.points)).collect()
+    }
 }
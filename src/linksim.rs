@@ -0,0 +1,145 @@
+//! Degraded-link simulator for the outbound command queue.
+//!
+//! The ground station used to model the downlink as a single fixed delay: every
+//! queued packet waited `delay` and then went out untouched, in order. Real
+//! rover links - long-range radio constrained by transmission distance - are
+//! not that tidy. They jitter, drop packets, and saturate. This adds a channel
+//! model on top of the existing queue so operators can rehearse teleoperation
+//! under the conditions they will actually fly in:
+//!
+//! * **Jitter** - each packet's release time is spread by a random offset
+//!   around the base delay, so packets can become eligible out of the order
+//!   they were queued (the queue flush reorders accordingly).
+//! * **Loss** - a per-packet probability that a datagram never reaches the
+//!   socket.
+//! * **Bandwidth** - a byte-per-second token bucket that holds packets back
+//!   when the simulated channel is saturated.
+//!
+//! All three are `0`/`0`/off by default, which reproduces the original
+//! clean-delay behaviour exactly.
+
+use std::collections::VecDeque;
+use time;
+
+/// Width of the rolling window used for the throughput readout.
+const THROUGHPUT_WINDOW_MS: i64 = 1000;
+
+/// A tiny xorshift PRNG. `rand` is not a dependency of this crate, and the
+/// channel model only needs cheap, non-cryptographic noise.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // Avoid the all-zero state, which xorshift cannot leave.
+        Rng { state: if seed == 0 { 0x9e3779b97f4a7c15 } else { seed } }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// A uniform sample in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Tunable channel impairments plus the accounting needed to enforce the
+/// bandwidth cap and report effective throughput.
+pub struct LinkSim {
+    /// Per-packet drop probability, in percent.
+    pub loss_pct: f64,
+    /// Peak magnitude of the random release-time offset, in milliseconds.
+    pub jitter_ms: f64,
+    /// Channel capacity in bytes per second; `0` means unlimited.
+    pub bandwidth_bps: f64,
+
+    rng: Rng,
+    /// Bytes still available to send this refill period (token bucket).
+    tokens: f64,
+    last_refill: time::Tm,
+    /// `(sent_at, bytes)` over the last second, for the throughput readout.
+    sent_window: VecDeque<(time::Tm, usize)>,
+}
+
+impl LinkSim {
+    /// A pristine channel: no loss, no jitter, unlimited bandwidth.
+    pub fn new() -> LinkSim {
+        LinkSim {
+            loss_pct: 0.0,
+            jitter_ms: 0.0,
+            bandwidth_bps: 0.0,
+            rng: Rng::new(time::now().to_timespec().nsec as u64),
+            tokens: 0.0,
+            last_refill: time::now(),
+            sent_window: VecDeque::new(),
+        }
+    }
+
+    /// A random release-time offset to add to a packet's base delay, in the
+    /// range `[-jitter_ms, +jitter_ms]`. Returned as a `Duration` ready to add
+    /// to the base; the offset is not clamped, so the sum can be negative.
+    pub fn jitter(&mut self) -> time::Duration {
+        if self.jitter_ms <= 0.0 {
+            return time::Duration::zero();
+        }
+        let offset = (self.rng.unit() * 2.0 - 1.0) * self.jitter_ms;
+        time::Duration::milliseconds(offset as i64)
+    }
+
+    /// Roll the per-packet loss probability.
+    pub fn drops(&mut self) -> bool {
+        self.loss_pct > 0.0 && self.rng.unit() * 100.0 < self.loss_pct
+    }
+
+    /// Refill the token bucket for the time elapsed since the last call. Called
+    /// once per flush before deciding how many packets fit.
+    fn refill(&mut self) {
+        let now = time::now();
+        if self.bandwidth_bps <= 0.0 {
+            self.last_refill = now;
+            return;
+        }
+        let dt = (now - self.last_refill).num_milliseconds() as f64 / 1000.0;
+        self.last_refill = now;
+        self.tokens = (self.tokens + dt * self.bandwidth_bps).min(self.bandwidth_bps);
+    }
+
+    /// Prepare for a flush pass: refill the bucket and expire the throughput
+    /// window.
+    pub fn begin_flush(&mut self) {
+        self.refill();
+        let cutoff = time::now() - time::Duration::milliseconds(THROUGHPUT_WINDOW_MS);
+        while self.sent_window.front().map_or(false, |&(t, _)| t < cutoff) {
+            self.sent_window.pop_front();
+        }
+    }
+
+    /// Whether a `len`-byte packet fits in the remaining channel capacity this
+    /// flush. With the cap disabled this is always true.
+    pub fn can_send(&self, len: usize) -> bool {
+        self.bandwidth_bps <= 0.0 || self.tokens >= len as f64
+    }
+
+    /// Account for a packet that just went out: spend its bytes from the bucket
+    /// and add them to the throughput window.
+    pub fn on_sent(&mut self, len: usize) {
+        if self.bandwidth_bps > 0.0 {
+            self.tokens -= len as f64;
+        }
+        self.sent_window.push_back((time::now(), len));
+    }
+
+    /// Effective throughput over the last second, in bytes per second.
+    pub fn throughput_bps(&self) -> f64 {
+        let sent: usize = self.sent_window.iter().map(|&(_, n)| n).sum();
+        sent as f64 * 1000.0 / THROUGHPUT_WINDOW_MS as f64
+    }
+}
@@ -0,0 +1,63 @@
+//! Convert a binary mission log (see `sdlog`) into one CSV file per message
+//! type. Each DATA record is reconstructed from the FORMAT carried in-band, so
+//! this tool needs no compiled-in knowledge of the field layout.
+//!
+//! Usage: `logconv <log.bin> [out_dir]` (out_dir defaults to the log's folder).
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+mod sdlog;
+
+fn main() {
+    let mut args = env::args();
+    let prog = args.next().unwrap_or_else(|| "logconv".to_string());
+    let log_path = match args.next() {
+        Some(p) => p,
+        None => {
+            println!("usage: {} <log.bin> [out_dir]", prog);
+            return;
+        },
+    };
+    let out_dir = args.next().unwrap_or_else(|| {
+        Path::new(&log_path).parent()
+            .map(|p| p.to_string_lossy().into_owned())
+            .and_then(|p| if p.is_empty() { None } else { Some(p) })
+            .unwrap_or_else(|| ".".to_string())
+    });
+
+    let (formats, records) = sdlog::read_log(&log_path);
+
+    // One CSV writer per message type, opened lazily with its header row.
+    let mut writers: HashMap<u8, BufWriter<File>> = HashMap::new();
+    for rec in &records {
+        let fmt = match formats.get(&rec.id) {
+            Some(f) => f,
+            None => continue, // DATA with no matching FORMAT - skip
+        };
+
+        if !writers.contains_key(&rec.id) {
+            let path = format!("{}/{}.csv", out_dir, fmt.name);
+            let mut w = BufWriter::new(File::create(&path).unwrap());
+            let header = fmt.fields.iter().map(|f| f.label.as_str())
+                                   .collect::<Vec<_>>().join(",");
+            writeln!(&mut w, "{}", header).unwrap();
+            writers.insert(rec.id, w);
+        }
+
+        let mut offset = 0;
+        let mut cells = Vec::with_capacity(fmt.fields.len());
+        for field in &fmt.fields {
+            let (text, consumed) = sdlog::decode_field(field.ty, &rec.payload, offset);
+            offset += consumed;
+            cells.push(text);
+        }
+        let w = writers.get_mut(&rec.id).unwrap();
+        writeln!(w, "{}", cells.join(",")).unwrap();
+    }
+
+    println!("wrote {} CSV file(s) to {}", writers.len(), out_dir);
+}
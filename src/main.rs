@@ -1,5 +1,7 @@
-#![feature(convert)]
+#![feature(convert, custom_derive, plugin)]
+#![plugin(serde_macros)]
 
+use std::collections::HashMap;
 use std::net::UdpSocket;
 use std::sync::mpsc::channel;
 use std::thread;
@@ -7,6 +9,12 @@ use std::thread;
 use sdl2::controller;
 
 extern crate sdl2;
+extern crate serde;
+
+mod avg_val;
+use avg_val::AvgVal;
+mod settings;
+use settings::{ControllerSettings, ControllerFamily, BindSlot, SETTINGS_PATH};
 extern crate piston;
 extern crate conrod;
 extern crate graphics;
@@ -49,32 +57,187 @@ struct RoverUi {
     max_rpm: f32,
     l_rpm_status: String,
     r_rpm_status: String,
-    
+    // Rolling averages of the numeric RPM telemetry, so the displayed
+    // feedback is smoothed rather than a noisy instantaneous reading, plus
+    // the time since the last valid packet for a staleness indicator.
+    l_rpm_avg: AvgVal,
+    r_rpm_avg: AvgVal,
+    time_since_rpm: f64,
+
     // Forward camera controls
     f_pan: f32,
     f_tilt: f32,
-    
+
+    // Analog stick conditioning: a deadzone to kill drift and a per-side
+    // moving average to smooth mechanical noise before driving the motors.
+    deadzone: f32,
+    smoothing_window: usize,
+    l_axis_avg: AvgVal,
+    r_axis_avg: AvgVal,
+
+    // Haptic feedback queued this frame, applied to the active pad in the
+    // update block so the driver can feel rover state without looking away.
+    pending_rumble: Option<Rumble>,
+
+    // Persistent controller bindings and rover endpoint, plus the slot the
+    // "listen for next input" rebind mode is currently capturing into.
+    settings: ControllerSettings,
+    listening: Option<BindSlot>,
+
+    // Detected family of the active pad, so controls can be labelled correctly.
+    family: ControllerFamily,
+
     socket: UdpSocket,
 }
 
+/// Per-button press tracking for edge detection and hold-to-accelerate camera
+/// control. `toggle` flips on each rising edge for callers that want latching
+/// behaviour.
+struct ButtonState {
+    is_pressed: bool,
+    was_pressed: bool,
+    time_pressed: f64,
+    toggle: bool,
+}
+
+/// Step magnitudes (degrees) and timing for the hold-to-accelerate ramp.
+const FINE_STEP: f32 = 1.0;
+const FAST_STEP: f32 = 10.0;
+const HOLD_THRESHOLD: f64 = 0.3;
+const RAMP_TIME: f64 = 1.5;
+/// Seconds without a valid RPM packet before the status labels flag the link
+/// as stale.
+const RPM_STALE_SECS: f64 = 2.0;
+
+impl ButtonState {
+    fn new() -> ButtonState {
+        ButtonState { is_pressed: false, was_pressed: false, time_pressed: 0.0, toggle: false }
+    }
+
+    /// Fold in this frame's button read and elapsed `dt`, returning the step
+    /// magnitude to apply: a single fine step on the rising edge, holding fine
+    /// until the hold threshold, then ramping toward the fast step for quick
+    /// repositioning. Zero when the button is up.
+    fn update(&mut self, pressed: bool, dt: f64) -> f32 {
+        self.was_pressed = self.is_pressed;
+        self.is_pressed = pressed;
+
+        if !pressed {
+            self.time_pressed = 0.0;
+            return 0.0;
+        }
+
+        if !self.was_pressed {
+            // Rising edge: one precise nudge, restart the hold timer.
+            self.time_pressed = 0.0;
+            self.toggle = !self.toggle;
+            return FINE_STEP;
+        }
+
+        self.time_pressed += dt;
+        if self.time_pressed > HOLD_THRESHOLD {
+            let t = (((self.time_pressed - HOLD_THRESHOLD) / RAMP_TIME).min(1.0)) as f32;
+            FINE_STEP + t * (FAST_STEP - FINE_STEP)
+        } else {
+            FINE_STEP
+        }
+    }
+}
+
+/// A single rumble burst: low- and high-frequency motor magnitudes plus a
+/// duration, in the style of the usual dual-motor controller rumble command.
+#[derive(Copy, Clone)]
+struct Rumble {
+    low_freq: u16,
+    high_freq: u16,
+    duration_ms: u32,
+}
+
+impl Rumble {
+    /// A strong, short jolt for a Stop press or a lost pad.
+    fn alert() -> Rumble {
+        Rumble { low_freq: 0xFFFF, high_freq: 0xFFFF, duration_ms: 250 }
+    }
+
+    /// A gentle, longer buzz for a motor-status warning.
+    fn warning() -> Rumble {
+        Rumble { low_freq: 0x4000, high_freq: 0x2000, duration_ms: 600 }
+    }
+
+    /// Peak magnitude as a `0.0..1.0` strength for the haptic rumble API.
+    fn strength(&self) -> f32 {
+        self.low_freq.max(self.high_freq) as f32 / 65535.0
+    }
+}
+
 impl RoverUi {
-    fn new(socket: UdpSocket) -> RoverUi {
+    fn new(socket: UdpSocket, family: ControllerFamily) -> RoverUi {
+        // No saved profile yet? Start from this pad's family-appropriate set.
+        let settings = ControllerSettings::load_or(SETTINGS_PATH, family.default_bindings());
+        let max_rpm = settings.max_rpm;
+
         RoverUi {
             bg_color: rgb(0.2, 0.35, 0.45),
-            
+
             l_rpm: 0.0,
             r_rpm: 0.0,
             both_rpm: false,
-            max_rpm: 2000.0,
+            max_rpm: max_rpm,
             l_rpm_status: "UNAVAILABLE".to_string(),
             r_rpm_status: "UNAVAILABLE".to_string(),
+            l_rpm_avg: AvgVal::new(8),
+            r_rpm_avg: AvgVal::new(8),
+            time_since_rpm: 0.0,
             
             f_pan: 90.0,
             f_tilt: 130.0,
-            
+
+            deadzone: 0.1,
+            smoothing_window: 8,
+            l_axis_avg: AvgVal::new(8),
+            r_axis_avg: AvgVal::new(8),
+
+            pending_rumble: None,
+
+            settings: settings,
+            listening: None,
+
+            family: family,
+
             socket: socket,
         }
     }
+
+    /// Format a side's RPM status for display: the smoothed numeric reading
+    /// when telemetry is numeric, otherwise the raw status word, annotated
+    /// with a staleness marker once the link has gone quiet.
+    fn rpm_status_display(&self, status: &str, avg: &AvgVal) -> String {
+        let base = match (status.parse::<f64>().is_ok(), avg.get()) {
+            (true, Some(v)) => format!("{:.0} RPM", v),
+            _ => status.to_string(),
+        };
+        if self.time_since_rpm >= RPM_STALE_SECS {
+            format!("{} (no data {:.0}s)", base, self.time_since_rpm)
+        } else {
+            base
+        }
+    }
+
+    /// Begin capturing the next controller input into the given binding slot.
+    fn listen_for(&mut self, slot: BindSlot) {
+        self.listening = Some(slot);
+    }
+
+    /// Resize the smoothing window, reallocating the per-side averagers. A
+    /// window of zero is clamped to one (no smoothing).
+    fn set_smoothing_window(&mut self, window: usize) {
+        let window = window.max(1);
+        if window != self.smoothing_window {
+            self.smoothing_window = window;
+            self.l_axis_avg = AvgVal::new(window);
+            self.r_axis_avg = AvgVal::new(window);
+        }
+    }
     
     fn on_key_pressed(&mut self, key: input::Key) {
         match key {
@@ -132,17 +295,17 @@ impl RoverUi {
     
     fn send_rpm(&self) {
         let packet = format!("A{}:{}", self.l_rpm as i32, self.r_rpm as i32);
-        self.socket.send_to(packet.as_bytes(), ("10.10.153.25", 30001)).unwrap();
+        self.socket.send_to(packet.as_bytes(), (self.settings.rover_ip.as_str(), self.settings.rover_port)).unwrap();
     }
-    
+
     fn send_f_pan(&self) {
         let packet = format!("B{}", self.f_pan as i32);
-        self.socket.send_to(packet.as_bytes(), ("10.10.153.25", 30001)).unwrap();
+        self.socket.send_to(packet.as_bytes(), (self.settings.rover_ip.as_str(), self.settings.rover_port)).unwrap();
     }
-    
+
     fn send_f_tilt(&self) {
         let packet = format!("C{}", self.f_tilt as i32);
-        self.socket.send_to(packet.as_bytes(), ("10.10.153.25", 30001)).unwrap();
+        self.socket.send_to(packet.as_bytes(), (self.settings.rover_ip.as_str(), self.settings.rover_port)).unwrap();
     }
 }
 
@@ -164,8 +327,19 @@ fn main() {
     let glyph_cache = GlyphCache::new(&font_path).unwrap();
     let mut ui = Ui::new(glyph_cache, theme);
     
-    // Initialize game pad
-    let controller = init_game_pad();
+    // Initialize game pad(s). We keep a live map of open controllers keyed by
+    // instance id and track which one is active, so a pad can be plugged or
+    // unplugged mid-drive without killing control.
+    let mut controllers: HashMap<i32, controller::GameController> = HashMap::new();
+    let mut haptics: HashMap<i32, sdl2::haptic::Haptic> = HashMap::new();
+    let mut active_controller: Option<i32> = None;
+    let family = init_game_pad(&mut controllers, &mut haptics, &mut active_controller);
+
+    // Edge/hold tracking for the D-pad camera controls.
+    let mut dpad_left = ButtonState::new();
+    let mut dpad_right = ButtonState::new();
+    let mut dpad_up = ButtonState::new();
+    let mut dpad_down = ButtonState::new();
     
     // Create a UDP socket to talk to the rover
     let socket = UdpSocket::bind("0.0.0.0:30001").unwrap();
@@ -184,7 +358,7 @@ fn main() {
             }
         }).unwrap();
     
-    let mut rover_ui = RoverUi::new(socket);
+    let mut rover_ui = RoverUi::new(socket, family);
     rover_ui.send_rpm();
     rover_ui.send_f_pan();
     rover_ui.send_f_tilt();
@@ -207,44 +381,132 @@ fn main() {
         });
         
         // Update
-        e.update(|_| {
+        e.update(|u_args| {
+            let dt = u_args.dt;
             while let Ok(msg) = packet_r.try_recv() {
                 //println!("Got packet: {}", msg);
-                let rpm_parts: Vec<String> = msg.split(":").map(|s| s.to_string()).collect();
-                rover_ui.l_rpm_status = rpm_parts[0].clone();
-                rover_ui.r_rpm_status = rpm_parts[1].clone();
+                // Expected shape is at least "left:right"; a shorter split
+                // means a malformed or truncated datagram, so drop it rather
+                // than indexing out of bounds.
+                let rpm_parts: Vec<&str> = msg.split(":").collect();
+                if rpm_parts.len() < 2 {
+                    continue;
+                }
+
+                let l = rpm_parts[0].trim();
+                let r = rpm_parts[1].trim();
+
+                // Feed parseable numeric readings into the rolling averages;
+                // textual status words (e.g. a fault string) leave the buffers
+                // untouched and are shown verbatim.
+                if let Ok(v) = l.parse::<f64>() {
+                    rover_ui.l_rpm_avg.add_value(v);
+                }
+                if let Ok(v) = r.parse::<f64>() {
+                    rover_ui.r_rpm_avg.add_value(v);
+                }
+
+                rover_ui.l_rpm_status = l.to_string();
+                rover_ui.r_rpm_status = r.to_string();
+                rover_ui.time_since_rpm = 0.0;
+
+                // Buzz the pad when either motor reports something other than
+                // a normal running state, so a fault is felt, not just seen.
+                if is_motor_fault(&rover_ui.l_rpm_status) || is_motor_fault(&rover_ui.r_rpm_status) {
+                    rover_ui.pending_rumble = Some(Rumble::warning());
+                }
             }
-            
-            if let Some(ref controller) = controller {
-                // Control RPM with analog sticks
-                let left_y = controller.get_axis(controller::Axis::LeftY).unwrap();
-                let right_y = controller.get_axis(controller::Axis::RightY).unwrap();
-                
-                let l_rpm = -(left_y as f32 / 32768.0) * rover_ui.max_rpm;
-                let r_rpm = -(right_y as f32 / 32768.0) * rover_ui.max_rpm;
-                
+
+            // Age the telemetry so the status labels can flag a silent link.
+            rover_ui.time_since_rpm += dt;
+
+            // Service controller hotplug in the same loop as the window events.
+            if service_controller_hotplug(&mut controllers, &mut haptics, &mut active_controller,
+                                          &mut rover_ui.settings, &mut rover_ui.listening) {
+                // The active pad vanished - fail safe to a full stop so the
+                // rover doesn't run away on the last commanded RPM, and fire a
+                // strong alert on whatever pad remains.
+                rover_ui.l_rpm = 0.0;
+                rover_ui.r_rpm = 0.0;
+                rover_ui.send_rpm();
+                rover_ui.pending_rumble = Some(Rumble::alert());
+            }
+
+            if let Some(controller) = active_controller.and_then(|id| controllers.get(&id)) {
+                // Control RPM with analog sticks. Normalize, apply the
+                // deadzone, then feed the moving-average filter so drift and
+                // momentary spikes don't reach the motors.
+                let left_axis = rover_ui.settings.left_rpm_axis;
+                let right_axis = rover_ui.settings.right_rpm_axis;
+                // Guard the reads so a pad lacking, say, RightY reports zero
+                // rather than panicking.
+                let left_y = controller.get_axis(axis_from_index(left_axis.axis)).unwrap_or(0);
+                let right_y = controller.get_axis(axis_from_index(right_axis.axis)).unwrap_or(0);
+
+                let l_sign = if left_axis.invert { -1.0 } else { 1.0 };
+                let r_sign = if right_axis.invert { -1.0 } else { 1.0 };
+                let l_norm = apply_deadzone(l_sign * (left_y as f32 / 32768.0), rover_ui.deadzone);
+                let r_norm = apply_deadzone(r_sign * (right_y as f32 / 32768.0), rover_ui.deadzone);
+
+                rover_ui.l_axis_avg.add_value(l_norm as f64);
+                rover_ui.r_axis_avg.add_value(r_norm as f64);
+
+                let l_rpm = rover_ui.l_axis_avg.get().unwrap_or(0.0) as f32 * rover_ui.max_rpm;
+                let r_rpm = rover_ui.r_axis_avg.get().unwrap_or(0.0) as f32 * rover_ui.max_rpm;
+
                 rover_ui.try_update_rpm(l_rpm, r_rpm);
                 
-                // Control pan with left/right arrow keys
-                if controller.get_button(controller::Button::DPadLeft).unwrap() {
-                    rover_ui.f_pan -= f32::min(5.0, rover_ui.f_pan - 0.0);
-                    rover_ui.send_f_pan();
+                // Pan with the left/right D-pad: tap for a fine nudge, hold to
+                // accelerate. Only emit a packet when the value actually moves.
+                // A bindable stop button halts the rover from the pad.
+                if controller.get_button(button_from_index(rover_ui.settings.stop_button)).unwrap_or(false) {
+                    rover_ui.l_rpm = 0.0;
+                    rover_ui.r_rpm = 0.0;
+                    rover_ui.send_rpm();
+                    rover_ui.pending_rumble = Some(Rumble::alert());
                 }
-                if controller.get_button(controller::Button::DPadRight).unwrap() {
-                    rover_ui.f_pan += f32::min(5.0, 180.0 - rover_ui.f_pan);
-                    rover_ui.send_f_pan();
+
+                let left_step = dpad_left.update(controller.get_button(button_from_index(rover_ui.settings.pan_left_button)).unwrap_or(false), dt);
+                if left_step > 0.0 {
+                    let new_pan = (rover_ui.f_pan - left_step).max(0.0);
+                    if new_pan != rover_ui.f_pan {
+                        rover_ui.f_pan = new_pan;
+                        rover_ui.send_f_pan();
+                    }
                 }
-                
-                // Control tilt with up/down arrow keys
-                if controller.get_button(controller::Button::DPadDown).unwrap() {
-                    rover_ui.f_tilt -= f32::min(5.0, rover_ui.f_tilt - 90.0);
-                    rover_ui.send_f_tilt();
+                let right_step = dpad_right.update(controller.get_button(button_from_index(rover_ui.settings.pan_right_button)).unwrap_or(false), dt);
+                if right_step > 0.0 {
+                    let new_pan = (rover_ui.f_pan + right_step).min(180.0);
+                    if new_pan != rover_ui.f_pan {
+                        rover_ui.f_pan = new_pan;
+                        rover_ui.send_f_pan();
+                    }
                 }
-                if controller.get_button(controller::Button::DPadUp).unwrap() {
-                    rover_ui.f_tilt += f32::min(5.0, 180.0 - rover_ui.f_tilt);
-                    rover_ui.send_f_tilt();
+
+                // Tilt with the up/down D-pad, clamped to [90, 180].
+                let down_step = dpad_down.update(controller.get_button(button_from_index(rover_ui.settings.tilt_down_button)).unwrap_or(false), dt);
+                if down_step > 0.0 {
+                    let new_tilt = (rover_ui.f_tilt - down_step).max(90.0);
+                    if new_tilt != rover_ui.f_tilt {
+                        rover_ui.f_tilt = new_tilt;
+                        rover_ui.send_f_tilt();
+                    }
+                }
+                let up_step = dpad_up.update(controller.get_button(button_from_index(rover_ui.settings.tilt_up_button)).unwrap_or(false), dt);
+                if up_step > 0.0 {
+                    let new_tilt = (rover_ui.f_tilt + up_step).min(180.0);
+                    if new_tilt != rover_ui.f_tilt {
+                        rover_ui.f_tilt = new_tilt;
+                        rover_ui.send_f_tilt();
+                    }
                 }
             }
+
+            // Apply any rumble queued by the Stop button, a lost pad, or a
+            // motor-status warning to the active controller's haptic device.
+            if let Some(rumble) = rover_ui.pending_rumble.take() {
+                play_rumble(&mut haptics, active_controller, rumble);
+            }
         });
         
         // Render GUI
@@ -318,18 +580,21 @@ fn draw_ui<'a>(c: Context, gl: &mut GlGraphics, ui: &mut Ui<GlyphCache<'a>>, rov
             rover_ui.l_rpm = 0.0;
             rover_ui.r_rpm = 0.0;
             rover_ui.send_rpm();
+            rover_ui.pending_rumble = Some(Rumble::alert());
         })
         .set(STOP_BUTTON, ui);
     
-    // Left status RPM
-    Label::new(rover_ui.l_rpm_status.as_str())
+    // Left status RPM - smoothed value plus a staleness hint.
+    let l_rpm_display = rover_ui.rpm_status_display(&rover_ui.l_rpm_status, &rover_ui.l_rpm_avg);
+    Label::new(l_rpm_display.as_str())
         .xy(110.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 60.0)
         .font_size(32)
         .color(rover_ui.bg_color.plain_contrast())
         .set(L_RPM_STATUS, ui);
-    
-    // Right status RPM
-    Label::new(rover_ui.r_rpm_status.as_str())
+
+    // Right status RPM - smoothed value plus a staleness hint.
+    let r_rpm_display = rover_ui.rpm_status_display(&rover_ui.r_rpm_status, &rover_ui.r_rpm_avg);
+    Label::new(r_rpm_display.as_str())
         .xy((ui.win_w / 2.0) - 110.0, (ui.win_h / 2.0) - 60.0)
         .font_size(32)
         .color(rover_ui.bg_color.plain_contrast())
@@ -361,13 +626,149 @@ fn draw_ui<'a>(c: Context, gl: &mut GlGraphics, ui: &mut Ui<GlyphCache<'a>>, rov
         })
         .set(F_TILT_SLIDER, ui);
 
+    // Stick deadzone tuning
+    Slider::new(rover_ui.deadzone, 0.0, 0.5)
+        .dimensions(200.0, 30.0)
+        .xy(110.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 160.0)
+        .rgb(0.5, 0.3, 0.6)
+        .frame(1.0)
+        .label("Deadzone")
+        .label_color(white())
+        .react(|new_dz| {
+            rover_ui.deadzone = new_dz;
+        })
+        .set(DEADZONE_SLIDER, ui);
+
+    // Smoothing window tuning (frames of moving average)
+    Slider::new(rover_ui.smoothing_window as f32, 1.0, 30.0)
+        .dimensions(200.0, 30.0)
+        .xy(110.0 - (ui.win_w / 2.0) + 210.0, (ui.win_h / 2.0) - 160.0)
+        .rgb(0.5, 0.3, 0.6)
+        .frame(1.0)
+        .label("Smoothing")
+        .label_color(white())
+        .react(|new_window| {
+            rover_ui.set_smoothing_window(new_window as usize);
+        })
+        .set(SMOOTHING_SLIDER, ui);
+
+    // Rebind controls: each button arms "listen for next input" for one slot,
+    // and the next controller event captured in the event loop fills it.
+    Button::new()
+        .dimensions(130.0, 30.0)
+        .xy((ui.win_w / 2.0) - 80.0, (ui.win_h / 2.0) - 110.0)
+        .rgb(0.4, 0.4, 0.7)
+        .frame(1.0)
+        .label("Bind Stop")
+        .react(|| { rover_ui.listen_for(BindSlot::StopButton); })
+        .set(BIND_STOP_BUTTON, ui);
+
+    Button::new()
+        .dimensions(130.0, 30.0)
+        .xy((ui.win_w / 2.0) - 80.0, (ui.win_h / 2.0) - 145.0)
+        .rgb(0.4, 0.4, 0.7)
+        .frame(1.0)
+        .label("Bind L Axis")
+        .react(|| { rover_ui.listen_for(BindSlot::LeftRpmAxis); })
+        .set(BIND_L_AXIS_BUTTON, ui);
+
+    Button::new()
+        .dimensions(130.0, 30.0)
+        .xy((ui.win_w / 2.0) - 80.0, (ui.win_h / 2.0) - 180.0)
+        .rgb(0.4, 0.4, 0.7)
+        .frame(1.0)
+        .label("Bind R Axis")
+        .react(|| { rover_ui.listen_for(BindSlot::RightRpmAxis); })
+        .set(BIND_R_AXIS_BUTTON, ui);
+
+    // Show when a rebind is waiting for input.
+    let bind_status = if rover_ui.listening.is_some() {
+        "Listening for input..."
+    } else {
+        ""
+    };
+    Label::new(bind_status)
+        .xy((ui.win_w / 2.0) - 80.0, (ui.win_h / 2.0) - 210.0)
+        .font_size(16)
+        .color(rover_ui.bg_color.plain_contrast())
+        .set(BIND_STATUS_LABEL, ui);
+
     // Draw our UI!
     ui.draw(c, gl);
 }
 
-pub fn init_game_pad() -> Option<controller::GameController> {
+/// Map a stored axis index back to an SDL controller axis, defaulting to the
+/// right trigger for anything out of range.
+fn axis_from_index(index: i32) -> controller::Axis {
+    match index {
+        0 => controller::Axis::LeftX,
+        1 => controller::Axis::LeftY,
+        2 => controller::Axis::RightX,
+        3 => controller::Axis::RightY,
+        4 => controller::Axis::TriggerLeft,
+        _ => controller::Axis::TriggerRight,
+    }
+}
+
+/// Map a stored button index back to an SDL controller button, defaulting to
+/// `A` for anything out of range.
+fn button_from_index(index: i32) -> controller::Button {
+    match index {
+        0 => controller::Button::A,
+        1 => controller::Button::B,
+        2 => controller::Button::X,
+        3 => controller::Button::Y,
+        11 => controller::Button::DPadUp,
+        12 => controller::Button::DPadDown,
+        13 => controller::Button::DPadLeft,
+        14 => controller::Button::DPadRight,
+        _ => controller::Button::A,
+    }
+}
+
+/// Whether a motor status string reports anything other than a normal running
+/// state, e.g. a fault or the "UNAVAILABLE" placeholder.
+fn is_motor_fault(status: &str) -> bool {
+    let s = status.to_uppercase();
+    s.contains("UNAVAILABLE") || s.contains("FAULT") || s.contains("ERROR") || s.contains("STALL")
+}
+
+/// Try to open the haptic device backing the given controller so rumble can be
+/// played on it. Pads without haptics are simply skipped.
+fn open_haptic(instance_id: i32, haptics: &mut HashMap<i32, sdl2::haptic::Haptic>) {
+    if let Ok(mut haptic) = sdl2::haptic::Haptic::open_from_joystick_id(instance_id) {
+        haptic.rumble_init().ok();
+        haptics.insert(instance_id, haptic);
+    }
+}
+
+/// Play a rumble burst on the active controller's haptic device, if any.
+fn play_rumble(haptics: &mut HashMap<i32, sdl2::haptic::Haptic>, active: Option<i32>, rumble: Rumble) {
+    if let Some(haptic) = active.and_then(|id| haptics.get_mut(&id)) {
+        haptic.rumble_play(rumble.strength(), rumble.duration_ms);
+    }
+}
+
+/// Apply a symmetric deadzone to a normalized `[-1, 1]` axis value: anything
+/// inside `deadzone` clamps to exactly zero, and the remaining travel is
+/// rescaled so motion just past the edge starts from zero rather than jumping.
+fn apply_deadzone(norm: f32, deadzone: f32) -> f32 {
+    let mag = norm.abs();
+    if mag < deadzone {
+        0.0
+    } else {
+        norm.signum() * (mag - deadzone) / (1.0 - deadzone)
+    }
+}
+
+/// Scan for already-connected game controllers at startup, opening each into
+/// `controllers` keyed by instance id and adopting the first as active. Later
+/// plug/unplug events are handled live by `service_controller_hotplug`.
+pub fn init_game_pad(controllers: &mut HashMap<i32, controller::GameController>,
+                     haptics: &mut HashMap<i32, sdl2::haptic::Haptic>,
+                     active: &mut Option<i32>) -> ControllerFamily {
     use sdl2::{joystick, controller};
-    
+
     println!("Looking for game controller...");
 
     let available =
@@ -378,6 +779,8 @@ pub fn init_game_pad() -> Option<controller::GameController> {
 
     println!("{} joysticks available", available);
 
+    let mut family = ControllerFamily::Generic;
+
     // Iterate over all available joysticks and look for game
     // controllers.
     for id in 0..available {
@@ -386,10 +789,18 @@ pub fn init_game_pad() -> Option<controller::GameController> {
 
             match controller::GameController::open(id) {
                 Ok(c) => {
-                    // We managed to find and open a game controller,
-                    // exit the loop
-                    println!("Success: opened \"{}\"", c.name());
-                    return Some(c);
+                    let name = c.name();
+                    println!("Success: opened \"{}\"", name);
+                    let instance_id = c.instance_id();
+                    let adopt = active.is_none();
+                    controllers.insert(instance_id, c);
+                    open_haptic(instance_id, haptics);
+                    if adopt {
+                        *active = Some(instance_id);
+                        // Classify the adopted pad so bindings and labels adapt.
+                        family = ControllerFamily::detect(name.as_str(), 0, 0);
+                        println!("Detected controller family: {}", family.label());
+                    }
                 },
                 Err(e) => println!("Failed to open game controller: {:?}", e),
             }
@@ -399,7 +810,62 @@ pub fn init_game_pad() -> Option<controller::GameController> {
         }
     }
 
-    None
+    family
+}
+
+/// Drain pending SDL controller events, keeping the open-controller map in
+/// step with the hardware. Returns `true` if the active controller was just
+/// removed, so the caller can apply a safety stop.
+pub fn service_controller_hotplug(controllers: &mut HashMap<i32, controller::GameController>,
+                                  haptics: &mut HashMap<i32, sdl2::haptic::Haptic>,
+                                  active: &mut Option<i32>,
+                                  settings: &mut ControllerSettings,
+                                  listening: &mut Option<BindSlot>) -> bool {
+    use sdl2::event::Event;
+
+    let mut active_lost = false;
+    loop {
+        match sdl2::event::poll_event() {
+            Event::None => break,
+            // Rebind capture: the next button/axis event fills the open slot.
+            Event::ControllerButtonDown { button, .. } if listening.is_some() => {
+                let slot = listening.take().unwrap();
+                settings.set_button(slot, button as i32);
+                settings.save(SETTINGS_PATH);
+            },
+            Event::ControllerAxisMotion { axis, value, .. }
+                    if listening.is_some() && (value as i32).abs() > 16000 => {
+                let slot = listening.take().unwrap();
+                settings.set_axis(slot, axis as i32);
+                settings.save(SETTINGS_PATH);
+            },
+            // `which` is the joystick device index to open.
+            Event::ControllerDeviceAdded { which, .. } => {
+                match controller::GameController::open(which as u32) {
+                    Ok(c) => {
+                        println!("Controller connected: \"{}\"", c.name());
+                        let instance_id = c.instance_id();
+                        controllers.insert(instance_id, c);
+                        open_haptic(instance_id, haptics);
+                        *active = Some(instance_id);
+                    },
+                    Err(e) => println!("Failed to open added controller: {:?}", e),
+                }
+            },
+            // `which` is the instance id of the controller that went away.
+            Event::ControllerDeviceRemoved { which, .. } => {
+                controllers.remove(&which);
+                haptics.remove(&which);
+                if *active == Some(which) {
+                    *active = controllers.keys().cloned().next();
+                    active_lost = true;
+                    println!("Active controller disconnected; stopping rover");
+                }
+            },
+            _ => {},
+        }
+    }
+    active_lost
 }
 
 // Widget IDs
@@ -410,4 +876,10 @@ const STOP_BUTTON: WidgetId = R_RPM_SLIDER + 1;
 const L_RPM_STATUS: WidgetId = STOP_BUTTON + 1;
 const R_RPM_STATUS: WidgetId = L_RPM_STATUS + 1;
 const F_PAN_SLIDER: WidgetId = R_RPM_STATUS + 1;
-const F_TILT_SLIDER: WidgetId = F_PAN_SLIDER + 1;
\ No newline at end of file
+const F_TILT_SLIDER: WidgetId = F_PAN_SLIDER + 1;
+const DEADZONE_SLIDER: WidgetId = F_TILT_SLIDER + 1;
+const SMOOTHING_SLIDER: WidgetId = DEADZONE_SLIDER + 1;
+const BIND_STOP_BUTTON: WidgetId = SMOOTHING_SLIDER + 1;
+const BIND_L_AXIS_BUTTON: WidgetId = BIND_STOP_BUTTON + 1;
+const BIND_R_AXIS_BUTTON: WidgetId = BIND_L_AXIS_BUTTON + 1;
+const BIND_STATUS_LABEL: WidgetId = BIND_R_AXIS_BUTTON + 1;
\ No newline at end of file
@@ -0,0 +1,145 @@
+//! Minimal MAVLink v1 ingestion for the common dialect.
+//!
+//! We frame MAVLink v1 packets (`0xFE len seq sysid compid msgid payload crc`)
+//! out of a byte stream and decode just the messages the telemetry dashboard
+//! consumes. Decoded messages are bridged to the legacy `handle_packet` string
+//! format so they flow through the existing `AvgVal` / `LineGraph` / `RygLimit`
+//! path unchanged.
+
+const STX: u8 = 0xFE;
+
+// Common-dialect message ids we care about.
+const MSG_HEARTBEAT: u8 = 0;
+const MSG_SYS_STATUS: u8 = 1;
+const MSG_ATTITUDE: u8 = 30;
+const MSG_GLOBAL_POSITION_INT: u8 = 33;
+const MSG_VFR_HUD: u8 = 74;
+const MSG_SCALED_PRESSURE: u8 = 29;
+const MSG_BATTERY_STATUS: u8 = 147;
+
+/// A decoded MAVLink message, reduced to the fields the UI needs.
+pub enum MavMessage {
+    Heartbeat,
+    /// (latitude deg, longitude deg, altitude m, heading deg)
+    GlobalPosition(f64, f64, f64, f64),
+    /// ground speed, m/s
+    VfrHud(f64),
+    /// (pitch deg, roll deg, yaw deg)
+    Attitude(f64, f64, f64),
+    /// (battery voltage V, current A)
+    SysStatus(f64, f64),
+    /// (pressure hPa, temperature C)
+    ScaledPressure(f64, f64),
+}
+
+impl MavMessage {
+    /// Render as a legacy telemetry string understood by `TelemetryUi::handle_packet`.
+    pub fn to_legacy_string(&self) -> String {
+        match *self {
+            MavMessage::Heartbeat => "HEARTBEAT".to_string(),
+            MavMessage::GlobalPosition(lat, lon, alt, hdg) =>
+                format!("MAV_GPOS:{}:{}:{}:{}", lat, lon, alt, hdg),
+            MavMessage::VfrHud(spd) => format!("MAV_VFR:{}", spd),
+            MavMessage::Attitude(pitch, roll, yaw) =>
+                format!("MAV_ATT:{}:{}:{}", pitch, roll, yaw),
+            MavMessage::SysStatus(v, a) => format!("MAV_SYS:{}:{}", v, a),
+            MavMessage::ScaledPressure(p, t) => format!("MAV_PRESS:{}:{}", p, t),
+        }
+    }
+}
+
+/// Streaming MAVLink v1 frame assembler. Feed it whatever bytes arrive; it
+/// buffers partial frames and resynchronizes on the start-of-frame byte.
+pub struct MavParser {
+    buf: Vec<u8>,
+}
+
+impl MavParser {
+    pub fn new() -> MavParser {
+        MavParser { buf: Vec::new() }
+    }
+
+    /// Append `bytes` and return every complete message that can now be decoded.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<MavMessage> {
+        self.buf.extend_from_slice(bytes);
+        let mut out = Vec::new();
+
+        loop {
+            // Resync: drop anything before the next start byte.
+            while !self.buf.is_empty() && self.buf[0] != STX {
+                self.buf.remove(0);
+            }
+            if self.buf.len() < 6 {
+                break;
+            }
+            let len = self.buf[1] as usize;
+            let total = 6 + len + 2; // header + payload + crc
+            if self.buf.len() < total {
+                break;
+            }
+            let msgid = self.buf[5];
+            let payload: Vec<u8> = self.buf[6..6 + len].to_vec();
+            // We don't verify CRC_EXTRA here; framing + length is enough for a
+            // link that only carries the common dialect.
+            if let Some(msg) = decode(msgid, &payload) {
+                out.push(msg);
+            }
+            self.buf.drain(0..total);
+        }
+
+        out
+    }
+}
+
+fn le_u16(p: &[u8], i: usize) -> u16 { (p[i] as u16) | ((p[i + 1] as u16) << 8) }
+
+fn le_i16(p: &[u8], i: usize) -> i16 { le_u16(p, i) as i16 }
+
+fn le_i32(p: &[u8], i: usize) -> i32 {
+    (p[i] as i32) | ((p[i + 1] as i32) << 8) | ((p[i + 2] as i32) << 16) | ((p[i + 3] as i32) << 24)
+}
+
+fn le_f32(p: &[u8], i: usize) -> f32 {
+    let bits = (p[i] as u32) | ((p[i + 1] as u32) << 8)
+        | ((p[i + 2] as u32) << 16) | ((p[i + 3] as u32) << 24);
+    f32::from_bits(bits)
+}
+
+fn decode(msgid: u8, p: &[u8]) -> Option<MavMessage> {
+    match msgid {
+        MSG_HEARTBEAT => Some(MavMessage::Heartbeat),
+        MSG_GLOBAL_POSITION_INT if p.len() >= 28 => {
+            let lat = le_i32(p, 4) as f64 * 1e-7;
+            let lon = le_i32(p, 8) as f64 * 1e-7;
+            let alt = le_i32(p, 12) as f64 / 1000.0; // mm -> m
+            let hdg = le_u16(p, 26) as f64 / 100.0;   // cdeg -> deg
+            Some(MavMessage::GlobalPosition(lat, lon, alt, hdg))
+        },
+        MSG_VFR_HUD if p.len() >= 20 => {
+            Some(MavMessage::VfrHud(le_f32(p, 4) as f64)) // groundspeed
+        },
+        MSG_ATTITUDE if p.len() >= 16 => {
+            let roll = (le_f32(p, 4) as f64).to_degrees();
+            let pitch = (le_f32(p, 8) as f64).to_degrees();
+            let yaw = (le_f32(p, 12) as f64).to_degrees();
+            Some(MavMessage::Attitude(pitch, roll, yaw))
+        },
+        MSG_SYS_STATUS if p.len() >= 18 => {
+            let volts = le_u16(p, 14) as f64 / 1000.0;  // mV -> V
+            let amps = le_i16(p, 16) as f64 / 100.0;    // cA -> A
+            Some(MavMessage::SysStatus(volts, amps))
+        },
+        MSG_BATTERY_STATUS if p.len() >= 14 => {
+            // voltages[0] at offset 10 (mV), current_battery i16 at offset 8 (cA)
+            let volts = le_u16(p, 10) as f64 / 1000.0;
+            let amps = le_i16(p, 8) as f64 / 100.0;
+            Some(MavMessage::SysStatus(volts, amps))
+        },
+        MSG_SCALED_PRESSURE if p.len() >= 14 => {
+            let press = le_f32(p, 4) as f64;            // hPa
+            let temp = le_i16(p, 12) as f64 / 100.0;    // cdegC -> C
+            Some(MavMessage::ScaledPressure(press, temp))
+        },
+        _ => None,
+    }
+}
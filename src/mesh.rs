@@ -0,0 +1,187 @@
+//! Lightweight triangle mesh plus a software 3D draw path for the attitude
+//! model.
+//!
+//! Nothing in the GUI showed orientation in three dimensions, so this loads a
+//! small OBJ-style mesh (`v`/`vn`/`f` lines) from `assets/` and renders it
+//! through the ordinary piston 2D `Graphics` backend: vertices are rotated by
+//! the live attitude and a fixed three-quarter view, orthographically
+//! projected, depth-sorted (painter's algorithm) and drawn as flat-shaded
+//! triangles. No GPU mesh pipeline is needed, which keeps it in step with the
+//! rest of the immediate-mode widgets.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use graphics::{Context, Graphics};
+
+/// A triangle mesh: a vertex pool and a list of triangles indexing into it.
+pub struct Mesh {
+    vertices: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Parse a minimal OBJ file: `v x y z` vertices and `f a b c` faces
+    /// (polygons are fan-triangulated; `a/b/c` vertex-only or `a//n` forms are
+    /// both accepted, only the vertex index is used). `vn` lines are ignored -
+    /// face normals are recomputed at draw time so shading is robust to a file
+    /// with missing or bad normals.
+    pub fn load_obj(path: &str) -> io::Result<Mesh> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut tok = line.split_whitespace();
+            match tok.next() {
+                Some("v") => {
+                    let coords: Vec<f64> = tok.filter_map(|s| s.parse().ok()).collect();
+                    if coords.len() >= 3 {
+                        vertices.push([coords[0], coords[1], coords[2]]);
+                    }
+                },
+                Some("f") => {
+                    let idx: Vec<usize> = tok.filter_map(|s| {
+                        s.split('/').next().and_then(|i| i.parse::<usize>().ok())
+                    }).collect();
+                    // Fan-triangulate, converting 1-based OBJ indices to 0-based.
+                    for i in 1..idx.len().saturating_sub(1) + 1 {
+                        if i + 1 < idx.len() {
+                            triangles.push([idx[0] - 1, idx[i] - 1, idx[i + 1] - 1]);
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(Mesh { vertices: vertices, triangles: triangles })
+    }
+
+    /// A built-in stand-in rover body (an elongated chassis box), used when the
+    /// asset file is missing so the view is never blank.
+    pub fn rover() -> Mesh {
+        let (x, y, z) = (1.0, 0.3, 0.6);
+        let vertices = vec![
+            [-x, -y, -z], [x, -y, -z], [x, y, -z], [-x, y, -z],
+            [-x, -y,  z], [x, -y,  z], [x, y,  z], [-x, y,  z],
+        ];
+        let triangles = vec![
+            [0, 1, 2], [0, 2, 3], // bottom
+            [4, 6, 5], [4, 7, 6], // top
+            [0, 4, 5], [0, 5, 1], // sides
+            [1, 5, 6], [1, 6, 2],
+            [2, 6, 7], [2, 7, 3],
+            [3, 7, 4], [3, 4, 0],
+        ];
+        Mesh { vertices: vertices, triangles: triangles }
+    }
+
+    /// Draw the mesh rotated by `attitude` (roll, pitch, yaw in degrees) into a
+    /// `w` x `h` panel. With no attitude it shows a caged placeholder.
+    pub fn draw<G: Graphics>(&self, attitude: Option<(f64, f64, f64)>,
+                             size: (f64, f64), c: Context, g: &mut G) {
+        use graphics::*;
+
+        let (w, h) = size;
+        Rectangle::new([0.08, 0.08, 0.12, 1.0])
+            .draw([0.0, 0.0, w, h], &c.draw_state, c.transform, g);
+
+        let (roll, pitch, yaw) = match attitude {
+            Some(a) => a,
+            None => {
+                Line::new([1.0, 0.2, 0.2, 1.0], 2.0).draw([0.0, 0.0, w, h], &c.draw_state, c.transform, g);
+                Line::new([1.0, 0.2, 0.2, 1.0], 2.0).draw([0.0, h, w, 0.0], &c.draw_state, c.transform, g);
+                return;
+            },
+        };
+
+        // Attitude rotation followed by a fixed three-quarter viewing angle.
+        let att = mul(rot_z(yaw.to_radians()), mul(rot_y(pitch.to_radians()), rot_x(roll.to_radians())));
+        let view = mul(rot_x((-20.0f64).to_radians()), rot_y(30.0f64.to_radians()));
+        let xform = mul(view, att);
+
+        let scale = (w.min(h) / 2.0) * 0.6;
+        let (cx, cy) = (w / 2.0, h / 2.0);
+        let light = normalize([0.4, 0.6, 1.0]);
+
+        // Depth-sort faces back-to-front for the painter's algorithm.
+        let mut faces: Vec<(f64, [f64; 3], [[f64; 3]; 3])> = Vec::with_capacity(self.triangles.len());
+        for tri in &self.triangles {
+            let p: [[f64; 3]; 3] = [
+                apply(&xform, self.vertices[tri[0]]),
+                apply(&xform, self.vertices[tri[1]]),
+                apply(&xform, self.vertices[tri[2]]),
+            ];
+            let n = normalize(cross(sub(p[1], p[0]), sub(p[2], p[0])));
+            let depth = (p[0][2] + p[1][2] + p[2][2]) / 3.0;
+            faces.push((depth, n, p));
+        }
+        faces.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        for &(_, n, p) in &faces {
+            // No back-face cull: drawing strictly far-to-back lets nearer
+            // triangles overwrite hidden ones regardless of winding order.
+            let shade = 0.25 + 0.75 * dot(n, light).abs();
+            let poly = [
+                [cx + p[0][0] * scale, cy - p[0][1] * scale],
+                [cx + p[1][0] * scale, cy - p[1][1] * scale],
+                [cx + p[2][0] * scale, cy - p[2][1] * scale],
+            ];
+            Polygon::new([0.2 * shade as f32, 0.8 * shade as f32, 0.4 * shade as f32, 1.0])
+                .draw(&poly, &c.draw_state, c.transform, g);
+        }
+    }
+}
+
+// --- Tiny 3x3 matrix / vector helpers (column-applied to row vectors) ---
+
+type Mat3 = [[f64; 3]; 3];
+
+fn rot_x(a: f64) -> Mat3 {
+    [[1.0, 0.0, 0.0], [0.0, a.cos(), -a.sin()], [0.0, a.sin(), a.cos()]]
+}
+
+fn rot_y(a: f64) -> Mat3 {
+    [[a.cos(), 0.0, a.sin()], [0.0, 1.0, 0.0], [-a.sin(), 0.0, a.cos()]]
+}
+
+fn rot_z(a: f64) -> Mat3 {
+    [[a.cos(), -a.sin(), 0.0], [a.sin(), a.cos(), 0.0], [0.0, 0.0, 1.0]]
+}
+
+fn mul(a: Mat3, b: Mat3) -> Mat3 {
+    let mut r = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            r[i][j] = a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j];
+        }
+    }
+    r
+}
+
+fn apply(m: &Mat3, v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let len = dot(v, v).sqrt();
+    if len == 0.0 { v } else { [v[0] / len, v[1] / len, v[2] / len] }
+}
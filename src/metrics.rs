@@ -0,0 +1,204 @@
+//! Lightweight timing metrics for the video decode/record/link hot loops.
+//!
+//! The pipeline only ever emitted ad-hoc `println!`s of PTS, which is no help
+//! when an operator is trying to work out whether lag lives in software
+//! scaling, in the encoder, or out on the link. Each tracked quantity gets a
+//! `Summary` - a fixed-bucket latency histogram plus a running count - kept
+//! behind an `Arc<Mutex<_>>` so the hot loops can update it cheaply and the UI
+//! (or a scrape socket) can read a consistent snapshot. Quantiles are
+//! estimated from the bucket boundaries, which is accurate enough to spot a
+//! regressing stage without the cost of keeping every sample.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+
+/// Upper edges of the latency buckets, in microseconds. A sample counts into
+/// the first bucket whose edge it does not exceed; anything larger lands in an
+/// implicit overflow bucket.
+const BUCKET_EDGES_US: [u64; 12] =
+    [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000];
+
+/// A histogram of observed durations for one pipeline stage.
+#[derive(Clone)]
+pub struct Summary {
+    count: u64,
+    sum_us: u64,
+    /// One counter per edge in `BUCKET_EDGES_US`, plus a trailing overflow.
+    buckets: [u64; 13],
+}
+
+impl Summary {
+    pub fn new() -> Summary {
+        Summary { count: 0, sum_us: 0, buckets: [0; 13] }
+    }
+
+    /// Record one observation, in microseconds.
+    pub fn observe(&mut self, micros: u64) {
+        self.count += 1;
+        self.sum_us += micros;
+        let mut slot = BUCKET_EDGES_US.len();
+        for (i, &edge) in BUCKET_EDGES_US.iter().enumerate() {
+            if micros <= edge {
+                slot = i;
+                break;
+            }
+        }
+        self.buckets[slot] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean observation in microseconds, or `0.0` before any samples.
+    pub fn mean_us(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum_us as f64 / self.count as f64 }
+    }
+
+    /// Estimate the `q` quantile (0..1) from the bucket upper edges.
+    pub fn quantile_us(&self, q: f64) -> u64 {
+        if self.count == 0 { return 0; }
+        let target = (q * self.count as f64).ceil() as u64;
+        let mut cumulative = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            cumulative += n;
+            if cumulative >= target {
+                return BUCKET_EDGES_US.get(i).cloned().unwrap_or(BUCKET_EDGES_US[BUCKET_EDGES_US.len() - 1]);
+            }
+        }
+        BUCKET_EDGES_US[BUCKET_EDGES_US.len() - 1]
+    }
+}
+
+/// Shared handle to a single `Summary`, cloneable across threads.
+#[derive(Clone)]
+pub struct Metric {
+    name: &'static str,
+    summary: Arc<Mutex<Summary>>,
+}
+
+impl Metric {
+    fn new(name: &'static str) -> Metric {
+        Metric { name: name, summary: Arc::new(Mutex::new(Summary::new())) }
+    }
+
+    /// Record an observation, in microseconds.
+    pub fn observe(&self, micros: u64) {
+        self.summary.lock().unwrap().observe(micros);
+    }
+
+    /// A consistent copy of the current histogram.
+    pub fn snapshot(&self) -> Summary {
+        self.summary.lock().unwrap().clone()
+    }
+}
+
+/// The full set of metrics the video pipeline exposes, all cheaply cloneable
+/// so each producer thread keeps its own handle to the shared summaries.
+#[derive(Clone)]
+pub struct VideoMetrics {
+    /// Time spent in `sws_context.run` per frame.
+    pub scaling: Metric,
+    /// Time spent in `encoder.encode` per frame.
+    pub encode: Metric,
+    /// Decode-to-display latency per frame.
+    pub display_latency: Metric,
+    /// Time spent sending each packet flushed from `NavigationUi::out_queue`.
+    pub queue_flush: Metric,
+}
+
+impl VideoMetrics {
+    pub fn new() -> VideoMetrics {
+        VideoMetrics {
+            scaling: Metric::new("video_scaling_seconds"),
+            encode: Metric::new("video_encode_seconds"),
+            display_latency: Metric::new("video_display_latency_seconds"),
+            queue_flush: Metric::new("out_queue_flush_seconds"),
+        }
+    }
+
+    fn all(&self) -> [&Metric; 4] {
+        [&self.scaling, &self.encode, &self.display_latency, &self.queue_flush]
+    }
+
+    /// Render the current snapshot in Prometheus text exposition format.
+    ///
+    /// Each metric becomes a summary with p50/p90/p99 quantiles plus the
+    /// `_sum`/`_count` pair the convention expects; durations are reported in
+    /// seconds to match Prometheus base units.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for metric in self.all().iter() {
+            let s = metric.snapshot();
+            out.push_str(&format!("# TYPE {} summary\n", metric.name));
+            for &q in &[0.5, 0.9, 0.99] {
+                out.push_str(&format!("{}{{quantile=\"{}\"}} {:.6}\n",
+                                      metric.name, q, s.quantile_us(q) as f64 / 1_000_000.0));
+            }
+            out.push_str(&format!("{}_sum {:.6}\n", metric.name,
+                                  s.mean_us() * s.count() as f64 / 1_000_000.0));
+            out.push_str(&format!("{}_count {}\n", metric.name, s.count()));
+        }
+        out
+    }
+
+    /// A compact one-line-per-stage summary for an on-screen overlay.
+    pub fn overlay_lines(&self) -> Vec<String> {
+        self.all().iter().map(|metric| {
+            let s = metric.snapshot();
+            format!("{}: p50 {}us p90 {}us p99 {}us (n={})",
+                    metric.name, s.quantile_us(0.5), s.quantile_us(0.9), s.quantile_us(0.99), s.count())
+        }).collect()
+    }
+}
+
+static INIT: Once = Once::new();
+static mut SHARED: Option<VideoMetrics> = None;
+
+/// The process-wide metrics registry. Every producer - the decode thread, the
+/// record thread, the UI's outbound-queue flush - records into this same set of
+/// summaries, and the scrape socket reads a consistent snapshot from it. The
+/// returned handle is a cheap bundle of `Arc`s, so cloning it per thread is
+/// fine.
+pub fn shared() -> VideoMetrics {
+    unsafe {
+        INIT.call_once(|| { SHARED = Some(VideoMetrics::new()); });
+        SHARED.clone().unwrap()
+    }
+}
+
+/// Spawn a tiny scrape server that answers every connection with the current
+/// snapshot in Prometheus text exposition format, so a local Prometheus (or a
+/// plain `curl`) can pull latency histograms off a running GUI. Failing to bind
+/// is non-fatal: the metrics are still gathered and can be shown in the overlay.
+pub fn serve_scrape(addr: &'static str) {
+    thread::Builder::new()
+        .name("metrics_scrape".to_string())
+        .spawn(move || {
+            let listener = match TcpListener::bind(addr) {
+                Ok(l) => l,
+                Err(e) => {
+                    println!("WARNING: could not bind metrics scrape socket {}: {}", addr, e);
+                    return;
+                },
+            };
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                // Drain the request line; we serve the same payload regardless.
+                let mut scratch = [0u8; 512];
+                let _ = stream.read(&mut scratch);
+                let body = shared().to_prometheus();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        })
+        .unwrap();
+}
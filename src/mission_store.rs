@@ -0,0 +1,98 @@
+//! Persistent per-mission telemetry store and replay.
+//!
+//! Live telemetry used to vanish on exit: `handle_packet` parsed each packet
+//! straight into the in-memory widgets and nothing touched disk. This records
+//! every inbound packet string into a small SQLite file in the mission folder
+//! (`mission_data/<timestamp>/telemetry.sqlite`) with the schema
+//! `(t_ms INTEGER, packet_id TEXT, payload TEXT)`, where `t_ms` is milliseconds
+//! since the recorder opened.
+//!
+//! [`replay`] runs the store back: a background thread reads rows in `t_ms`
+//! order, sleeps the inter-row delta, and hands each reconstructed packet
+//! string to a feed closure - normally one that forwards into the same channel
+//! `handle_packet` already drains - so the voltage/RPM graphs redraw exactly
+//! as they did live, with no rover connected.
+
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+
+/// File name of the per-mission SQLite store inside a mission folder.
+const STORE_FILE: &'static str = "telemetry.sqlite";
+
+fn store_path(mission_folder: &str) -> String {
+    format!("mission_data/{}/{}", mission_folder, STORE_FILE)
+}
+
+/// Writes inbound packets to the mission's SQLite store, timestamped relative
+/// to when it was opened.
+pub struct Recorder {
+    conn: Connection,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Open (creating if needed) the store in `mission_folder` and start the
+    /// relative clock. Any SQLite error is surfaced to the caller, which keeps
+    /// running unrecorded rather than bringing the GUI down.
+    pub fn open(mission_folder: &str) -> Result<Recorder, rusqlite::Error> {
+        let conn = Connection::open(store_path(mission_folder))?;
+        conn.execute("CREATE TABLE IF NOT EXISTS telemetry (\
+                          t_ms INTEGER NOT NULL, \
+                          packet_id TEXT NOT NULL, \
+                          payload TEXT NOT NULL)", &[])?;
+        Ok(Recorder { conn: conn, start: Instant::now() })
+    }
+
+    /// Append one packet. The packet id is its leading `:`-delimited token,
+    /// kept in its own column so a later query can filter by message type.
+    pub fn record(&self, packet: &str) {
+        let elapsed = self.start.elapsed();
+        let t_ms = (elapsed.as_secs() * 1000 + elapsed.subsec_nanos() as u64 / 1_000_000) as i64;
+        let packet_id = packet.split(":").next().unwrap_or("").to_string();
+        if let Err(e) = self.conn.execute(
+            "INSERT INTO telemetry (t_ms, packet_id, payload) VALUES (?1, ?2, ?3)",
+            &[&t_ms, &packet_id, &packet.to_string()]) {
+            println!("WARNING: telemetry record failed: {}", e);
+        }
+    }
+}
+
+/// Spawn a thread that replays a recorded mission into `feed`, pacing each
+/// packet by the gap that separated it from the previous one. Returns
+/// immediately; a missing or unreadable store logs a warning and the thread
+/// exits, leaving the UI idle.
+pub fn replay<F>(mission_folder: &str, feed: F)
+    where F: Fn(String) + Send + 'static
+{
+    let path = store_path(mission_folder);
+    thread::Builder::new()
+        .name("telemetry_replay".to_string())
+        .spawn(move || {
+            let conn = match Connection::open(&Path::new(&path)) {
+                Ok(c) => c,
+                Err(e) => { println!("WARNING: could not open replay store {}: {}", path, e); return; },
+            };
+            let mut stmt = match conn.prepare("SELECT t_ms, payload FROM telemetry ORDER BY t_ms ASC") {
+                Ok(s) => s,
+                Err(e) => { println!("WARNING: could not read replay store: {}", e); return; },
+            };
+            let rows = stmt.query_map(&[], |row| {
+                let t_ms: i64 = row.get(0);
+                let payload: String = row.get(1);
+                (t_ms, payload)
+            }).unwrap();
+
+            let mut last_t_ms = 0i64;
+            for row in rows {
+                let (t_ms, payload) = match row { Ok(r) => r, Err(_) => continue };
+                let delta = (t_ms - last_t_ms).max(0) as u64;
+                thread::sleep(Duration::from_millis(delta));
+                last_t_ms = t_ms;
+                feed(payload);
+            }
+        })
+        .unwrap();
+}
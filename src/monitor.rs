@@ -0,0 +1,211 @@
+//! Safe-operating-range monitoring for telemetry channels.
+//!
+//! `handle_packet` used to `parse().unwrap()` a voltage straight onto a graph,
+//! with no notion of a nominal range and no survival of a malformed packet.
+//! This gives each channel (12V bus, per-track RPM, and later current or
+//! temperature) configurable min/warn/max thresholds: incoming samples are
+//! clamped into a displayable range and sorted into OK/warning/critical bands.
+//! A band only eases back toward nominal once the value has recovered past a
+//! hysteresis margin, so a noisy signal sitting on a limit latches the alarm
+//! instead of flickering. The severity/colour mapping mirrors the `RygLimit`
+//! scheme already used by the telemetry dashboard.
+
+/// Safe-operating band of a monitored value, listed best-first in severity
+/// order.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Band {
+    Ok,
+    Warning,
+    Critical,
+}
+
+impl Band {
+    /// 0 nominal, 1 warning, 2 critical - used for the worst-of rollup that
+    /// colours the banner.
+    pub fn severity(&self) -> u8 {
+        match *self {
+            Band::Ok => 0,
+            Band::Warning => 1,
+            Band::Critical => 2,
+        }
+    }
+
+    /// RGBA line/label colour: green nominal, amber warning, red critical.
+    pub fn color(&self) -> [f32; 4] {
+        match *self {
+            Band::Ok => [0.2, 0.9, 0.3, 1.0],
+            Band::Warning => [1.0, 0.85, 0.0, 1.0],
+            Band::Critical => [1.0, 0.2, 0.2, 1.0],
+        }
+    }
+}
+
+/// Thresholds for one channel. `min`/`max` bound the displayable range (samples
+/// are clamped into it) and double as the critical limits; `warn_low`/
+/// `warn_high` mark the amber band just inside them. `hysteresis` is the margin
+/// a value must recover by before its band is allowed to improve.
+#[derive(Copy, Clone)]
+pub struct Thresholds {
+    pub min: f64,
+    pub warn_low: f64,
+    pub warn_high: f64,
+    pub max: f64,
+    pub hysteresis: f64,
+}
+
+impl Thresholds {
+    /// A one-sided lower limit, e.g. bus voltage sag: warn below `warn`,
+    /// critical below `min`; `max` only bounds the display range.
+    pub fn falling(min: f64, warn: f64, max: f64) -> Thresholds {
+        Thresholds {
+            min: min,
+            warn_low: warn,
+            warn_high: max,
+            max: max,
+            hysteresis: (warn - min).abs() * 0.1,
+        }
+    }
+
+    /// A symmetric magnitude limit, e.g. track RPM: nominal within `±warn`,
+    /// warning out to `±max`, critical beyond.
+    pub fn magnitude(warn: f64, max: f64) -> Thresholds {
+        Thresholds {
+            min: -max,
+            warn_low: -warn,
+            warn_high: warn,
+            max: max,
+            hysteresis: (max - warn).abs() * 0.1,
+        }
+    }
+}
+
+/// A single monitored channel: its thresholds, last clamped value, and latched
+/// band.
+struct Channel {
+    label: String,
+    thresholds: Thresholds,
+    value: f64,
+    band: Band,
+}
+
+impl Channel {
+    /// Band of `v` against the strict thresholds.
+    fn classify(&self, v: f64) -> Band {
+        let t = &self.thresholds;
+        if v <= t.min || v >= t.max {
+            Band::Critical
+        } else if v <= t.warn_low || v >= t.warn_high {
+            Band::Warning
+        } else {
+            Band::Ok
+        }
+    }
+
+    /// Band of `v` against thresholds tightened by the hysteresis margin, used
+    /// only when deciding whether to ease an alarm back down.
+    fn classify_relaxed(&self, v: f64) -> Band {
+        let t = &self.thresholds;
+        let h = t.hysteresis;
+        if v <= t.min + h || v >= t.max - h {
+            Band::Critical
+        } else if v <= t.warn_low + h || v >= t.warn_high - h {
+            Band::Warning
+        } else {
+            Band::Ok
+        }
+    }
+
+    fn observe(&mut self, raw: f64) {
+        self.value = raw.max(self.thresholds.min).min(self.thresholds.max);
+
+        let strict = self.classify(raw);
+        if strict.severity() > self.band.severity() {
+            // Escalate the moment a limit is crossed.
+            self.band = strict;
+        } else if strict.severity() < self.band.severity() {
+            // De-escalate only once clear of the boundary by the margin.
+            let relaxed = self.classify_relaxed(raw);
+            if relaxed.severity() < self.band.severity() {
+                self.band = relaxed;
+            }
+        }
+    }
+}
+
+/// A set of monitored channels plus a parse-error latch, driving the alarm
+/// banner and per-channel colouring.
+pub struct Monitor {
+    channels: Vec<Channel>,
+    parse_error: bool,
+}
+
+impl Monitor {
+    pub fn new() -> Monitor {
+        Monitor { channels: Vec::new(), parse_error: false }
+    }
+
+    /// Register a channel with the given thresholds. Channels keep insertion
+    /// order so the banner lists them predictably.
+    pub fn channel(&mut self, label: &str, thresholds: Thresholds) {
+        let value = thresholds.warn_low.max(thresholds.min);
+        self.channels.push(Channel {
+            label: label.to_string(),
+            thresholds: thresholds,
+            value: value,
+            band: Band::Ok,
+        });
+    }
+
+    /// Feed a fresh sample into `label`; unknown labels are ignored.
+    pub fn observe(&mut self, label: &str, raw: f64) {
+        if let Some(ch) = self.channels.iter_mut().find(|c| c.label == label) {
+            ch.observe(raw);
+        }
+    }
+
+    /// Latched band of `label`, or `Ok` if it isn't registered.
+    pub fn band_of(&self, label: &str) -> Band {
+        self.channels.iter().find(|c| c.label == label).map(|c| c.band).unwrap_or(Band::Ok)
+    }
+
+    /// Last clamped value of `label`, or `0.0` if it isn't registered.
+    pub fn value_of(&self, label: &str) -> f64 {
+        self.channels.iter().find(|c| c.label == label).map(|c| c.value).unwrap_or(0.0)
+    }
+
+    /// Record that a packet failed to parse; raises a standing parse-error
+    /// alarm rather than crashing the GUI.
+    pub fn note_parse_error(&mut self) {
+        self.parse_error = true;
+    }
+
+    /// The worst band across every channel, for the banner colour.
+    pub fn worst(&self) -> Band {
+        let mut worst = Band::Ok;
+        for c in &self.channels {
+            if c.band.severity() > worst.severity() {
+                worst = c.band;
+            }
+        }
+        if self.parse_error && worst.severity() < Band::Critical.severity() {
+            worst = Band::Critical;
+        }
+        worst
+    }
+
+    /// One line per active alarm for the on-screen banner; empty when nominal.
+    pub fn active_alarms(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for c in &self.channels {
+            match c.band {
+                Band::Warning => out.push(format!("{} WARN", c.label)),
+                Band::Critical => out.push(format!("{} CRITICAL", c.label)),
+                Band::Ok => {},
+            }
+        }
+        if self.parse_error {
+            out.push("TELEMETRY PARSE ERROR".to_string());
+        }
+        out
+    }
+}
@@ -1,10 +1,11 @@
 use std::ascii::AsciiExt;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
 use std::io::Write;
 use std::net::UdpSocket;
 use std::ops::DerefMut;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 
 use conrod::{
     self,
@@ -33,9 +34,25 @@ use gfx_device_gl;
 use piston_window::{self, Glyphs, Key};
 use time;
 
+use audio::VoiceChannel;
+use autopilot::{Autopilot, AutopilotCmd};
+use blackbox::{Player, Recorder};
+use command_encoder::{CommandEncoder, LegacyEncoder, MavlinkEncoder};
 use conrod_config;
+use exif::SnapshotMeta;
+use framing;
+use framing::ReliableTracker;
+use gradient::Gradient;
 use imu;
-use video_stream::VideoMsg;
+use layout::{ScalePolicy, UiScale};
+use linksim::LinkSim;
+use mesh;
+use metrics;
+use monitor::{Monitor, Thresholds};
+use osd;
+use pid::PidTuning;
+use video_stream::{RecordMode, VideoMsg};
+use watchdog::Watchdog;
 
 enum MissionTime {
     Paused(time::Duration),
@@ -49,10 +66,15 @@ pub struct NavigationUi {
 
     // IMU
     pitch_roll_heading: Option<(f64, f64, f64)>,
+    imu_filter: imu::ComplementaryFilter,
     pitch: imu::Roll,
     roll: imu::Roll,
     heading: imu::Heading,
 
+    // 3D attitude model and its latest (roll, pitch, yaw) from ATTITUDE packets.
+    attitude_mesh: mesh::Mesh,
+    attitude: Option<(f64, f64, f64)>,
+
     // GPS
     latitude: Option<f64>,
     longitude: Option<f64>,
@@ -65,8 +87,34 @@ pub struct NavigationUi {
     pub r_rpm: f32,
     pub max_rpm: f32,
 
+    // Closed-loop rate control: the slider sets a setpoint and, when enabled,
+    // a per-side PID drives the actual motor command from the measured wheel
+    // RPM reported over telemetry.
+    pid_enabled: bool,
+    tuning_open: bool,
+    l_pid: PidTuning,
+    r_pid: PidTuning,
+    l_cmd: f32,
+    r_cmd: f32,
+    measured_l_rpm: f32,
+    measured_r_rpm: f32,
+
     pub motor_speed: f32,
 
+    // Keyboard drive layer: held direction inputs (-1/0/1) form a throttle and
+    // steering pair that are mixed into the two tracks, slew-rate limited into
+    // the per-track commanded RPM, and gated by a tap-tempo cruise setpoint.
+    drive_fwd_input: f32,
+    drive_turn_input: f32,
+    drive_l: f32,
+    drive_r: f32,
+    cruise: Option<f32>,
+    last_cruise_tap: Option<Instant>,
+
+    /// GPS waypoint autopilot. When engaged it owns `l_rpm`/`r_rpm` until the
+    /// route completes or any drive key is pressed.
+    autopilot: Autopilot,
+
     pub sadl: f32,
     pub last_sadl_time: time::Tm,
 
@@ -80,11 +128,29 @@ pub struct NavigationUi {
     pub f_tilting: f32,
     pub last_f_tilt_time: time::Tm,
     pub want_snapshot: bool,
+    pub recording: bool,
+
+    // Intervalometer: periodic automated snapshots, modeled on PX4's camera
+    // trigger module. `interval`/`activation` are operator-tuned via sliders;
+    // `last_snapshot_time` anchors the schedule and `pending_disengage` holds
+    // the fire time for the matching disengage once an engage has gone out.
+    pub intervalometer_enabled: bool,
+    pub intervalometer_interval_ms: f32,
+    pub intervalometer_activation_ms: f32,
+    last_snapshot_time: time::Tm,
+    pending_disengage: Option<time::Tm>,
+    pub snapshot_count: u32,
 
     pub command: String,
     pub command_mode: bool,
     command_history: Vec<String>,
 
+    // The active wire encoding for drive/camera/SADL intents - legacy text
+    // packets by default, swappable to MAVLink at startup or via the
+    // protocol toggle button.
+    encoder: Box<CommandEncoder>,
+    pub mavlink_active: bool,
+
     client: UdpSocket,
     vid0_t: Sender<VideoMsg>,
     vid1_t: Sender<VideoMsg>,
@@ -96,6 +162,69 @@ pub struct NavigationUi {
     delay: time::Duration,
     delay_str: String,
 
+    /// Configurable degraded-link model applied to `out_queue`, with the text
+    /// buffers backing its loss/jitter/bandwidth fields.
+    link: LinkSim,
+    loss_str: String,
+    jitter_str: String,
+    bandwidth_str: String,
+
+    /// Empty unless `frames` has given up on a command; drawn next to the
+    /// drive controls so a lost command is impossible to miss.
+    pub link_status: String,
+
+    /// CRC-protected reliable framing over the `out_queue`: each queued packet
+    /// is sequenced and checksummed, retransmitted until the rover acks it.
+    frames: ReliableTracker,
+
+    /// Named firmware parameters, PX4-style: `params` holds the last value the
+    /// rover acknowledged, `param_edits` the per-row text buffers, and
+    /// `param_dirty` the names edited locally but not yet confirmed back.
+    params: HashMap<String, f32>,
+    param_edits: HashMap<String, String>,
+    param_dirty: HashSet<String>,
+    /// First visible row in the parameter table; scrolled with the up/down
+    /// buttons since the table can outgrow its fixed window.
+    param_scroll: usize,
+
+    /// Per-source telemetry health tracker feeding the alarm banner.
+    watchdog: Watchdog,
+
+    /// Safe-operating-range tracker for the measured WRPM feedback: clamps
+    /// each side into a displayable range, classifies it OK/warning/critical
+    /// with hysteresis, and latches a standing alarm on a malformed WRPM
+    /// packet instead of the GUI ever unwrapping one.
+    monitor: Monitor,
+
+    // Scalar-to-colour maps for the readouts and gauges: RPM by fraction of
+    // maximum, speed and altitude by absolute band, and a tip-over map keyed
+    // on the attitude angle's magnitude.
+    /// How hardcoded widget coordinates are scaled onto the live window.
+    scale_policy: ScalePolicy,
+
+    rpm_gradient: Gradient,
+    speed_gradient: Gradient,
+    altitude_gradient: Gradient,
+    tip_gradient: Gradient,
+
+    pub voice: VoiceChannel,
+
+    /// Burned-in video overlay configuration; toggle elements off for a clean
+    /// recording.
+    pub osd: osd::OsdConfig,
+
+    // Mission black box: `recorder` captures inbound/outbound traffic while a
+    // mission runs; `player`, when set, replays a recorded mission through
+    // `handle_packet`. In replay `transmit` is false so no packet reaches the
+    // socket, and `playback_time`/`playback_speed`/`playback_cursor` drive the
+    // scrub.
+    recorder: Option<Recorder>,
+    player: Option<Player>,
+    transmit: bool,
+    playback_time: f64,
+    playback_speed: f32,
+    playback_cursor: usize,
+
     image_map: conrod::image::Map<<piston_window::G2d<'static> as Graphics>::Texture>,
 }
 
@@ -104,6 +233,7 @@ impl NavigationUi {
                vid0_t: Sender<VideoMsg>,
                vid1_t: Sender<VideoMsg>,
                vid2_t: Sender<VideoMsg>,
+               voice: VoiceChannel,
                mission_folder: String) -> NavigationUi {
         NavigationUi {
             bg_color: rgb(0.2, 0.35, 0.45),
@@ -111,10 +241,16 @@ impl NavigationUi {
             mission_time: MissionTime::Paused(time::Duration::zero()),
 
             pitch_roll_heading: None,
+            imu_filter: imu::ComplementaryFilter::new(),
             pitch: imu::Roll::new(),
             roll: imu::Roll::new(),
             heading: imu::Heading::new(),
 
+            // Prefer the asset mesh, falling back to the built-in chassis.
+            attitude_mesh: mesh::Mesh::load_obj("assets/rover.obj")
+                .unwrap_or_else(|_| mesh::Mesh::rover()),
+            attitude: None,
+
             latitude: None,
             longitude: None,
             speed: None,
@@ -123,10 +259,28 @@ impl NavigationUi {
 
             l_rpm: 0.0,
             r_rpm: 0.0,
+
+            pid_enabled: false,
+            tuning_open: false,
+            l_pid: PidTuning::new(),
+            r_pid: PidTuning::new(),
+            l_cmd: 0.0,
+            r_cmd: 0.0,
+            measured_l_rpm: 0.0,
+            measured_r_rpm: 0.0,
+
+            drive_fwd_input: 0.0,
+            drive_turn_input: 0.0,
+            drive_l: 0.0,
+            drive_r: 0.0,
+            cruise: None,
+            last_cruise_tap: None,
             max_rpm: 100.0,
 
             motor_speed: 1.0,
 
+            autopilot: Autopilot::new(),
+
             sadl: 0.0,
             last_sadl_time: time::now(),
 
@@ -139,11 +293,22 @@ impl NavigationUi {
             f_tilting: 0.0,
             last_f_tilt_time: time::now(),
             want_snapshot: false,
+            recording: false,
+
+            intervalometer_enabled: false,
+            intervalometer_interval_ms: 5000.0,
+            intervalometer_activation_ms: 200.0,
+            last_snapshot_time: time::now(),
+            pending_disengage: None,
+            snapshot_count: 0,
 
             command: "".to_string(),
             command_mode: false,
             command_history: vec![],
 
+            encoder: Box::new(LegacyEncoder),
+            mavlink_active: false,
+
             client: client,
             vid0_t: vid0_t,
             vid1_t: vid1_t,
@@ -155,19 +320,286 @@ impl NavigationUi {
             delay: time::Duration::seconds(0),
             delay_str: "".to_string(),
 
+            link: LinkSim::new(),
+            loss_str: "".to_string(),
+            jitter_str: "".to_string(),
+            bandwidth_str: "".to_string(),
+
+            link_status: "".to_string(),
+
+            frames: ReliableTracker::new(),
+
+            params: HashMap::new(),
+            param_edits: HashMap::new(),
+            param_dirty: HashSet::new(),
+            param_scroll: 0,
+
+            // Watch the live telemetry sources fed through `handle_packet`; a
+            // source silent for more than two seconds is flagged stale.
+            watchdog: Watchdog::new(&["IMU", "GPS"], Duration::from_millis(2000)),
+
+            monitor: {
+                let mut monitor = Monitor::new();
+                monitor.channel("L RPM", Thresholds::magnitude(1800.0, 2000.0));
+                monitor.channel("R RPM", Thresholds::magnitude(1800.0, 2000.0));
+                monitor
+            },
+
+            scale_policy: ScalePolicy::Stretch,
+
+            // Green in the nominal band, amber mid-range, red at the limit.
+            rpm_gradient: Gradient::new(vec![
+                (0.0, rgb(0.0, 1.0, 0.0)),
+                (0.7, rgb(1.0, 0.85, 0.0)),
+                (1.0, rgb(1.0, 0.0, 0.0)),
+            ]),
+            speed_gradient: Gradient::new(vec![
+                (0.0, rgb(0.0, 1.0, 0.0)),
+                (5.0, rgb(1.0, 0.85, 0.0)),
+                (10.0, rgb(1.0, 0.0, 0.0)),
+            ]),
+            altitude_gradient: Gradient::new(vec![
+                (0.0, rgb(0.0, 1.0, 0.0)),
+                (2000.0, rgb(1.0, 0.85, 0.0)),
+                (4000.0, rgb(1.0, 0.0, 0.0)),
+            ]),
+            // Degrees of tilt: green upright, amber leaning, red near roll-over.
+            tip_gradient: Gradient::new(vec![
+                (0.0, rgb(0.0, 1.0, 0.0)),
+                (25.0, rgb(1.0, 0.85, 0.0)),
+                (45.0, rgb(1.0, 0.0, 0.0)),
+            ]),
+
+            voice: voice,
+
+            osd: osd::OsdConfig::new(),
+
+            recorder: None,
+            player: None,
+            transmit: true,
+            playback_time: 0.0,
+            playback_speed: 1.0,
+            playback_cursor: 0,
+
             image_map: conrod::image::Map::new(),
         }
     }
 
+    /// Enter replay mode: load the black box from `mission_folder`, stop
+    /// transmitting, and rewind to the start. The gauges, RPM and command
+    /// history will reconstruct as the recorded packets feed back through
+    /// `handle_packet`.
+    pub fn load_replay(&mut self, mission_folder: &str) {
+        match Player::load(mission_folder) {
+            Ok(player) => {
+                self.player = Some(player);
+                self.transmit = false;
+                self.recorder = None;
+                self.playback_time = 0.0;
+                self.playback_cursor = 0;
+            },
+            Err(e) => println!("WARNING: could not load replay {}: {}", mission_folder, e),
+        }
+    }
+
+    /// Feed any recorded inbound packets now due, advancing the playback clock
+    /// by `dt` seconds scaled by the speed multiplier.
+    fn advance_replay(&mut self, dt: f64) {
+        self.playback_time += dt * 1000.0 * self.playback_speed.max(0.0) as f64;
+
+        let mut due = Vec::new();
+        if let Some(ref player) = self.player {
+            while self.playback_cursor < player.len() {
+                let &(t, ref payload) = player.event(self.playback_cursor);
+                if (t as f64) <= self.playback_time {
+                    due.push(payload.clone());
+                    self.playback_cursor += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        for packet in due {
+            self.handle_packet(packet);
+        }
+    }
+
+    /// Move the replay cursor to `t_ms`. Scrubbing backwards rewinds to the
+    /// start so the reconstructed state is rebuilt from scratch on the way to
+    /// the new position.
+    fn scrub_replay(&mut self, t_ms: f64) {
+        if t_ms < self.playback_time {
+            self.playback_cursor = 0;
+        }
+        self.playback_time = t_ms;
+    }
+
     pub fn update(&mut self, dt: f64) {
+        // In replay, advance the recorded timeline instead of driving live.
+        if self.player.is_some() {
+            self.advance_replay(dt);
+        }
+
+        let dt_secs = dt;
         let dt = dt as f32;
 
         self.f_pan += self.f_panning*180.0*dt; // 180 degrees per second
         self.f_tilt += self.f_tilting*90.0*dt; // 90 degrees per second
 
+        // The autopilot, when engaged, drives the tracks directly; otherwise
+        // the manual keyboard ramp owns them.
+        if self.autopilot.engaged() {
+            self.update_autopilot(dt_secs);
+        } else {
+            self.update_drive(dt);
+        }
+
+        // When closed-loop control is on, drive the motor command from the
+        // measured RPM rather than sending the slider setpoint raw.
+        if self.pid_enabled {
+            self.update_pid(dt);
+        }
+
+        self.update_intervalometer();
+
         self.flush_out_queue();
     }
 
+    /// Differential-drive mixer: combine the held-key throttle and steering
+    /// inputs into the two tracks the way PX4's throttle/roll mixer does -
+    /// `l = throttle + steering`, `r = throttle - steering`, normalized so a
+    /// simultaneous full throttle-and-turn never clips a track past full scale
+    /// - then slew-rate limit the commanded RPM so motion ramps instead of
+    /// slamming the motors (no wheel slip or current spikes). The slewed values
+    /// feed the 5-RPM dead-band in `try_update_*`, which keeps this from
+    /// flooding the socket every frame.
+    fn update_drive(&mut self, dt: f32) {
+        // Peak RPM change allowed per second per track (the slew rate).
+        const DRIVE_SLEW: f32 = 200.0;
+        let max = 100.0 * self.motor_speed;
+
+        // Throttle and steering in [-1, 1]. A latched cruise setpoint stands in
+        // for the throttle when no drive key is held.
+        let throttle = if self.drive_fwd_input != 0.0 {
+            self.drive_fwd_input
+        } else if let Some(cruise) = self.cruise {
+            if max > 0.0 { (cruise / max).max(-1.0).min(1.0) } else { 0.0 }
+        } else {
+            0.0
+        };
+        let steering = self.drive_turn_input;
+
+        // Mix, then normalize by the larger track so neither exceeds unity
+        // before scaling to RPM - hold Up+Left for a gentle arc rather than a
+        // pivot.
+        let mut l = throttle + steering;
+        let mut r = throttle - steering;
+        let peak = l.abs().max(r.abs());
+        if peak > 1.0 {
+            l /= peak;
+            r /= peak;
+        }
+
+        let step = DRIVE_SLEW * dt;
+        self.drive_l = ramp_toward(self.drive_l, l * max, step);
+        self.drive_r = ramp_toward(self.drive_r, r * max, step);
+
+        self.try_update_l_rpm(self.drive_l);
+        self.try_update_r_rpm(self.drive_r);
+    }
+
+    /// Step the waypoint autopilot and apply its command. The heading is taken
+    /// from the fused IMU attitude and the fix from the last GPS packet, both
+    /// of which may be absent; the autopilot holds then brakes on loss. Drive
+    /// commands take the same per-track setpoint path as the manual drive; a
+    /// brake zeroes both tracks and sends the brake, as Stop does.
+    fn update_autopilot(&mut self, dt: f64) {
+        let fix = match (self.latitude, self.longitude) {
+            (Some(lat), Some(lon)) => Some((lat, lon)),
+            _ => None,
+        };
+        let heading = self.pitch_roll_heading.map(|(_, _, h)| h);
+
+        match self.autopilot.update(fix, heading, self.motor_speed, dt) {
+            AutopilotCmd::Drive(l_rpm, r_rpm) => {
+                // Route through the shared setpoint path so the same 5-RPM
+                // dead-band and closed-loop guard that gate the manual drive
+                // also gate the autopilot - no socket flooding, and the PID
+                // owns the outgoing command when it is enabled.
+                self.try_update_l_rpm(l_rpm);
+                self.try_update_r_rpm(r_rpm);
+            },
+            AutopilotCmd::Brake => {
+                self.l_rpm = 0.0;
+                self.r_rpm = 0.0;
+                self.send_l_rpm();
+                self.send_r_rpm();
+                self.send_brake();
+            },
+            AutopilotCmd::Idle => { },
+        }
+    }
+
+    /// Closed-loop step: run each side's PID from the measured wheel RPM
+    /// toward the slider setpoint and push the resulting command, resending a
+    /// side only when its command moves by more than 1 RPM so we don't flood
+    /// the socket.
+    fn update_pid(&mut self, dt: f32) {
+        let l_cmd = self.l_pid.update(self.l_rpm, self.measured_l_rpm, dt)
+            .max(-self.max_rpm).min(self.max_rpm);
+        let r_cmd = self.r_pid.update(self.r_rpm, self.measured_r_rpm, dt)
+            .max(-self.max_rpm).min(self.max_rpm);
+
+        if (l_cmd - self.l_cmd).abs() > 1.0 {
+            self.l_cmd = l_cmd;
+            self.send_l_cmd();
+        }
+        if (r_cmd - self.r_cmd).abs() > 1.0 {
+            self.r_cmd = r_cmd;
+            self.send_r_cmd();
+        }
+    }
+
+    /// Timed automated snapshots, modeled on PX4's camera trigger module
+    /// (`hrt_call_every`): every `intervalometer_interval_ms` an engage packet
+    /// goes out and `intervalometer_activation_ms` later a matching disengage
+    /// follows, so the shutter holds open for a fixed duration instead of a
+    /// single pulse. The pending disengage is tracked independently of the
+    /// enabled flag so toggling off mid-hold still closes the shutter.
+    fn update_intervalometer(&mut self) {
+        if let Some(fire_at) = self.pending_disengage {
+            if time::now() >= fire_at {
+                self.send_camera_trigger(false);
+                self.pending_disengage = None;
+            }
+        }
+
+        if !self.intervalometer_enabled {
+            return;
+        }
+
+        let since_last = (time::now() - self.last_snapshot_time).num_milliseconds();
+        if since_last >= self.intervalometer_interval_ms as i64 {
+            self.last_snapshot_time = time::now();
+            self.snapshot_count += 1;
+            self.send_camera_trigger(true);
+            self.pending_disengage = Some(time::now() + time::Duration::milliseconds(self.intervalometer_activation_ms as i64));
+            self.want_snapshot = true;
+            self.command_history.push(format!("Snapshot #{} (auto)", self.snapshot_count));
+        }
+    }
+
+    /// Flip the intervalometer on or off. Enabling fires the first shot right
+    /// away rather than waiting a full interval, matching the immediate
+    /// response of the manual Snapshot button.
+    pub fn toggle_intervalometer(&mut self) {
+        self.intervalometer_enabled = !self.intervalometer_enabled;
+        if self.intervalometer_enabled {
+            self.last_snapshot_time = time::now() - time::Duration::milliseconds(self.intervalometer_interval_ms as i64);
+        }
+    }
+
     pub fn draw_ui<'a>(&mut self, c: Context,
                           g: &mut gfx_graphics::GfxGraphics<'a, gfx_device_gl::Resources, gfx_device_gl::CommandBuffer>,
                           glyph_cache: &mut conrod::backend::piston_window::GlyphCache, ui: &mut conrod_config::Ui) {
@@ -181,13 +613,23 @@ impl NavigationUi {
                                              &self.image_map,
                                              |img| img);
 
-        // Draw other stuff
-        self.pitch.draw(c.trans(20.0, 215.0), g);
-        self.roll.draw(c.trans(170.0, 215.0), g);
+        // Draw other stuff - the pitch and roll pointers redden toward the
+        // tip-over angle so an unsafe attitude is obvious at a glance.
+        let pitch_color = self.tip_gradient.sample_rgba(self.pitch.angle().abs() as f32);
+        let roll_color = self.tip_gradient.sample_rgba(self.roll.angle().abs() as f32);
+        self.pitch.draw(c.trans(20.0, 215.0), g, pitch_color);
+        self.roll.draw(c.trans(170.0, 215.0), g, roll_color);
         self.heading.draw(c.trans(320.0, 215.0), g);
+
+        // Live 3D attitude model, fed by ATTITUDE packets through the same
+        // channel as the other telemetry.
+        self.attitude_mesh.draw(self.attitude, (150.0, 150.0), c.trans(20.0, 345.0), g);
     }
 
     pub fn set_widgets(&mut self, ui: &mut conrod_config::UiCell) {
+        let scale = UiScale::compute(ui.win_w, ui.win_h, self.scale_policy);
+        let vw = UiScale::REF_W;
+        let vh = UiScale::REF_H;
         use std::cmp;
 
         let time_now = time::now();
@@ -199,18 +641,47 @@ impl NavigationUi {
 
         // Local time
         Text::new(format!("{}", time_now.strftime("Local  %x  %X").unwrap()).as_str())
-            .x_y((-ui.win_w / 2.0) + 100.0, (ui.win_h / 2.0) - 10.0)
+            .x_y(scale.x((-vw / 2.0) + 100.0), scale.y((vh / 2.0) - 10.0))
             .font_size(16)
             .color(self.bg_color.plain_contrast())
             .set(LOCAL_TIME, ui);
 
         // UTC time
         Text::new(format!("{}", time_now.to_utc().strftime("%Z  %x  %X").unwrap()).as_str())
-            .x_y((-ui.win_w / 2.0) + 104.0, (ui.win_h / 2.0) - 30.0)
+            .x_y(scale.x((-vw / 2.0) + 104.0), scale.y((vh / 2.0) - 30.0))
             .font_size(16)
             .color(self.bg_color.plain_contrast())
             .set(UTC_TIME, ui);
 
+        // Telemetry-health alarm banner: worst-first list of stale/invalid
+        // sources, coloured by the worst severity raised. Hidden when healthy.
+        let events = self.watchdog.events();
+        if let Some(worst) = events.iter().map(|e| e.severity).max_by_key(|s| s.rank()) {
+            let text = events.iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join("   ");
+            let c = worst.color();
+            Text::new(text.as_str())
+                .x_y(scale.x(0.0), scale.y((vh / 2.0) - 10.0))
+                .font_size(22)
+                .color(conrod::color::rgba(c[0], c[1], c[2], c[3]))
+                .set(HEALTH_BANNER, ui);
+        }
+
+        // Safe-operating-range alarm banner: active WRPM channels latched
+        // until they recover past the hysteresis margin, plus a standing
+        // entry for a malformed WRPM packet. Hidden when nominal.
+        let alarms = self.monitor.active_alarms();
+        if !alarms.is_empty() {
+            let wc = self.monitor.worst().color();
+            Text::new(format!("ALARM  {}", alarms.join("    ")).as_str())
+                .x_y(scale.x(0.0), scale.y((vh / 2.0) - 32.0))
+                .font_size(20)
+                .color(conrod::color::rgba(wc[0], wc[1], wc[2], wc[3]))
+                .set(ALARM_BANNER, ui);
+        }
+
         // Mission time label
         let mission_time =
             match self.mission_time {
@@ -228,7 +699,7 @@ impl NavigationUi {
         let minutes = total_minutes - total_hours*60;
         let seconds = total_seconds - total_minutes*60;
         Text::new(format!("Mission Time: {}:{}:{}:{}", days, hours, minutes, seconds).as_str())
-            .x_y((-ui.win_w / 2.0) + 150.0, (ui.win_h / 2.0) - 70.0)
+            .x_y(scale.x((-vw / 2.0) + 150.0), scale.y((vh / 2.0) - 70.0))
             .font_size(20)
             .color(self.bg_color.plain_contrast())
             .set(MISSION_TIME_LABEL, ui);
@@ -240,8 +711,8 @@ impl NavigationUi {
                 MissionTime::Running(_, _) => "Pause",
             };
         if Button::new()
-            .w_h(100.0, 30.0)
-            .x_y((-ui.win_w / 2.0) + 55.0, (ui.win_h / 2.0) - 100.0)
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x((-vw / 2.0) + 55.0), scale.y((vh / 2.0) - 100.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label(mission_start_text)
@@ -252,15 +723,24 @@ impl NavigationUi {
                 MissionTime::Paused(current_time) => {
                     self.mission_time = MissionTime::Running(time::now(), current_time);
 
-                    self.vid0_t.send(VideoMsg::Start(format!("mission_data/{}/forward{}.mp4", self.mission_folder, self.vid_num)));
-                    self.vid1_t.send(VideoMsg::Start(format!("mission_data/{}/reverse{}.mkv", self.mission_folder, self.vid_num)));
-                    self.vid2_t.send(VideoMsg::Start(format!("mission_data/{}/hazard{}.mkv", self.mission_folder, self.vid_num)));
+                    // Start the black box alongside the video capture.
+                    match Recorder::open(&self.mission_folder) {
+                        Ok(recorder) => self.recorder = Some(recorder),
+                        Err(e) => println!("WARNING: could not open black box: {}", e),
+                    }
+
+                    self.vid0_t.send(VideoMsg::Start(format!("mission_data/{}/forward{}.mp4", self.mission_folder, self.vid_num), RecordMode::Single));
+                    self.vid1_t.send(VideoMsg::Start(format!("mission_data/{}/reverse{}.mkv", self.mission_folder, self.vid_num), RecordMode::Single));
+                    self.vid2_t.send(VideoMsg::Start(format!("mission_data/{}/hazard{}.mkv", self.mission_folder, self.vid_num), RecordMode::Single));
 
                     self.vid_num += 1;
                 },
                 MissionTime::Running(start_time, extra_time) => {
                     self.mission_time = MissionTime::Paused((time::now() - start_time) + extra_time);
 
+                    // Close the black box with the video capture.
+                    self.recorder = None;
+
                     self.vid0_t.send(VideoMsg::Stop);
                     self.vid1_t.send(VideoMsg::Stop);
                     self.vid2_t.send(VideoMsg::Stop);
@@ -270,8 +750,8 @@ impl NavigationUi {
 
         // Mission reset button
         if Button::new()
-            .w_h(100.0, 30.0)
-            .x_y((-ui.win_w / 2.0) + 160.0, (ui.win_h / 2.0) - 100.0)
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x((-vw / 2.0) + 160.0), scale.y((vh / 2.0) - 100.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Reset")
@@ -281,9 +761,62 @@ impl NavigationUi {
             self.mission_time = MissionTime::Paused(time::Duration::zero());
         }
 
+        // Cycle the layout scaling policy (stretch / letterbox / 1:1).
+        if Button::new()
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x((-vw / 2.0) + 265.0), scale.y((vh / 2.0) - 135.0))
+            .rgb(0.5, 0.5, 0.7)
+            .border(1.0)
+            .label(self.scale_policy.label())
+            .set(SCALE_TOGGLE, ui)
+            .was_clicked()
+        {
+            self.scale_policy = self.scale_policy.next();
+        }
+
+        // Mission replay: load the current mission's black box, then scrub and
+        // speed-control the recorded timeline.
+        let replay_label = if self.player.is_some() { "Replaying" } else { "Replay" };
+        if Button::new()
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x((-vw / 2.0) + 265.0), scale.y((vh / 2.0) - 100.0))
+            .rgb(0.3, 0.6, 0.8)
+            .border(1.0)
+            .label(replay_label)
+            .set(REPLAY_BUTTON, ui)
+            .was_clicked()
+        {
+            let folder = self.mission_folder.clone();
+            self.load_replay(&folder);
+        }
+
+        if self.player.is_some() {
+            if let Some(speed) = Slider::new(self.playback_speed, 0.0, 8.0)
+                .w_h(scale.w(150.0), scale.h(20.0))
+                .x_y(scale.x((-vw / 2.0) + 120.0), scale.y((vh / 2.0) - 125.0))
+                .rgb(0.3, 0.6, 0.8).border(1.0)
+                .label(format!("Speed x{:.1}", self.playback_speed).as_str()).label_color(WHITE)
+                .set(REPLAY_SPEED_SLIDER, ui)
+            {
+                self.playback_speed = speed;
+            }
+
+            let duration = self.player.as_ref().map(|p| p.duration_ms()).unwrap_or(0) as f32;
+            if let Some(t) = Slider::new(self.playback_time as f32, 0.0, duration.max(1.0))
+                .w_h(scale.w(300.0), scale.h(20.0))
+                .x_y(scale.x((-vw / 2.0) + 195.0), scale.y((vh / 2.0) - 150.0))
+                .rgb(0.3, 0.6, 0.8).border(1.0)
+                .label(format!("{:.1}s / {:.1}s", self.playback_time / 1000.0, duration as f64 / 1000.0).as_str())
+                .label_color(WHITE)
+                .set(REPLAY_SCRUB_SLIDER, ui)
+            {
+                self.scrub_replay(t as f64);
+            }
+        }
+
         // Time delay
         Text::new("Time Delay:")
-            .x_y((-ui.win_w / 2.0) + 70.0, (ui.win_h / 2.0) - 150.0)
+            .x_y(scale.x((-vw / 2.0) + 70.0), scale.y((vh / 2.0) - 150.0))
             .font_size(18)
             .color(self.bg_color.plain_contrast())
             .set(TIME_DELAY, ui);
@@ -291,8 +824,8 @@ impl NavigationUi {
         let mut new_delay = false;
         for event in TextBox::new(&mut self.delay_str)
             .font_size(16)
-            .w_h(50.0, 20.0)
-            .x_y((-ui.win_w / 2.0) + 150.0, (ui.win_h / 2.0) - 150.0)
+            .w_h(scale.w(50.0), scale.h(20.0))
+            .x_y(scale.x((-vw / 2.0) + 150.0), scale.y((vh / 2.0) - 150.0))
             .border(1.0)
             .border_color(self.bg_color.invert().plain_contrast())
             .color(self.bg_color.invert())
@@ -305,11 +838,65 @@ impl NavigationUi {
             }
         }
 
+        // Degraded-link simulator: loss %, jitter ms and bandwidth bytes/sec,
+        // sharing the Time Delay row, with a live queue/throughput readout.
+        {
+            let text_color = self.bg_color.plain_contrast();
+            let box_border = self.bg_color.invert().plain_contrast();
+            let box_fill = self.bg_color.invert();
+
+            let fields = [
+                ("Loss %:", LINK_LOSS_LABEL, LINK_LOSS_VALUE, 230.0),
+                ("Jitter ms:", LINK_JITTER_LABEL, LINK_JITTER_VALUE, 400.0),
+                ("BW B/s:", LINK_BANDWIDTH_LABEL, LINK_BANDWIDTH_VALUE, 570.0),
+            ];
+            for &(caption, label_id, value_id, x) in fields.iter() {
+                Text::new(caption)
+                    .x_y(scale.x((-vw / 2.0) + x), scale.y((vh / 2.0) - 150.0))
+                    .font_size(14)
+                    .color(text_color)
+                    .set(label_id, ui);
+                let buf = match value_id {
+                    LINK_LOSS_VALUE => &mut self.loss_str,
+                    LINK_JITTER_VALUE => &mut self.jitter_str,
+                    _ => &mut self.bandwidth_str,
+                };
+                let mut entered: Option<f64> = None;
+                for event in TextBox::new(buf)
+                    .font_size(16)
+                    .w_h(scale.w(55.0), scale.h(20.0))
+                    .x_y(scale.x((-vw / 2.0) + x + 55.0), scale.y((vh / 2.0) - 150.0))
+                    .border(1.0)
+                    .border_color(box_border)
+                    .color(box_fill)
+                    .set(value_id, ui)
+                {
+                    if let widget::text_box::Event::Enter = event {
+                        entered = buf.parse::<f64>().ok();
+                    }
+                }
+                if let Some(v) = entered {
+                    match value_id {
+                        LINK_LOSS_VALUE => self.link.loss_pct = v,
+                        LINK_JITTER_VALUE => self.link.jitter_ms = v,
+                        _ => self.link.bandwidth_bps = v,
+                    }
+                }
+            }
+
+            Text::new(&format!("queued {} B  @ {:.0} B/s",
+                               self.queued_bytes(), self.link.throughput_bps()))
+                .x_y(scale.x((-vw / 2.0) + 285.0), scale.y((vh / 2.0) - 170.0))
+                .font_size(12)
+                .color(text_color)
+                .set(LINK_READOUT, ui);
+        }
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // IMU section
 
         Text::new("IMU")
-            .x_y((-ui.win_w / 2.0) + 100.0, (ui.win_h / 2.0) - 190.0)
+            .x_y(scale.x((-vw / 2.0) + 100.0), scale.y((vh / 2.0) - 190.0))
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(IMU_LABEL, ui);
@@ -327,13 +914,13 @@ impl NavigationUi {
         // IMU pitch
 
         Text::new(format!("Pitch").as_str())
-            .x_y((-ui.win_w / 2.0) + 40.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 40.0), scale.y((vh / 2.0) - 350.0))
             .font_size(18)
             .color(self.bg_color.plain_contrast())
             .set(IMU_PITCH_LABEL, ui);
 
         Text::new(pitch.as_str())
-            .x_y((-ui.win_w / 2.0) + 120.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 120.0), scale.y((vh / 2.0) - 350.0))
             .font_size(16)
             .color(imu_color)
             .set(IMU_PITCH_VALUE, ui);
@@ -341,13 +928,13 @@ impl NavigationUi {
         // IMU roll
 
         Text::new(format!("Roll").as_str())
-            .x_y((-ui.win_w / 2.0) + 190.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 190.0), scale.y((vh / 2.0) - 350.0))
             .font_size(18)
             .color(self.bg_color.plain_contrast())
             .set(IMU_ROLL_LABEL, ui);
 
         Text::new(roll.as_str())
-            .x_y((-ui.win_w / 2.0) + 250.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 250.0), scale.y((vh / 2.0) - 350.0))
             .font_size(16)
             .color(imu_color)
             .set(IMU_ROLL_VALUE, ui);
@@ -355,13 +942,13 @@ impl NavigationUi {
         // IMU heading
 
         Text::new("Heading")
-            .x_y((-ui.win_w / 2.0) + 340.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 340.0), scale.y((vh / 2.0) - 350.0))
             .font_size(18)
             .color(self.bg_color.plain_contrast())
             .set(IMU_HEADING_LABEL, ui);
 
         Text::new(heading.as_str())
-            .x_y((-ui.win_w / 2.0) + 420.0, (ui.win_h / 2.0) - 350.0)
+            .x_y(scale.x((-vw / 2.0) + 420.0), scale.y((vh / 2.0) - 350.0))
             .font_size(16)
             .color(imu_color)
             .set(IMU_HEADING_VALUE, ui);
@@ -370,7 +957,7 @@ impl NavigationUi {
         // GPS section
 
         Text::new("GPS")
-            .x_y((-ui.win_w / 2.0) + 400.0, (ui.win_h / 2.0) - 10.0)
+            .x_y(scale.x((-vw / 2.0) + 400.0), scale.y((vh / 2.0) - 10.0))
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(GPS_LABEL, ui);
@@ -385,7 +972,7 @@ impl NavigationUi {
                 None => ("NO DATA".to_string(), rgb(1.0, 0.0, 0.0)),
             };
         Text::new(latitude.as_str())
-            .x_y((-ui.win_w / 2.0) + 420.0, (ui.win_h / 2.0) - 35.0)
+            .x_y(scale.x((-vw / 2.0) + 420.0), scale.y((vh / 2.0) - 35.0))
             .font_size(16)
             .color(latitude_color)
             .set(LATITUDE_LABEL, ui);
@@ -401,7 +988,7 @@ impl NavigationUi {
                 None => ("NO DATA".to_string(), rgb(1.0, 0.0, 0.0)),
             };
         Text::new(longitude.as_str())
-            .x_y((-ui.win_w / 2.0) + 420.0, (ui.win_h / 2.0) - 55.0)
+            .x_y(scale.x((-vw / 2.0) + 420.0), scale.y((vh / 2.0) - 55.0))
             .font_size(16)
             .color(longitude_color)
             .set(LONGITUDE_LABEL, ui);
@@ -410,12 +997,13 @@ impl NavigationUi {
         let (speed, speed_color) =
             match self.speed {
                 Some(speed) => {
-                    (format!("{0:.2} m/s", speed), rgb(0.0, 1.0, 0.0))
+                    (format!("{0:.2} m/s", speed),
+                     self.speed_gradient.sample(speed.abs() as f32))
                 },
                 None => ("NO DATA".to_string(), rgb(1.0, 0.0, 0.0)),
             };
         Text::new(speed.as_str())
-            .x_y((-ui.win_w / 2.0) + 400.0, (ui.win_h / 2.0) - 75.0)
+            .x_y(scale.x((-vw / 2.0) + 400.0), scale.y((vh / 2.0) - 75.0))
             .font_size(16)
             .color(speed_color)
             .set(SPEED_LABEL, ui);
@@ -424,12 +1012,13 @@ impl NavigationUi {
         let (altitude, altitude_color) =
             match self.altitude {
                 Some(alt) => {
-                    (format!("{0:.2} m", alt), rgb(0.0, 1.0, 0.0))
+                    (format!("{0:.2} m", alt),
+                     self.altitude_gradient.sample(alt.abs() as f32))
                 },
                 None => ("NO DATA".to_string(), rgb(1.0, 0.0, 0.0)),
             };
         Text::new(altitude.as_str())
-            .x_y((-ui.win_w / 2.0) + 400.0, (ui.win_h / 2.0) - 95.0)
+            .x_y(scale.x((-vw / 2.0) + 400.0), scale.y((vh / 2.0) - 95.0))
             .font_size(16)
             .color(altitude_color)
             .set(ALTITUDE_LABEL, ui);
@@ -443,43 +1032,152 @@ impl NavigationUi {
                 None => ("NO DATA".to_string(), rgb(1.0, 0.0, 0.0)),
             };
         Text::new(angle.as_str())
-            .x_y((-ui.win_w / 2.0) + 400.0, (ui.win_h / 2.0) - 115.0)
+            .x_y(scale.x((-vw / 2.0) + 400.0), scale.y((vh / 2.0) - 115.0))
             .font_size(16)
             .color(angle_color)
             .set(ANGLE_LABEL, ui);
 
         ////////////////////////////////////////////////////////////////////////////////////////////
 
-        // Left RPM slider
+        // Left RPM slider - label shades toward red as |rpm| nears the max.
+        let l_rpm_color = self.rpm_gradient.sample(self.l_rpm.abs() / self.max_rpm);
         if let Some(new_rpm) = Slider::new(self.l_rpm, -self.max_rpm, self.max_rpm)
-            .w_h(150.0, 30.0)
-            .x_y(275.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 145.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 145.0))
             .rgb(0.5, 0.3, 0.6)
             .border(1.0)
             .label("L Motor")
-            .label_color(WHITE)
+            .label_color(l_rpm_color)
             .set(L_RPM_SLIDER, ui)
         {
             self.try_update_l_rpm(new_rpm);
         }
 
-        // Right RPM slider
+        // Right RPM slider - label shades toward red as |rpm| nears the max.
+        let r_rpm_color = self.rpm_gradient.sample(self.r_rpm.abs() / self.max_rpm);
         if let Some(new_rpm) = Slider::new(self.r_rpm, -self.max_rpm, self.max_rpm)
-            .w_h(150.0, 30.0)
-            .x_y(275.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 185.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 185.0))
             .rgb(0.5, 0.3, 0.6)
             .border(1.0)
             .label("R Motor")
-            .label_color(WHITE)
+            .label_color(r_rpm_color)
             .set(R_RPM_SLIDER, ui)
         {
             self.try_update_r_rpm(new_rpm);
         }
 
+        // Measured WRPM feedback, coloured by its safe-operating-range band.
+        let l_fbk_color = self.monitor.band_of("L RPM").color();
+        Text::new(format!("fbk {:.0}", self.monitor.value_of("L RPM")).as_str())
+            .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 160.0))
+            .font_size(14)
+            .color(conrod::color::rgba(l_fbk_color[0], l_fbk_color[1], l_fbk_color[2], l_fbk_color[3]))
+            .set(L_RPM_MEASURED_LABEL, ui);
+
+        let r_fbk_color = self.monitor.band_of("R RPM").color();
+        Text::new(format!("fbk {:.0}", self.monitor.value_of("R RPM")).as_str())
+            .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 200.0))
+            .font_size(14)
+            .color(conrod::color::rgba(r_fbk_color[0], r_fbk_color[1], r_fbk_color[2], r_fbk_color[3]))
+            .set(R_RPM_MEASURED_LABEL, ui);
+
+        // Collapsible "Tuning" section for the closed-loop rate controller.
+        let tuning_label = if self.tuning_open { "Tuning v" } else { "Tuning >" };
+        if Button::new()
+            .w_h(scale.w(150.0), scale.h(25.0))
+            .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 220.0))
+            .rgb(0.3, 0.3, 0.5)
+            .border(1.0)
+            .label(tuning_label)
+            .label_color(WHITE)
+            .set(TUNING_TOGGLE, ui)
+            .was_clicked()
+        {
+            self.tuning_open = !self.tuning_open;
+        }
+
+        if self.tuning_open {
+            let enable_label = if self.pid_enabled { "PID: ON" } else { "PID: OFF" };
+            let enable_rgb = if self.pid_enabled { (0.3, 0.8, 0.3) } else { (0.6, 0.6, 0.6) };
+            if Button::new()
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 250.0))
+                .rgb(enable_rgb.0, enable_rgb.1, enable_rgb.2)
+                .border(1.0)
+                .label(enable_label)
+                .label_color(WHITE)
+                .set(PID_ENABLE_BUTTON, ui)
+                .was_clicked()
+            {
+                self.pid_enabled = !self.pid_enabled;
+                if !self.pid_enabled {
+                    self.l_pid.reset();
+                    self.r_pid.reset();
+                }
+            }
+
+            // Gains edit both wheels together; they normally share tuning.
+            if let Some(kp) = Slider::new(self.l_pid.kp_flat(), 0.0, 2.0)
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 280.0))
+                .rgb(0.5, 0.3, 0.6).border(1.0)
+                .label(format!("Kp {:.2}", self.l_pid.kp_flat()).as_str()).label_color(WHITE)
+                .set(KP_SLIDER, ui)
+            {
+                self.l_pid.set_kp(kp);
+                self.r_pid.set_kp(kp);
+            }
+
+            if let Some(ki) = Slider::new(self.l_pid.ki, 0.0, 1.0)
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 310.0))
+                .rgb(0.5, 0.3, 0.6).border(1.0)
+                .label(format!("Ki {:.2}", self.l_pid.ki).as_str()).label_color(WHITE)
+                .set(KI_SLIDER, ui)
+            {
+                self.l_pid.ki = ki;
+                self.r_pid.ki = ki;
+            }
+
+            if let Some(kd) = Slider::new(self.l_pid.kd, 0.0, 0.5)
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 340.0))
+                .rgb(0.5, 0.3, 0.6).border(1.0)
+                .label(format!("Kd {:.3}", self.l_pid.kd).as_str()).label_color(WHITE)
+                .set(KD_SLIDER, ui)
+            {
+                self.l_pid.kd = kd;
+                self.r_pid.kd = kd;
+            }
+
+            if let Some(kf) = Slider::new(self.l_pid.kf, 0.0, 2.0)
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 370.0))
+                .rgb(0.5, 0.3, 0.6).border(1.0)
+                .label(format!("Kf {:.2}", self.l_pid.kf).as_str()).label_color(WHITE)
+                .set(KF_SLIDER, ui)
+            {
+                self.l_pid.kf = kf;
+                self.r_pid.kf = kf;
+            }
+
+            if let Some(i_limit) = Slider::new(self.l_pid.i_limit, 0.0, 100.0)
+                .w_h(scale.w(150.0), scale.h(25.0))
+                .x_y(scale.x(275.0 - (vw / 2.0)), scale.y((vh / 2.0) - 400.0))
+                .rgb(0.5, 0.3, 0.6).border(1.0)
+                .label(format!("I limit {:.0}", self.l_pid.i_limit).as_str()).label_color(WHITE)
+                .set(I_LIMIT_SLIDER, ui)
+            {
+                self.l_pid.i_limit = i_limit;
+                self.r_pid.i_limit = i_limit;
+            }
+        }
+
         // Stop button
         if Button::new()
-            .w_h(100.0, 30.0)
-            .x_y(455.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 145.0)
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x(455.0 - (vw / 2.0)), scale.y((vh / 2.0) - 145.0))
             .rgb(1.0, 0.0, 0.0)
             .border(1.0)
             .label("Stop")
@@ -495,8 +1193,8 @@ impl NavigationUi {
 
         // Motor speed slider
         if let Some(new_speed) = Slider::new(self.motor_speed, 0.0, 1.0)
-            .w_h(150.0, 30.0)
-            .x_y(435.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 185.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x(435.0 - (vw / 2.0)), scale.y((vh / 2.0) - 185.0))
             .rgb(0.5, 0.3, 0.6)
             .border(1.0)
             .label("Motor Speed")
@@ -509,8 +1207,8 @@ impl NavigationUi {
         // Camera pan slider
         self.f_pan = self.f_pan.max(0.0).min(180.0);
         if let Some(new_pan) = Slider::new(self.f_pan, 0.0, 180.0)
-            .w_h(150.0, 30.0)
-            .x_y((ui.win_w / 2.0) - 425.0, (ui.win_h / 2.0) - 425.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 425.0), scale.y((vh / 2.0) - 425.0))
             .rgb(0.5, 0.3, 0.6)
             .border(1.0)
             .label("Pan")
@@ -523,8 +1221,8 @@ impl NavigationUi {
         // Camera tilt slider
         self.f_tilt = self.f_tilt.max(60.0).min(180.0);
         if let Some(new_tilt) = Slider::new(self.f_tilt, 60.0, 180.0)
-            .w_h(150.0, 30.0)
-            .x_y((ui.win_w / 2.0) - 270.0, (ui.win_h / 2.0) - 425.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 270.0), scale.y((vh / 2.0) - 425.0))
             .rgb(0.5, 0.3, 0.6)
             .border(1.0)
             .label("Tilt")
@@ -535,8 +1233,8 @@ impl NavigationUi {
         }
 
         if Button::new()
-            .w_h(100.0, 30.0)
-            .x_y((ui.win_w / 2.0) - 350.0, (ui.win_h / 2.0) - 470.0)
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 350.0), scale.y((vh / 2.0) - 470.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Snapshot")
@@ -546,16 +1244,80 @@ impl NavigationUi {
             self.want_snapshot = true;
         }
 
+        // Intervalometer: toggles automated periodic snapshots, with the
+        // interval and shutter-hold duration tuned via the two sliders below
+        // and the running shot count visible in the command history.
+        let intervalometer_label = if self.intervalometer_enabled {
+            format!("Intervalometer ({})", self.snapshot_count)
+        } else {
+            "Intervalometer".to_string()
+        };
+        let intervalometer_rgb = if self.intervalometer_enabled { (0.8, 0.5, 0.3) } else { (0.3, 0.6, 0.8) };
+        if Button::new()
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 350.0), scale.y((vh / 2.0) - 510.0))
+            .rgb(intervalometer_rgb.0, intervalometer_rgb.1, intervalometer_rgb.2)
+            .border(1.0)
+            .label(intervalometer_label.as_str())
+            .set(INTERVALOMETER_BUTTON, ui)
+            .was_clicked()
+        {
+            self.toggle_intervalometer();
+        }
+
+        if let Some(new_interval) = Slider::new(self.intervalometer_interval_ms, 500.0, 60000.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 350.0), scale.y((vh / 2.0) - 545.0))
+            .rgb(0.5, 0.3, 0.6)
+            .border(1.0)
+            .label(format!("Interval {}ms", self.intervalometer_interval_ms as i32).as_str())
+            .label_color(WHITE)
+            .set(INTERVALOMETER_INTERVAL_SLIDER, ui)
+        {
+            self.intervalometer_interval_ms = new_interval;
+        }
+
+        if let Some(new_activation) = Slider::new(self.intervalometer_activation_ms, 50.0, 5000.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x((vw / 2.0) - 350.0), scale.y((vh / 2.0) - 580.0))
+            .rgb(0.5, 0.3, 0.6)
+            .border(1.0)
+            .label(format!("Hold {}ms", self.intervalometer_activation_ms as i32).as_str())
+            .label_color(WHITE)
+            .set(INTERVALOMETER_ACTIVATION_SLIDER, ui)
+        {
+            self.intervalometer_activation_ms = new_activation;
+        }
+
+        // Waypoint autopilot: engage loads the route file and hands the motors
+        // to the navigator; the label shows the remaining waypoint count.
+        let (autopilot_label, autopilot_rgb) = if self.autopilot.engaged() {
+            (format!("Auto ({})", self.autopilot.remaining()), (0.8, 0.5, 0.3))
+        } else {
+            ("Autopilot".to_string(), (0.3, 0.6, 0.8))
+        };
+        if Button::new()
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x(455.0 - (vw / 2.0)), scale.y((vh / 2.0) - 105.0))
+            .rgb(autopilot_rgb.0, autopilot_rgb.1, autopilot_rgb.2)
+            .border(1.0)
+            .label(autopilot_label.as_str())
+            .set(AUTOPILOT_BUTTON, ui)
+            .was_clicked()
+        {
+            self.toggle_autopilot();
+        }
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // SADL
         Text::new("SADL")
-            .x_y((ui.win_w / 2.0) - 660.0, (ui.win_h / 2.0) - 465.0)
+            .x_y(scale.x((vw / 2.0) - 660.0), scale.y((vh / 2.0) - 465.0))
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(SADL_LABEL, ui);
         if Button::new()
-            .x_y((ui.win_w / 2.0) - 590.0, (ui.win_h / 2.0) - 465.0)
-            .w_h(60.0, 30.0)
+            .x_y(scale.x((vw / 2.0) - 590.0), scale.y((vh / 2.0) - 465.0))
+            .w_h(scale.w(60.0), scale.h(30.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Up")
@@ -566,8 +1328,8 @@ impl NavigationUi {
             self.send_sadl();
         }
         if Button::new()
-            .x_y((ui.win_w / 2.0) - 525.0, (ui.win_h / 2.0) - 465.0)
-            .w_h(60.0, 30.0)
+            .x_y(scale.x((vw / 2.0) - 525.0), scale.y((vh / 2.0) - 465.0))
+            .w_h(scale.w(60.0), scale.h(30.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Down")
@@ -578,10 +1340,17 @@ impl NavigationUi {
             self.send_sadl();
         }
 
+        // Link status (empty unless `frames` has given up on a command)
+        Text::new(self.link_status.as_str())
+            .x_y(scale.x(455.0 - (vw / 2.0)), scale.y((vh / 2.0) - 225.0))
+            .font_size(18)
+            .color(rgb(1.0, 0.0, 0.0))
+            .set(LINK_STATUS, ui);
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // Command section
         Text::new("Command")
-            .x_y(110.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 615.0)
+            .x_y(scale.x(110.0 - (vw / 2.0)), scale.y((vh / 2.0) - 615.0))
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(COMMAND_LABEL, ui);
@@ -590,8 +1359,8 @@ impl NavigationUi {
         for event in TextBox::new(&mut self.command)
             //.enable(self.command_mode)
             .font_size(16)
-            .w_h(320.0, 20.0)
-            .x_y(165.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 640.0)
+            .w_h(scale.w(320.0), scale.h(20.0))
+            .x_y(scale.x(165.0 - (vw / 2.0)), scale.y((vh / 2.0) - 640.0))
             .border(1.0)
             .border_color(self.bg_color.invert().plain_contrast())
             .color(self.bg_color.invert())
@@ -604,8 +1373,8 @@ impl NavigationUi {
         }
 
         if Button::new()
-            .w_h(100.0, 30.0)
-            .x_y(380.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 640.0)
+            .w_h(scale.w(100.0), scale.h(30.0))
+            .x_y(scale.x(380.0 - (vw / 2.0)), scale.y((vh / 2.0) - 640.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Send")
@@ -621,13 +1390,13 @@ impl NavigationUi {
                 false => "Real-time Mode",
             };
         Text::new(mode_label)
-            .x_y(200.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 675.0)
+            .x_y(scale.x(200.0 - (vw / 2.0)), scale.y((vh / 2.0) - 675.0))
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(MODE_LABEL, ui);
         if Button::new()
-            .w_h(150.0, 30.0)
-            .x_y(380.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 675.0)
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x(380.0 - (vw / 2.0)), scale.y((vh / 2.0) - 675.0))
             .rgb(0.3, 0.8, 0.3)
             .border(1.0)
             .label("Toggle Mode")
@@ -636,21 +1405,216 @@ impl NavigationUi {
         {
             self.command_mode = !self.command_mode;
         }
-        
+
+        // Protocol backend: legacy text packets or MAVLink COMMAND_LONG
+        // frames for the drive/camera/SADL intents.
+        let protocol_label = if self.mavlink_active { "Protocol: MAVLink" } else { "Protocol: Legacy" };
+        if Button::new()
+            .w_h(scale.w(150.0), scale.h(30.0))
+            .x_y(scale.x(380.0 - (vw / 2.0)), scale.y((vh / 2.0) - 710.0))
+            .rgb(0.3, 0.8, 0.3)
+            .border(1.0)
+            .label(protocol_label)
+            .set(PROTOCOL_TOGGLE_BUTTON, ui)
+            .was_clicked()
+        {
+            self.toggle_protocol();
+        }
+
         for (i, mut edit) in (0..self.command_history.len()).zip(TextEdit::new("")
-            .x_y(200.0 - (ui.win_w / 2.0), (ui.win_h / 2.0) - 675.0)
-            .w_h(200.0, 300.0)
+            .x_y(scale.x(200.0 - (vw / 2.0)), scale.y((vh / 2.0) - 675.0))
+            .w_h(scale.w(200.0), scale.h(300.0))
             .color(LIGHT_BLUE)
             .line_spacing(2.5)
             .set(COMMAND_HISTORY, ui))
         {
             edit = self.command_history[i].clone();
         }
+
+        self.set_param_table(ui);
+    }
+
+    /// Draw the PX4-style parameter console: a "Request All" button, up/down
+    /// scroll and one editable row per known parameter. Rows the operator has
+    /// edited but the rover has not yet acknowledged are bordered yellow; an
+    /// Enter in a value box queues a `PARAM_SET`.
+    fn set_param_table(&mut self, ui: &mut conrod_config::UiCell) {
+        let scale = UiScale::compute(ui.win_w, ui.win_h, self.scale_policy);
+        let vw = UiScale::REF_W;
+        let vh = UiScale::REF_H;
+        // How many rows fit in the fixed table window.
+        const VISIBLE_ROWS: usize = 10;
+        let left = (vw / 2.0) - 330.0;
+        let top = (vh / 2.0) - 150.0;
+
+        Text::new("Parameters")
+            .x_y(scale.x(left + 70.0), scale.y(top))
+            .font_size(22)
+            .color(self.bg_color.plain_contrast())
+            .set(PARAM_TABLE_TITLE, ui);
+
+        if Button::new()
+            .w_h(scale.w(110.0), scale.h(25.0))
+            .x_y(scale.x(left + 200.0), scale.y(top))
+            .rgb(0.3, 0.8, 0.3)
+            .border(1.0)
+            .label("Request All")
+            .set(PARAM_REQUEST_ALL_BUTTON, ui)
+            .was_clicked()
+        {
+            self.request_all_params();
+        }
+
+        // Stable row order so a row doesn't jump around between frames.
+        let mut names: Vec<String> = self.params.keys().cloned().collect();
+        names.sort();
+
+        // Clamp the scroll offset to the current row count.
+        let max_scroll = names.len().saturating_sub(VISIBLE_ROWS);
+        if self.param_scroll > max_scroll {
+            self.param_scroll = max_scroll;
+        }
+
+        if Button::new()
+            .w_h(scale.w(25.0), scale.h(25.0))
+            .x_y(scale.x(left + 280.0), scale.y(top))
+            .rgb(0.3, 0.3, 0.5)
+            .border(1.0)
+            .label("^")
+            .label_color(WHITE)
+            .set(PARAM_SCROLL_UP, ui)
+            .was_clicked()
+        {
+            self.param_scroll = self.param_scroll.saturating_sub(1);
+        }
+        if Button::new()
+            .w_h(scale.w(25.0), scale.h(25.0))
+            .x_y(scale.x(left + 310.0), scale.y(top))
+            .rgb(0.3, 0.3, 0.5)
+            .border(1.0)
+            .label("v")
+            .label_color(WHITE)
+            .set(PARAM_SCROLL_DOWN, ui)
+            .was_clicked()
+        {
+            if self.param_scroll < max_scroll {
+                self.param_scroll += 1;
+            }
+        }
+
+        for (row, name) in names.iter().skip(self.param_scroll).take(VISIBLE_ROWS).enumerate() {
+            let y = top - 35.0 - (row as f64) * 28.0;
+            let dirty = self.param_dirty.contains(name);
+
+            Text::new(name.as_str())
+                .x_y(scale.x(left + 70.0), scale.y(y))
+                .font_size(14)
+                .color(self.bg_color.plain_contrast())
+                .set(PARAM_ROWS_START + row * 2, ui);
+
+            let border_color = if dirty {
+                rgb(1.0, 1.0, 0.0)
+            } else {
+                self.bg_color.invert().plain_contrast()
+            };
+
+            let mut entered = false;
+            {
+                let buf = self.param_edits.entry(name.clone()).or_insert_with(String::new);
+                for event in TextBox::new(buf)
+                    .font_size(14)
+                    .w_h(scale.w(90.0), scale.h(22.0))
+                    .x_y(scale.x(left + 230.0), scale.y(y))
+                    .border(1.0)
+                    .border_color(border_color)
+                    .color(self.bg_color.invert())
+                    .set(PARAM_ROWS_START + row * 2 + 1, ui)
+                {
+                    if let widget::text_box::Event::Enter = event {
+                        entered = true;
+                    }
+                }
+            }
+
+            if entered {
+                if let Ok(value) = self.param_edits[name].trim().parse::<f32>() {
+                    self.send_param_set(name, value);
+                }
+            }
+        }
+    }
+
+    /// Toggle the waypoint autopilot. Engaging (re)loads the route from the
+    /// mission's `waypoints.txt`, falling back to a top-level `waypoints.txt`,
+    /// so a dropped route file just leaves the autopilot with nothing to do
+    /// rather than crashing the GUI.
+    fn toggle_autopilot(&mut self) {
+        if self.autopilot.engaged() {
+            self.autopilot.disengage();
+            self.l_rpm = 0.0;
+            self.r_rpm = 0.0;
+            self.send_lr_rpm();
+            self.send_brake();
+            return;
+        }
+
+        let mission_route = format!("mission_data/{}/waypoints.txt", self.mission_folder);
+        match read_route_file(&mission_route).or_else(|_| read_route_file("waypoints.txt")) {
+            Ok(text) => self.autopilot.load_route(&text),
+            Err(e) => {
+                println!("WARNING: no waypoint route loaded: {}", e);
+                self.autopilot.set_waypoints(vec![]);
+            },
+        }
+        self.autopilot.engage();
+    }
+
+    /// Mission metadata to embed into a locally-saved snapshot.
+    pub fn snapshot_meta(&self) -> SnapshotMeta {
+        let (roll, heading) = match self.pitch_roll_heading {
+            Some((_, roll, heading)) => (Some(roll), Some(heading)),
+            None => (None, None),
+        };
+        SnapshotMeta {
+            captured: time::now(),
+            pan: self.f_pan,
+            tilt: self.f_tilt,
+            roll: roll,
+            heading: heading,
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+
+    /// Snapshot the live telemetry the video OSD renders, with the mission
+    /// clock preformatted as `HH:MM:SS`.
+    pub fn osd_state(&self) -> osd::OsdState {
+        let elapsed = match self.mission_time {
+            MissionTime::Paused(t) => t,
+            MissionTime::Running(start_time, extra_time) => (time::now() - start_time) + extra_time,
+        };
+        let total_seconds = elapsed.num_seconds();
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let seconds = total_seconds % 60;
+        osd::OsdState {
+            roll: self.roll.angle(),
+            heading: self.heading.angle(),
+            pan: self.f_pan,
+            tilt: self.f_tilt,
+            timecode: format!("{:02}:{:02}:{:02}", hours, minutes, seconds),
+        }
     }
 
     pub fn handle_packet(&mut self, packet: String) {
         //println!("{}", packet);
 
+        // Append the raw datagram to the black box before parsing so even a
+        // packet we can't decode is preserved for analysis.
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_inbound(&packet);
+        }
+
         let packets = packet.split("|");
 
         for packet in packets {
@@ -658,46 +1622,102 @@ impl NavigationUi {
 
             match packet_parts[0].as_str() {
                 "GPS" => {
+                    // GPS:lat:lon:speed:alt:angle - anything else is malformed.
                     if packet_parts.len() == 6 {
-                        self.latitude = packet_parts[1].parse().ok();
-                        self.longitude = packet_parts[2].parse().ok();
-                        self.speed = packet_parts[3].parse().ok();
-                        self.altitude = packet_parts[4].parse().ok();
-                        self.angle = packet_parts[5].parse().ok();
+                        if let (Ok(lat), Ok(lon), Ok(speed), Ok(alt), Ok(angle)) =
+                            (packet_parts[1].parse::<f64>(),
+                             packet_parts[2].parse::<f64>(),
+                             packet_parts[3].parse::<f64>(),
+                             packet_parts[4].parse::<f64>(),
+                             packet_parts[5].parse::<f64>()) {
+                            self.latitude = Some(lat);
+                            self.longitude = Some(lon);
+                            self.speed = Some(speed);
+                            self.altitude = Some(alt);
+                            self.angle = Some(angle);
+                            self.watchdog.record_valid("GPS");
+                        } else {
+                            self.watchdog.record_error("GPS");
+                        }
+                    } else {
+                        self.watchdog.record_error("GPS");
                     }
                 },
                 "IMU" => {
-                    let ax: f64 = packet_parts[1].parse().unwrap();
-                    let ay: f64 = packet_parts[2].parse().unwrap();
-                    let az: f64 = packet_parts[3].parse().unwrap();
-
-                    let mx: f64 = packet_parts[7].parse().unwrap();
-                    let my: f64 = packet_parts[8].parse().unwrap();
-                    let mz: f64 = packet_parts[9].parse().unwrap();
-
-                    let (ax, ay, az) = (ay, -az, ax);
-                    let (mx, my, mz) = (my, -mz, mx);
-
-                    let roll = f64::atan2(ay, az);
-                    let pitch = f64::atan2(-ax, ay*f64::sin(roll) + az*f64::cos(roll));
-                    let heading = f64::atan2(mz*f64::sin(roll) - my*f64::cos(roll),
-                                             mx*f64::cos(pitch) + my*f64::sin(pitch)*f64::sin(roll) + mz*f64::sin(pitch)*f64::cos(roll));
-                    let mut roll = roll.to_degrees() + 180.0;
-                    let pitch = pitch.to_degrees();
-                    let heading = heading.to_degrees();
-
-                    let mut heading = heading;
-                    if heading < 0.0 {
-                        heading += 360.0;
+                    // IMU:ax:ay:az:gx:gy:gz:mx:my:mz. A short or non-numeric
+                    // packet counts as an error rather than panicking the GUI.
+                    let fields: Option<Vec<f64>> = if packet_parts.len() == 10 {
+                        packet_parts[1..10].iter().map(|p| p.parse::<f64>().ok()).collect()
+                    } else {
+                        None
+                    };
+
+                    if let Some(v) = fields {
+                        let (pitch, roll, heading) =
+                            self.imu_filter.update((v[0], v[1], v[2]),
+                                                   (v[3], v[4], v[5]),
+                                                   (v[6], v[7], v[8]));
+                        self.pitch_roll_heading = Some((pitch, roll, heading));
+                        self.pitch.set_angle(-pitch);
+                        self.roll.set_angle(roll);
+                        self.heading.set_angle(heading);
+                        self.watchdog.record_valid("IMU");
+                    } else {
+                        self.watchdog.record_error("IMU");
+                    }
+                },
+                "ATTITUDE" => {
+                    // ATTITUDE:roll:pitch:yaw in degrees. Drives the 3D model
+                    // and the flat gauges together.
+                    if packet_parts.len() == 4 {
+                        if let (Ok(roll), Ok(pitch), Ok(yaw)) =
+                            (packet_parts[1].parse::<f64>(),
+                             packet_parts[2].parse::<f64>(),
+                             packet_parts[3].parse::<f64>()) {
+                            self.attitude = Some((roll, pitch, yaw));
+                            self.pitch.set_angle(-pitch);
+                            self.roll.set_angle(roll);
+                            self.heading.set_angle(yaw);
+                        }
+                    }
+                },
+                "PARAM" => {
+                    // PARAM:name:value - the rover's current value for a named
+                    // parameter. Doubles as the ack for a prior PARAM_SET: any
+                    // report for the name clears its dirty mark and replaces the
+                    // edit buffer with the reported value.
+                    if packet_parts.len() == 3 {
+                        if let Ok(value) = packet_parts[2].parse::<f32>() {
+                            let name = packet_parts[1].clone();
+                            self.params.insert(name.clone(), value);
+                            self.param_edits.insert(name.clone(), format!("{}", value));
+                            self.param_dirty.remove(&name);
+                        }
                     }
-                    heading = 360.0 - heading;
-                    if roll >= 180.0 {
-                        roll -= 360.0;
+                },
+                "WRPM" => {
+                    // WRPM:left:right - measured wheel RPM feedback that closes
+                    // the PID loop. A malformed packet used to be silently
+                    // dropped; now it raises a standing parse-error alarm
+                    // instead.
+                    let parsed = if packet_parts.len() == 3 {
+                        match (packet_parts[1].parse::<f32>(), packet_parts[2].parse::<f32>()) {
+                            (Ok(l), Ok(r)) => Some((l, r)),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    };
+
+                    match parsed {
+                        Some((l, r)) => {
+                            self.measured_l_rpm = l;
+                            self.measured_r_rpm = r;
+                            self.monitor.observe("L RPM", l as f64);
+                            self.monitor.observe("R RPM", r as f64);
+                        },
+                        None => self.monitor.note_parse_error(),
                     }
-                    self.pitch_roll_heading = Some((pitch, roll, heading));
-                    self.pitch.set_angle(-pitch);
-                    self.roll.set_angle(roll);
-                    self.heading.set_angle(heading);
                 },
                 _ => { /*println!("WARNING: Unknown packet ID: {}", packet_parts[0])*/ },
             }
@@ -711,6 +1731,13 @@ impl NavigationUi {
             return;
         }
 
+        // Any manual drive input immediately drops the autopilot so the
+        // operator always has the last word over the motors.
+        match key {
+            Space | Up | Down | Left | Right => self.autopilot.disengage(),
+            _ => { },
+        }
+
         // here need to add key for rpm values, need stuff between 0 and 100 - 10/29 CP
         // thought was to have '+' and '-' keys control a percentage slider, where
         // the l_rpm and r_rpm get multiplied by this perecentage (1 for 100%, 0.5 for 50%)
@@ -719,7 +1746,12 @@ impl NavigationUi {
 
         match key {
             Space => {
-                // LR motor stop
+                // Full stop: drop the ramp, cancel cruise, brake.
+                self.drive_fwd_input = 0.0;
+                self.drive_turn_input = 0.0;
+                self.drive_l = 0.0;
+                self.drive_r = 0.0;
+                self.cruise = None;
                 self.l_rpm = 0.0;
                 self.r_rpm = 0.0;
                 self.send_lr_rpm();
@@ -727,29 +1759,37 @@ impl NavigationUi {
                 self.send_brake();
             }
             Up => {
-                // Forward
-                println!("foo");
-                self.l_rpm = 100.0*self.motor_speed;
-                self.r_rpm = 100.0*self.motor_speed;
-                self.send_lr_rpm();
+                // Ramp both tracks forward while held.
+                self.drive_fwd_input = 1.0;
+                self.cruise = None;
             },
             Down => {
-                // Forward
-                self.l_rpm = -100.0*self.motor_speed;
-                self.r_rpm = -100.0*self.motor_speed;
-                self.send_lr_rpm();
+                // Ramp both tracks backward while held.
+                self.drive_fwd_input = -1.0;
+                self.cruise = None;
             },
             Left => {
-                // Forward
-                self.l_rpm = -100.0*self.motor_speed;
-                self.r_rpm = 100.0*self.motor_speed;
-                self.send_lr_rpm();
+                // Turn differential: left track back, right track forward.
+                self.drive_turn_input = -1.0;
+                self.cruise = None;
             },
             Right => {
-                // Forward
-                self.l_rpm = 100.0*self.motor_speed;
-                self.r_rpm = -100.0*self.motor_speed;
-                self.send_lr_rpm();
+                // Turn differential: left track forward, right track back.
+                self.drive_turn_input = 1.0;
+                self.cruise = None;
+            },
+            C => {
+                // Tap-tempo cruise: two taps within the window latch the
+                // current forward RPM as a setpoint that holds until the next
+                // drive key or Stop.
+                let now = Instant::now();
+                if let Some(prev) = self.last_cruise_tap {
+                    if now.duration_since(prev) < Duration::from_millis(1500) {
+                        // Latch the current forward RPM (mean of the two tracks).
+                        self.cruise = Some((self.drive_l + self.drive_r) / 2.0);
+                    }
+                }
+                self.last_cruise_tap = Some(now);
             },
             Minus => {
                 self.motor_speed -= 0.1;
@@ -793,6 +1833,14 @@ impl NavigationUi {
                 // Camera right
                 self.f_panning = 1.0;
             },
+            R => {
+                // Toggle camera recording
+                self.toggle_recording();
+            },
+            T => {
+                // Push-to-talk engage
+                self.voice.set_talking(true);
+            },
             _ => { },
         }
     }
@@ -805,11 +1853,14 @@ impl NavigationUi {
         }
 
         match key {
-            Up | Down | Left | Right => {
-                // LR motor stop
-                self.l_rpm = 0.0;
-                self.r_rpm = 0.0;
-                self.send_lr_rpm();
+            Up | Down => {
+                // Release the throttle input; the ramp decays it toward the
+                // cruise setpoint (or zero) instead of cutting dead.
+                self.drive_fwd_input = 0.0;
+            },
+            Left | Right => {
+                // Release the turn input; the differential ramps back out.
+                self.drive_turn_input = 0.0;
             },
             D1 | D2 => {
                 // SADL stop
@@ -829,6 +1880,10 @@ impl NavigationUi {
                 self.f_panning = 0.0;
                 self.send_f_pan();
             },
+            T => {
+                // Push-to-talk release
+                self.voice.set_talking(false);
+            },
             _ => { },
         }
     }
@@ -836,14 +1891,19 @@ impl NavigationUi {
     pub fn try_update_l_rpm(&mut self, l_rpm: f32) {
         if (l_rpm - self.l_rpm).abs() > 5.0 {
             self.l_rpm = l_rpm;
-            self.send_l_rpm();
+            // Closed loop owns the outgoing command; only send raw open-loop.
+            if !self.pid_enabled {
+                self.send_l_rpm();
+            }
         }
     }
 
     pub fn try_update_r_rpm(&mut self, r_rpm: f32) {
         if (r_rpm - self.r_rpm).abs() > 5.0 {
             self.r_rpm = r_rpm;
-            self.send_r_rpm();
+            if !self.pid_enabled {
+                self.send_r_rpm();
+            }
         }
     }
 
@@ -868,36 +1928,72 @@ impl NavigationUi {
         }
     }
 
+    /// Queue a `PARAM_SET:name:value` write for a named parameter and mark the
+    /// row dirty until the rover echoes it back.
+    pub fn send_param_set(&mut self, name: &str, value: f32) {
+        self.param_dirty.insert(name.to_string());
+        let packet = format!("PARAM_SET:{}:{}|", name, value);
+        let delay = self.delay;
+        self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+    }
+
+    /// Ask the rover to dump every parameter it knows about.
+    pub fn request_all_params(&mut self) {
+        let delay = self.delay;
+        self.queue_packet(delay, b"PARAM_REQUEST|".to_vec(), ("10.10.153.8".to_string(), 30001));
+    }
+
     pub fn send_brake(&mut self) {
+        let packet = self.encoder.brake();
         let delay = self.delay;
-        self.queue_packet(delay, vec![b'G'], ("10.10.153.8".to_string(), 30001));
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
     }
 
     pub fn send_l_rpm(&mut self) {
-        let packet = format!("A{}|", self.l_rpm as i32);
+        let rpm = self.l_rpm;
+        let packet = self.encoder.set_left_rpm(rpm);
         let delay = self.delay;
-        self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
     }
 
     pub fn send_r_rpm(&mut self) {
-        let packet = format!("B{}|", self.r_rpm as i32);
+        let rpm = self.r_rpm;
+        let packet = self.encoder.set_right_rpm(rpm);
         let delay = self.delay;
-        self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
+    }
+
+    /// Send the closed-loop left/right motor commands over the same channel
+    /// the open-loop sliders use.
+    pub fn send_l_cmd(&mut self) {
+        let l_cmd = self.l_cmd;
+        let packet = self.encoder.set_left_rpm(l_cmd);
+        let delay = self.delay;
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
+    }
+
+    pub fn send_r_cmd(&mut self) {
+        let r_cmd = self.r_cmd;
+        let packet = self.encoder.set_right_rpm(r_cmd);
+        let delay = self.delay;
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
     }
 
     pub fn send_lr_rpm(&mut self) {
-        let packet = format!("H{}|{}|", self.l_rpm as i32, self.r_rpm as i32);
+        let (l_rpm, r_rpm) = (self.l_rpm, self.r_rpm);
+        let packet = self.encoder.set_lr_rpm(l_rpm, r_rpm);
         let delay = self.delay;
-        self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
     }
 
     pub fn send_f_pan(&mut self) {
         let time_since = (time::now() - self.last_f_pan_time).num_milliseconds();
         if time_since >= 500 {
             self.last_f_pan_time = time::now();
-            let packet = format!("C{}|", self.f_pan as i32);
+            let f_pan = self.f_pan;
+            let packet = self.encoder.pan(f_pan);
             let delay = self.delay;
-            self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+            self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
         }
     }
 
@@ -905,14 +2001,42 @@ impl NavigationUi {
         let time_since = (time::now() - self.last_f_tilt_time).num_milliseconds();
         if time_since >= 500 {
             self.last_f_tilt_time = time::now();
-            let packet = format!("D{}|", self.f_tilt as i32);
+            let f_tilt = self.f_tilt;
+            let packet = self.encoder.tilt(f_tilt);
             let delay = self.delay;
-            self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
+            self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
         }
     }
 
     pub fn send_sadl(&mut self) {
-        let packet = format!("E{}|", self.sadl as i32);
+        let sadl = self.sadl;
+        let packet = self.encoder.sadl(sadl);
+        let delay = self.delay;
+        self.queue_packet(delay, packet, ("10.10.153.8".to_string(), 30001));
+    }
+
+    /// Swap the active wire encoding. `true` speaks MAVLink `COMMAND_LONG`
+    /// frames to any MAVLink-speaking flight/rover controller; `false` goes
+    /// back to the legacy text packets the original firmware expects.
+    pub fn set_mavlink_backend(&mut self, enabled: bool) {
+        self.mavlink_active = enabled;
+        self.encoder = if enabled {
+            Box::new(MavlinkEncoder::new())
+        } else {
+            Box::new(LegacyEncoder)
+        };
+    }
+
+    pub fn toggle_protocol(&mut self) {
+        let enabled = !self.mavlink_active;
+        self.set_mavlink_backend(enabled);
+    }
+
+    /// Queue an engage (`1`) or disengage (`0`) half of a camera trigger
+    /// pulse, used both for a single manual Snapshot press and for the
+    /// intervalometer's scheduled pairs.
+    pub fn send_camera_trigger(&mut self, engage: bool) {
+        let packet = format!("CAM_TRIGGER:{}|", if engage { 1 } else { 0 });
         let delay = self.delay;
         self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
     }
@@ -929,28 +2053,172 @@ impl NavigationUi {
         self.queue_packet(delay, packet.into_bytes(), ("10.10.153.8".to_string(), 30001));
     }
 
-    pub fn queue_packet(&mut self, delay: time::Duration, mut data: Vec<u8>, addr: (String, u16)) {
-        data.push(0); // Null terminate all of our packets
-        self.out_queue.push_back((time::now(), delay, data, addr));
+    /// Toggle continuous H.264/MP4 recording of every camera stream. Each
+    /// stream is muxed into its own `camN_<timestamp>.mp4` under the mission
+    /// folder so variable decode rates don't interleave.
+    pub fn toggle_recording(&mut self) {
+        if self.recording {
+            self.vid0_t.send(VideoMsg::Stop).ok();
+            self.vid1_t.send(VideoMsg::Stop).ok();
+            self.vid2_t.send(VideoMsg::Stop).ok();
+            self.recording = false;
+        } else {
+            let stamp = time::now().strftime("%Y%m%d_%H%M%S").unwrap();
+            self.vid0_t.send(VideoMsg::Start(format!("mission_data/{}/cam0_{}.mp4", self.mission_folder, stamp), RecordMode::Single)).ok();
+            self.vid1_t.send(VideoMsg::Start(format!("mission_data/{}/cam1_{}.mp4", self.mission_folder, stamp), RecordMode::Single)).ok();
+            self.vid2_t.send(VideoMsg::Start(format!("mission_data/{}/cam2_{}.mp4", self.mission_folder, stamp), RecordMode::Single)).ok();
+            self.recording = true;
+        }
+    }
+
+    pub fn queue_packet(&mut self, delay: time::Duration, payload: Vec<u8>, addr: (String, u16)) {
+        // Wrap the payload in the reliable frame: [seq][payload][crc8], then
+        // null-terminate as before. The frame is tracked for retransmission so
+        // a dropped command is resent until the rover acks it. This framing is
+        // a ground-station convention the legacy firmware expects; a real
+        // MAVLink receiver has its own CRC/seq in the COMMAND_LONG frame
+        // already, so a MAVLink payload goes out as-is, untracked.
+        let data = if self.mavlink_active {
+            payload
+        } else {
+            let seq = self.frames.next_seq();
+            let mut data = Vec::with_capacity(payload.len() + 3);
+            data.push(seq);
+            data.extend_from_slice(&payload);
+            let crc = framing::crc8(&data);
+            data.push(crc);
+            data.push(0); // Null terminate all of our packets
+
+            // The brake and a combined `H` stop with both wheels at zero are
+            // safety-critical: they get the tighter retransmit timeout and a
+            // shorter retry budget. Separate per-track zero-RPM commands are not.
+            let safety = is_safety_critical(&payload);
+            self.frames.register(seq, data.clone(), addr.clone(), safety);
+            data
+        };
+
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_outbound(&data, &addr);
+        }
+        // Spread the release time by the channel jitter so packets can become
+        // eligible out of enqueue order; clamp the total delay non-negative.
+        let mut eff = delay + self.link.jitter();
+        if eff < time::Duration::zero() {
+            eff = time::Duration::zero();
+        }
+        self.out_queue.push_back((time::now(), eff, data, addr));
+    }
+
+    /// Total bytes currently held in the outbound queue.
+    fn queued_bytes(&self) -> usize {
+        self.out_queue.iter().map(|&(_, _, ref data, _)| data.len()).sum()
     }
 
     fn flush_out_queue(&mut self) -> io::Result<usize> {
-        use std::iter;
+        use std::time::Instant;
+
+        // In a non-transmitting state (replay) drain the queue without
+        // touching the socket.
+        if !self.transmit {
+            self.out_queue.clear();
+            return Ok(0);
+        }
+
+        self.link.begin_flush();
 
         let mut bytes_written = 0;
-        while !self.out_queue.is_empty() {
-            if time::now()-self.out_queue[0].0 >= self.out_queue[0].1 {
-                let (_, _, mut data, addr) = self.out_queue.pop_front().unwrap();
-                let data_len = data.len();
-                bytes_written += try!(self.client.send_to(data.as_slice(), (addr.0.as_str(), addr.1)));
-                //data.extend(iter::repeat(b' ').take(64 - data_len)); // Pad the message to always be 64 bytes
-                //bytes_written += try!(self.client.write(data.as_slice()));
-            } else {
+        let now = time::now();
+        loop {
+            // Pick the eligible packet with the earliest release time, so that
+            // jitter-reordered packets leave ahead of ones queued before them.
+            let next = self.out_queue.iter().enumerate()
+                .filter(|&(_, &(queued, delay, _, _))| now - queued >= delay)
+                .min_by_key(|&(_, &(queued, delay, _, _))| queued + delay)
+                .map(|(i, _)| i);
+            let i = match next {
+                Some(i) => i,
+                None => break,
+            };
+
+            // Bandwidth cap: stop flushing once the channel is saturated and
+            // hold the remaining packets for the next tick.
+            if !self.link.can_send(self.out_queue[i].2.len()) {
                 break;
             }
+
+            let (_, _, data, addr) = self.out_queue.remove(i).unwrap();
+
+            // Simulated packet loss: the datagram is dequeued but never reaches
+            // the socket.
+            if self.link.drops() {
+                continue;
+            }
+
+            let send_started = Instant::now();
+            bytes_written += try!(self.client.send_to(data.as_slice(), (addr.0.as_str(), addr.1)));
+            self.link.on_sent(data.len());
+            metrics::shared().queue_flush.observe(send_started.elapsed().as_micros() as u64);
+        }
+
+        // Retransmit any frames the rover has not acked within their timeout,
+        // and surface the ones that have exhausted their retry budget.
+        let (resends, lost) = self.frames.tick(Instant::now());
+        for (frame, addr) in resends {
+            bytes_written += try!(self.client.send_to(frame.as_slice(), (addr.0.as_str(), addr.1)));
+            self.link.on_sent(frame.len());
         }
+        for seq in lost {
+            let msg = format!("COMMAND LOST (seq {})", seq);
+            self.link_status = msg.clone();
+            self.command_history.push(msg);
+        }
+
         Ok(bytes_written)
     }
+
+    /// Drop an acknowledged frame from the reliable table when the `packet_in`
+    /// thread splits an `ACK<seq>` out of the inbound stream.
+    pub fn ack_frame(&mut self, seq: u8) {
+        self.frames.ack(seq);
+    }
+}
+
+/// Whether a command payload is safety-critical and should get the tighter
+/// reliable-retransmit treatment: the `G` brake, or an `H` combined-RPM stop
+/// with both wheels commanded to zero.
+fn is_safety_critical(payload: &[u8]) -> bool {
+    match payload.first() {
+        Some(&b'G') => true,
+        Some(&b'H') => {
+            // H{l}|{r}| - a stop is both fields present and zero.
+            let body = String::from_utf8_lossy(&payload[1..]);
+            let fields: Vec<&str> = body.split('|').filter(|s| !s.is_empty()).collect();
+            !fields.is_empty()
+                && fields.iter().all(|s| s.trim().parse::<i32>().ok() == Some(0))
+        },
+        _ => false,
+    }
+}
+
+/// Read a waypoint route file into a string, in the File/Read style the rest
+/// of the crate uses (the stdlib shortcut isn't on this toolchain).
+fn read_route_file(path: &str) -> io::Result<String> {
+    use std::fs::File;
+    use std::io::Read;
+    let mut text = String::new();
+    try!(try!(File::open(path)).read_to_string(&mut text));
+    Ok(text)
+}
+
+/// Move `current` toward `target` by at most `step`, without overshooting.
+fn ramp_toward(current: f32, target: f32, step: f32) -> f32 {
+    if (target - current).abs() <= step {
+        target
+    } else if target > current {
+        current + step
+    } else {
+        current - step
+    }
 }
 
 fn gps_degrees_to_dms(degrees: f64) -> (i32, i32, f64) {
@@ -978,9 +2246,32 @@ widget_ids! {
     MISSION_TIME_LABEL,
     MISSION_START_BUTTON,
     MISSION_RESET_BUTTON,
+
+    // Mission black-box replay controls.
+    REPLAY_BUTTON,
+    REPLAY_SPEED_SLIDER,
+    REPLAY_SCRUB_SLIDER,
+    SCALE_TOGGLE,
     TIME_DELAY,
     TIME_DELAY_VALUE,
 
+    // Degraded-link simulator controls and readout.
+    LINK_LOSS_LABEL,
+    LINK_LOSS_VALUE,
+    LINK_JITTER_LABEL,
+    LINK_JITTER_VALUE,
+    LINK_BANDWIDTH_LABEL,
+    LINK_BANDWIDTH_VALUE,
+    LINK_READOUT,
+
+    // Prioritised telemetry-health alarm banner.
+    HEALTH_BANNER,
+
+    // Safe-operating-range alarm banner and measured-RPM readouts.
+    ALARM_BANNER,
+    L_RPM_MEASURED_LABEL,
+    R_RPM_MEASURED_LABEL,
+
     // IMU section
     IMU_LABEL,
 
@@ -1003,11 +2294,26 @@ widget_ids! {
 
     L_RPM_SLIDER,
     R_RPM_SLIDER,
+
+    // Collapsible closed-loop tuning section.
+    TUNING_TOGGLE,
+    PID_ENABLE_BUTTON,
+    KP_SLIDER,
+    KI_SLIDER,
+    KD_SLIDER,
+    KF_SLIDER,
+    I_LIMIT_SLIDER,
+
     MOTOR_SPEED_SLIDER,
     STOP_BUTTON,
+    AUTOPILOT_BUTTON,
     F_PAN_SLIDER,
     F_TILT_SLIDER,
     SNAPSHOT_BUTTON,
+    INTERVALOMETER_BUTTON,
+    INTERVALOMETER_INTERVAL_SLIDER,
+    INTERVALOMETER_ACTIVATION_SLIDER,
+    LINK_STATUS,
 
     COMMAND_HISTORY,
     COMMAND_LABEL,
@@ -1015,8 +2321,19 @@ widget_ids! {
     SEND_COMMAND_BUTTON,
     MODE_LABEL,
     MODE_TOGGLE_BUTTON,
+    PROTOCOL_TOGGLE_BUTTON,
 
     SADL_LABEL,
     SADL_UP,
     SADL_DOWN,
+
+    // Parameter console.
+    PARAM_TABLE_TITLE,
+    PARAM_REQUEST_ALL_BUTTON,
+    PARAM_SCROLL_UP,
+    PARAM_SCROLL_DOWN,
+    // Dynamic rows live at PARAM_ROWS_START + row*2 (name label) and
+    // PARAM_ROWS_START + row*2 + 1 (value text box). Keep this entry LAST so
+    // the reserved range never collides with another widget.
+    PARAM_ROWS_START,
 }
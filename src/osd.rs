@@ -0,0 +1,251 @@
+//! Heads-up overlay burned directly into the live video frame.
+//!
+//! During teleoperation the pilot should not have to dart between the video
+//! and a row of separate gauges, so this composites the attitude, heading,
+//! camera aim and mission clock straight onto the decoded `RgbaImage` before
+//! it is uploaded to the `Texture`. Because it mutates the RGBA buffer in
+//! place, the same layer can ride along into a recording (whose frames are
+//! copied from this buffer) when the elements are left on, or be switched off
+//! for a clean capture via [`OsdConfig`].
+//!
+//! Everything is drawn with a handful of alpha-blended primitives and a tiny
+//! built-in glyph set; no font or GPU state leaks in here, which keeps the
+//! overlay independent of the conrod widgets it mirrors.
+
+use image::RgbaImage;
+
+/// Translucent green used for the horizon, ticks and text.
+const FG: [u8; 3] = [60, 255, 90];
+/// Alpha applied when blending overlay pixels over the video, 0..=255.
+const ALPHA: u16 = 200;
+
+/// Which overlay elements are drawn and where. Defaults put every element on;
+/// flip a flag off (or call [`OsdConfig::clean`]) to record without burn-in.
+#[derive(Copy, Clone)]
+pub struct OsdConfig {
+    /// Roll-driven artificial-horizon line through the frame center.
+    pub horizon: bool,
+    /// Heading tape scrolling across the top of the frame.
+    pub compass: bool,
+    /// Pan/tilt reticle offset from center.
+    pub reticle: bool,
+    /// Mission timecode string.
+    pub timecode: bool,
+    /// Top edge of the compass tape, in pixels.
+    pub compass_y: u32,
+    /// Top-left corner of the timecode string, in pixels.
+    pub timecode_pos: (u32, u32),
+}
+
+impl OsdConfig {
+    /// All elements enabled, in their default positions.
+    pub fn new() -> OsdConfig {
+        OsdConfig {
+            horizon: true,
+            compass: true,
+            reticle: true,
+            timecode: true,
+            compass_y: 8,
+            timecode_pos: (8, 8),
+        }
+    }
+
+    /// Every element disabled, for a clean recording.
+    pub fn clean() -> OsdConfig {
+        OsdConfig { horizon: false, compass: false, reticle: false, timecode: false, ..OsdConfig::new() }
+    }
+}
+
+/// The live values the overlay renders, sampled from the telemetry widgets.
+pub struct OsdState {
+    /// Roll angle in degrees (positive rolls the horizon counter-clockwise).
+    pub roll: f64,
+    /// Heading in degrees, 0..360.
+    pub heading: f64,
+    /// Camera pan, degrees (0..180, 90 centered).
+    pub pan: f32,
+    /// Camera tilt, degrees.
+    pub tilt: f32,
+    /// Preformatted mission timecode, e.g. `01:23:45`.
+    pub timecode: String,
+}
+
+/// Composite the overlay described by `config` onto `img` in place.
+pub fn composite(img: &mut RgbaImage, state: &OsdState, config: &OsdConfig) {
+    let (w, h) = img.dimensions();
+
+    if config.horizon {
+        draw_horizon(img, w, h, state.roll);
+    }
+    if config.compass {
+        draw_compass(img, w, config.compass_y, state.heading);
+    }
+    if config.reticle {
+        draw_reticle(img, w, h, state.pan, state.tilt);
+    }
+    if config.timecode {
+        draw_text(img, config.timecode_pos.0, config.timecode_pos.1, &state.timecode);
+    }
+}
+
+/// A roll-indicating line through the center, plus a fixed center pip.
+fn draw_horizon(img: &mut RgbaImage, w: u32, h: u32, roll_deg: f64) {
+    let cx = w as f64 / 2.0;
+    let cy = h as f64 / 2.0;
+    let half = w as f64 * 0.35;
+    let (s, c) = (-roll_deg).to_radians().sin_cos();
+    let x0 = cx - half * c;
+    let y0 = cy - half * s;
+    let x1 = cx + half * c;
+    let y1 = cy + half * s;
+    draw_line(img, x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+
+    // Static aircraft-reference pip so the roll is read against the frame.
+    let cxi = cx as i32;
+    let cyi = cy as i32;
+    draw_line(img, cxi - 6, cyi, cxi - 2, cyi);
+    draw_line(img, cxi + 2, cyi, cxi + 6, cyi);
+}
+
+/// A heading tape: a baseline with a tick every 10 degrees around the current
+/// heading and the cardinal letter (or degree label) under each major tick.
+fn draw_compass(img: &mut RgbaImage, w: u32, y: u32, heading_deg: f64) {
+    let span = 90.0; // degrees visible across the frame
+    let px_per_deg = w as f64 / span;
+    let center = w as f64 / 2.0;
+    let baseline = y + 14;
+
+    draw_line(img, 0, baseline as i32, w as i32 - 1, baseline as i32);
+
+    let start = (heading_deg - span / 2.0).floor() as i32;
+    let end = (heading_deg + span / 2.0).ceil() as i32;
+    for deg in start..end + 1 {
+        if deg % 10 != 0 {
+            continue;
+        }
+        let norm = ((deg % 360) + 360) % 360;
+        let x = center + (deg as f64 - heading_deg) * px_per_deg;
+        if x < 0.0 || x >= w as f64 {
+            continue;
+        }
+        let major = norm % 30 == 0;
+        let tick_top = if major { y } else { y + 7 };
+        draw_line(img, x as i32, tick_top as i32, x as i32, baseline as i32);
+        if major {
+            let label = cardinal(norm);
+            let text_x = (x - (label.len() as f64 * 4.0) / 2.0).max(0.0) as u32;
+            draw_text(img, text_x, baseline + 3, &label);
+        }
+    }
+
+    // Center lubber line marking the exact heading.
+    draw_line(img, center as i32, y as i32, center as i32, (baseline + 4) as i32);
+}
+
+/// Cardinal label for the major headings, numeric otherwise.
+fn cardinal(deg: i32) -> String {
+    match deg {
+        0 => "N".to_string(),
+        90 => "E".to_string(),
+        180 => "S".to_string(),
+        270 => "W".to_string(),
+        other => format!("{}", other),
+    }
+}
+
+/// A crosshair whose offset from center reflects the camera pan/tilt, so the
+/// pilot sees where the head is pointed relative to straight ahead.
+fn draw_reticle(img: &mut RgbaImage, w: u32, h: u32, pan: f32, tilt: f32) {
+    let cx = w as f32 / 2.0;
+    let cy = h as f32 / 2.0;
+    // Pan 0..180 centered at 90, tilt 60..180 centered at 130.
+    let dx = (pan - 90.0) / 90.0 * (w as f32 * 0.4);
+    let dy = (tilt - 130.0) / 90.0 * (h as f32 * 0.4);
+    let x = (cx + dx) as i32;
+    let y = (cy + dy) as i32;
+    draw_line(img, x - 10, y, x - 3, y);
+    draw_line(img, x + 3, y, x + 10, y);
+    draw_line(img, x, y - 10, x, y - 3);
+    draw_line(img, x, y + 3, x, y + 10);
+}
+
+/// Alpha-blend one overlay pixel over the existing video pixel.
+fn blend_pixel(img: &mut RgbaImage, x: i32, y: i32) {
+    let (w, h) = img.dimensions();
+    if x < 0 || y < 0 || x as u32 >= w || y as u32 >= h {
+        return;
+    }
+    let px = img.get_pixel(x as u32, y as u32).data;
+    let mix = |bg: u8, fg: u8| (((fg as u16 * ALPHA) + (bg as u16 * (255 - ALPHA))) / 255) as u8;
+    let out = [mix(px[0], FG[0]), mix(px[1], FG[1]), mix(px[2], FG[2]), 255];
+    img.put_pixel(x as u32, y as u32, image::Rgba { data: out });
+}
+
+/// A 2px Bresenham line so thin strokes survive the 512px downscale.
+fn draw_line(img: &mut RgbaImage, x0: i32, y0: i32, x1: i32, y1: i32) {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let (mut x, mut y) = (x0, y0);
+    loop {
+        blend_pixel(img, x, y);
+        blend_pixel(img, x + 1, y);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Render a short string with the built-in 3x5 glyph set at 1px scale, 4px
+/// per character cell. Unknown characters render as blanks.
+fn draw_text(img: &mut RgbaImage, x: u32, y: u32, text: &str) {
+    let mut cursor = x;
+    for ch in text.chars() {
+        if let Some(rows) = glyph(ch) {
+            for (ry, row) in rows.iter().enumerate() {
+                for rx in 0..3 {
+                    if row & (1 << (2 - rx)) != 0 {
+                        blend_pixel(img, (cursor + rx) as i32, (y + ry as u32) as i32);
+                    }
+                }
+            }
+        }
+        cursor += 4;
+    }
+}
+
+/// 3x5 bitmap rows (MSB = leftmost column) for the glyphs the OSD needs.
+fn glyph(ch: char) -> Option<[u8; 5]> {
+    Some(match ch {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        'N' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'S' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        'W' => [0b101, 0b101, 0b101, 0b111, 0b101],
+        ' ' => return None,
+        _ => return None,
+    })
+}
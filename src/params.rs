@@ -0,0 +1,78 @@
+//! Reloadable ground-station parameters, in the spirit of a PX4 param set.
+//!
+//! Alarm thresholds and graph scales that used to be hardcoded in
+//! `TelemetryUi::new` live in a flat `KEY VALUE` text file (one pair per line,
+//! `#` comments allowed). Missing keys fall back to the built-in defaults, so a
+//! partial or absent file still yields a working dashboard, and edited values
+//! can be persisted back.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+/// A `key -> float` parameter table.
+pub struct Params {
+    values: HashMap<String, f64>,
+}
+
+impl Params {
+    /// The compiled-in defaults, matching the historical hardcoded values.
+    pub fn defaults() -> Params {
+        let mut values = HashMap::new();
+        {
+            let mut set = |k: &str, v: f64| { values.insert(k.to_string(), v); };
+            set("H_48_V.red", 45.0);    set("H_48_V.yellow", 48.0);
+            set("H_24_V.red", 22.0);    set("H_24_V.yellow", 24.0);
+            set("P_12_E_V.red", 10.0);  set("P_12_E_V.yellow", 12.0);
+            set("P_12_PL_V.red", 10.0); set("P_12_PL_V.yellow", 12.0);
+            set("MOTOR_TEMP.red", 80.0); set("MOTOR_TEMP.yellow", 60.0);
+            set("AVIONICS_TEMP.red", 60.0); set("AVIONICS_TEMP.yellow", 45.0);
+            set("V48_GRAPH.max", 80.0);
+            set("A24_GRAPH.max", 40.0);
+            set("V12_GRAPH.max", 20.0);
+            set("MOTOR_TEMP_GRAPH.max", 100.0);
+        }
+        Params { values: values }
+    }
+
+    /// Load `path`, overlaying any keys it defines onto the defaults. A missing
+    /// or unreadable file simply leaves the defaults in place.
+    pub fn load(path: &str) -> Params {
+        let mut params = Params::defaults();
+        if let Ok(file) = File::open(path) {
+            for line in BufReader::new(file).lines() {
+                let line = match line { Ok(l) => l, Err(_) => continue };
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') { continue; }
+                let mut it = line.splitn(2, |c| c == ' ' || c == '=');
+                if let (Some(key), Some(val)) = (it.next(), it.next()) {
+                    if let Ok(v) = val.trim().parse::<f64>() {
+                        params.values.insert(key.trim().to_string(), v);
+                    }
+                }
+            }
+        }
+        params
+    }
+
+    /// Look up `key`, falling back to `default` if it isn't present.
+    pub fn get(&self, key: &str, default: f64) -> f64 {
+        *self.values.get(key).unwrap_or(&default)
+    }
+
+    /// Overwrite a value in memory (used by the params panel before a persist).
+    pub fn set(&mut self, key: &str, value: f64) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    /// Persist the current table to `path`, keys sorted for a stable diff.
+    pub fn save(&self, path: &str) {
+        if let Ok(mut file) = File::create(path) {
+            let mut keys: Vec<&String> = self.values.keys().collect();
+            keys.sort();
+            for key in keys {
+                writeln!(&mut file, "{} {}", key, self.values[key]).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Per-side closed-loop RPM control.
+//!
+//! The sliders used to push a raw setpoint straight at the motors, so the
+//! operator had to fight terrain load by hand. This is a rate controller in
+//! the openpilot/PX4 mould: a gain-scheduled proportional term (breakpoint
+//! array `kp`), an integral with anti-windup (`ki`, `i_limit`), a derivative
+//! on the measured RPM (`kd`, like the `MC_*RATE_D` params) and a setpoint
+//! feedforward (`kf`). Each tick it drives the motor command from the
+//! *measured* wheel RPM toward the slider setpoint.
+
+/// Tuning and running state for one wheel's rate loop.
+pub struct PidTuning {
+    /// Proportional gain schedule: `(rpm_breakpoint, kp)` pairs, interpolated
+    /// against the current measured RPM so a light-load and a heavy-load
+    /// regime can use different gains.
+    pub kp: Vec<(f32, f32)>,
+    pub ki: f32,
+    pub kd: f32,
+    pub kf: f32,
+    /// Accumulated integral of the error, clamped to `i_limit`.
+    pub i_accumulator: f32,
+    pub i_limit: f32,
+    /// Last measured RPM, for the derivative term.
+    prev_measured: f32,
+}
+
+impl PidTuning {
+    /// Conservative starting gains: a two-point proportional schedule (0.4 at
+    /// rest easing to 0.25 at 100 RPM), a gentle integral and unity feedforward
+    /// so the loop roughly tracks even before tuning.
+    pub fn new() -> PidTuning {
+        PidTuning {
+            kp: vec![(0.0, 0.4), (100.0, 0.25)],
+            ki: 0.1,
+            kd: 0.0,
+            kf: 1.0,
+            i_accumulator: 0.0,
+            i_limit: 50.0,
+            prev_measured: 0.0,
+        }
+    }
+
+    /// Set a flat proportional gain, collapsing the schedule to one breakpoint.
+    /// Used by the tuning slider, which exposes a single number.
+    pub fn set_kp(&mut self, kp: f32) {
+        self.kp = vec![(0.0, kp)];
+    }
+
+    /// The current flat proportional gain (the first breakpoint's value).
+    pub fn kp_flat(&self) -> f32 {
+        self.kp.first().map(|&(_, v)| v).unwrap_or(0.0)
+    }
+
+    /// Piecewise-linear interpolation of `kp` against `rpm`, flat outside the
+    /// breakpoint range.
+    fn interp_kp(&self, rpm: f32) -> f32 {
+        match self.kp.first() {
+            None => 0.0,
+            Some(&(_, first)) => {
+                if rpm <= self.kp[0].0 {
+                    return first;
+                }
+                for w in self.kp.windows(2) {
+                    let (x0, y0) = w[0];
+                    let (x1, y1) = w[1];
+                    if rpm <= x1 {
+                        let t = if x1 > x0 { (rpm - x0) / (x1 - x0) } else { 0.0 };
+                        return y0 + t * (y1 - y0);
+                    }
+                }
+                self.kp.last().map(|&(_, v)| v).unwrap_or(first)
+            }
+        }
+    }
+
+    /// Advance the loop one `dt` tick and return the motor command that should
+    /// be sent. `setpoint` is the slider target, `measured` the telemetry RPM.
+    pub fn update(&mut self, setpoint: f32, measured: f32, dt: f32) -> f32 {
+        if dt <= 0.0 {
+            return self.kf * setpoint;
+        }
+
+        let kp = self.interp_kp(measured.abs());
+        let err = setpoint - measured;
+
+        // Integrate with anti-windup clamp.
+        self.i_accumulator = (self.i_accumulator + err * dt)
+            .max(-self.i_limit)
+            .min(self.i_limit);
+
+        // Derivative of the measured RPM.
+        let d = (measured - self.prev_measured) / dt;
+        self.prev_measured = measured;
+
+        kp * err + self.ki * self.i_accumulator + self.kd * d + self.kf * setpoint
+    }
+
+    /// Clear the integral and derivative history, e.g. on a full stop.
+    pub fn reset(&mut self) {
+        self.i_accumulator = 0.0;
+        self.prev_measured = 0.0;
+    }
+}
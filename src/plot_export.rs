@@ -0,0 +1,139 @@
+//! Post-mission plot export.
+//!
+//! In the spirit of the AltOS post-flight plotting tool, this renders the
+//! series already buffered inside a [`LineGraph`] to a standalone PNG — axes
+//! with labeled min/max ticks from the graph's configured ranges, each series
+//! in its stored RGBA color, and the time axis annotated in mission H:M:S. The
+//! live GUI is not needed and the logs are not re-read; the points held by the
+//! graph are reused directly.
+
+use image::{self, Rgba, RgbaImage};
+
+use line_graph::LineGraph;
+
+// Margins around the plotting area, leaving room for tick labels.
+const LEFT: u32 = 60;
+const RIGHT: u32 = 20;
+const TOP: u32 = 20;
+const BOTTOM: u32 = 40;
+
+const BG: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const AXIS: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Render `graph` to `path` as a PNG. `elapsed_secs` spans the full x range and
+/// is used to label the time axis in mission H:M:S.
+pub fn export_png(graph: &LineGraph, path: &str, elapsed_secs: f64) {
+    let width = graph.size.0 as u32 + LEFT + RIGHT;
+    let height = graph.size.1 as u32 + TOP + BOTTOM;
+    let mut img = RgbaImage::from_pixel(width, height, BG);
+
+    let plot_w = graph.size.0;
+    let plot_h = graph.size.1;
+    let x0 = LEFT;
+    let y0 = TOP;
+
+    // Axes: left and bottom of the plotting rectangle.
+    draw_line(&mut img, x0, y0, x0, y0 + plot_h as u32, AXIS);
+    draw_line(&mut img, x0, y0 + plot_h as u32, x0 + plot_w as u32, y0 + plot_h as u32, AXIS);
+
+    // Y ticks: configured min (bottom) and max (top).
+    draw_text(&mut img, 4, y0, &format!("{:.1}", graph.y_interval.1), AXIS);
+    draw_text(&mut img, 4, y0 + plot_h as u32 - 7, &format!("{:.1}", graph.y_interval.0), AXIS);
+
+    // X ticks: mission H:M:S at the start and end of the captured window.
+    draw_text(&mut img, x0, y0 + plot_h as u32 + 6, &hms(0.0), AXIS);
+    let end = hms(elapsed_secs);
+    let end_x = x0 + plot_w as u32 - (end.len() as u32 * 6);
+    draw_text(&mut img, end_x, y0 + plot_h as u32 + 6, &end, AXIS);
+
+    // Series, each in its stored color.
+    let (xmin, xmax) = graph.x_interval;
+    let (ymin, ymax) = graph.y_interval;
+    for (color, points) in graph.series() {
+        let px = Rgba([(color[0] * 255.0) as u8, (color[1] * 255.0) as u8,
+                       (color[2] * 255.0) as u8, (color[3] * 255.0) as u8]);
+        let mut prev: Option<(u32, u32)> = None;
+        for &(x, y) in points {
+            if x < xmin || x > xmax { continue; }
+            let fx = (x - xmin) / (xmax - xmin);
+            let fy = (y - ymin) / (ymax - ymin);
+            let sx = x0 + (fx * plot_w) as u32;
+            let sy = y0 + plot_h as u32 - (fy * plot_h).max(0.0).min(plot_h) as u32;
+            if let Some((px_x, px_y)) = prev {
+                draw_line(&mut img, px_x, px_y, sx, sy, px);
+            }
+            prev = Some((sx, sy));
+        }
+    }
+
+    if let Ok(ref mut fout) = ::std::fs::File::create(path) {
+        img.save(fout, image::PNG).ok();
+    }
+}
+
+/// Format a count of seconds as `H:MM:SS`.
+fn hms(secs: f64) -> String {
+    let s = secs.max(0.0) as i64;
+    format!("{}:{:02}:{:02}", s / 3600, (s % 3600) / 60, s % 60)
+}
+
+/// Bresenham line into the image, clipped to its bounds.
+fn draw_line(img: &mut RgbaImage, x0: u32, y0: u32, x1: u32, y1: u32, color: Rgba<u8>) {
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    let (mut x0, mut y0) = (x0 as i64, y0 as i64);
+    let (x1, y1) = (x1 as i64, y1 as i64);
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    loop {
+        if x0 >= 0 && x0 < w && y0 >= 0 && y0 < h {
+            img.put_pixel(x0 as u32, y0 as u32, color);
+        }
+        if x0 == x1 && y0 == y1 { break; }
+        let e2 = 2 * err;
+        if e2 >= dy { err += dy; x0 += sx; }
+        if e2 <= dx { err += dx; y0 += sy; }
+    }
+}
+
+/// Render a short ASCII string with the embedded 5x7 font at `(x, y)`.
+fn draw_text(img: &mut RgbaImage, x: u32, y: u32, text: &str, color: Rgba<u8>) {
+    let mut cx = x;
+    for ch in text.chars() {
+        let glyph = glyph(ch);
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (1 << (4 - col)) != 0 {
+                    let px = cx + col;
+                    let py = y + row as u32;
+                    if px < img.width() && py < img.height() {
+                        img.put_pixel(px, py, color);
+                    }
+                }
+            }
+        }
+        cx += 6;
+    }
+}
+
+/// 5x7 bitmap for the glyphs used in axis labels: digits, `:`, `.`, `-`, space.
+fn glyph(ch: char) -> [u8; 7] {
+    match ch {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        ':' => [0b00000, 0b00100, 0b00100, 0b00000, 0b00100, 0b00100, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00110, 0b00110],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        _   => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+    }
+}
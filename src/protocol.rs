@@ -0,0 +1,167 @@
+//! Typed, length-prefixed wire format for rover <-> GUI traffic.
+//!
+//! The legacy protocol shipped every packet as a formatted UTF-8 string, so any
+//! stray non-UTF-8 byte silently dropped a whole datagram and high-rate numeric
+//! telemetry paid the cost of `format!` on both ends. A frame here is:
+//!
+//! ```text
+//! | kind: u8 | flags: u8 | len: u16 LE | payload: [u8; len] |
+//! ```
+//!
+//! Multi-field telemetry is packed as little-endian `f32`s rather than text.
+//! Payloads at or above `COMPRESS_THRESHOLD` are deflated and flagged, following the
+//! compress-on-demand pattern used elsewhere for screen frames.
+
+use flate2::Compression;
+use flate2::read::{ZlibDecoder, ZlibEncoder};
+use std::io::Read;
+
+/// Bump when the frame layout or any `MsgKind` encoding changes.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Deflate payloads at or above this size; smaller ones aren't worth the CPU.
+const COMPRESS_THRESHOLD: usize = 64;
+
+/// Bit in the flags byte marking a deflated payload.
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+
+/// A decoded message. Telemetry variants carry parsed numerics; `Legacy` is the
+/// rollout bridge for peers still speaking the old string protocol.
+pub enum Msg {
+    Gps { lat: f32, lon: f32, speed: f32, altitude: f32, angle: f32 },
+    Imu { accel: [f32; 3], gyro: [f32; 3], mag: [f32; 3] },
+    RpmStatus { l: f32, r: f32 },
+    /// Raw command bytes (drive/camera/SADL strings) passed through verbatim.
+    Command(Vec<u8>),
+    /// An old-style UTF-8 string packet.
+    Legacy(String),
+}
+
+impl Msg {
+    fn kind(&self) -> u8 {
+        match *self {
+            Msg::Gps { .. } => 1,
+            Msg::Imu { .. } => 2,
+            Msg::RpmStatus { .. } => 3,
+            Msg::Command(_) => 4,
+            Msg::Legacy(_) => 255,
+        }
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut out = vec![];
+        match *self {
+            Msg::Gps { lat, lon, speed, altitude, angle } => {
+                for v in &[lat, lon, speed, altitude, angle] { push_f32(&mut out, *v); }
+            },
+            Msg::Imu { accel, gyro, mag } => {
+                for v in accel.iter().chain(gyro.iter()).chain(mag.iter()) { push_f32(&mut out, *v); }
+            },
+            Msg::RpmStatus { l, r } => { push_f32(&mut out, l); push_f32(&mut out, r); },
+            Msg::Command(ref bytes) => out.extend_from_slice(bytes),
+            Msg::Legacy(ref s) => out.extend_from_slice(s.as_bytes()),
+        }
+        out
+    }
+
+    /// Render the old string form so existing `handle_packet`s keep working
+    /// during rollout.
+    pub fn to_legacy_string(&self) -> String {
+        match *self {
+            Msg::Gps { lat, lon, speed, altitude, angle } =>
+                format!("GPS:{}:{}:{}:{}:{}", lat, lon, speed, altitude, angle),
+            Msg::Imu { accel, gyro, mag } =>
+                format!("IMU:{}:{}:{}:{}:{}:{}:{}:{}:{}", accel[0], accel[1], accel[2],
+                        gyro[0], gyro[1], gyro[2], mag[0], mag[1], mag[2]),
+            Msg::RpmStatus { l, r } => format!("RPM_STATUS:{}:{}", l as i32, r as i32),
+            Msg::Legacy(ref s) => s.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+fn push_f32(out: &mut Vec<u8>, v: f32) {
+    let bits = v.to_bits();
+    out.push(bits as u8);
+    out.push((bits >> 8) as u8);
+    out.push((bits >> 16) as u8);
+    out.push((bits >> 24) as u8);
+}
+
+fn read_f32(buf: &[u8], i: usize) -> f32 {
+    let bits = (buf[i] as u32) | ((buf[i + 1] as u32) << 8)
+             | ((buf[i + 2] as u32) << 16) | ((buf[i + 3] as u32) << 24);
+    f32::from_bits(bits)
+}
+
+/// Encode a message into a framed, optionally-compressed datagram.
+pub fn encode(msg: &Msg) -> Vec<u8> {
+    let payload = msg.payload();
+
+    let (flags, payload) =
+        if payload.len() >= COMPRESS_THRESHOLD {
+            let mut encoder = ZlibEncoder::new(&payload[..], Compression::fast());
+            let mut compressed = vec![];
+            encoder.read_to_end(&mut compressed).unwrap();
+            (FLAG_COMPRESSED, compressed)
+        } else {
+            (0, payload)
+        };
+
+    let mut out = Vec::with_capacity(payload.len() + 4);
+    out.push(msg.kind());
+    out.push(flags);
+    out.push(payload.len() as u8);
+    out.push((payload.len() >> 8) as u8);
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// Decode a framed datagram, transparently inflating a compressed payload.
+/// Returns `None` for a truncated or unknown frame; callers fall back to the
+/// legacy string path when that happens.
+pub fn decode(buf: &[u8]) -> Option<Msg> {
+    if buf.len() < 4 {
+        return None;
+    }
+    let kind = buf[0];
+    let flags = buf[1];
+    let len = (buf[2] as usize) | ((buf[3] as usize) << 8);
+    if buf.len() < 4 + len {
+        return None;
+    }
+
+    let payload =
+        if flags & FLAG_COMPRESSED != 0 {
+            let mut decoder = ZlibDecoder::new(&buf[4..4 + len]);
+            let mut out = vec![];
+            if decoder.read_to_end(&mut out).is_err() {
+                return None;
+            }
+            out
+        } else {
+            buf[4..4 + len].to_vec()
+        };
+
+    match kind {
+        1 if payload.len() >= 20 => Some(Msg::Gps {
+            lat: read_f32(&payload, 0),
+            lon: read_f32(&payload, 4),
+            speed: read_f32(&payload, 8),
+            altitude: read_f32(&payload, 12),
+            angle: read_f32(&payload, 16),
+        }),
+        2 if payload.len() >= 36 => Some(Msg::Imu {
+            accel: [read_f32(&payload, 0), read_f32(&payload, 4), read_f32(&payload, 8)],
+            gyro: [read_f32(&payload, 12), read_f32(&payload, 16), read_f32(&payload, 20)],
+            mag: [read_f32(&payload, 24), read_f32(&payload, 28), read_f32(&payload, 32)],
+        }),
+        3 if payload.len() >= 8 => Some(Msg::RpmStatus {
+            l: read_f32(&payload, 0),
+            r: read_f32(&payload, 4),
+        }),
+        4 => Some(Msg::Command(payload)),
+        255 => String::from_utf8(payload).ok().map(Msg::Legacy),
+        _ => None,
+    }
+}
@@ -0,0 +1,228 @@
+//! Self-describing binary mission log, in the spirit of PX4's sdlog2.
+//!
+//! The log is a flat stream of length-prefixed records. Each record is
+//! `len: u16 LE` followed by `len` body bytes, and the first body byte tags the
+//! record kind:
+//!
+//! * `b'F'` FORMAT - `msg_id: u8`, a length-prefixed short name (e.g. `VOLT`),
+//!   a length-prefixed run of field type chars, and a length-prefixed
+//!   comma-joined list of field labels. Emitted once per message type at
+//!   startup.
+//! * `b'D'` DATA - `msg_id: u8` followed by the little-endian field bytes laid
+//!   out in the order its FORMAT declared.
+//!
+//! Supported field type chars: `f` f32, `d` f64, `b` i8, `Q` u64 (the
+//! microsecond timestamp). Because every DATA record points back at a FORMAT
+//! carried in the same stream, fields can be added or reordered later without
+//! breaking an older decoder.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+
+/// One field of a message: its type char and label.
+pub struct Field {
+    pub ty: char,
+    pub label: String,
+}
+
+/// A decoded message definition (its in-band FORMAT record).
+pub struct MessageFormat {
+    pub id: u8,
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+impl MessageFormat {
+    /// Number of bytes one DATA payload (excluding the `msg_id`) occupies.
+    pub fn payload_len(&self) -> usize {
+        self.fields.iter().map(|f| type_size(f.ty)).sum()
+    }
+}
+
+/// Byte width of a field type char.
+pub fn type_size(ty: char) -> usize {
+    match ty {
+        'b' => 1,
+        'f' => 4,
+        'd' | 'Q' => 8,
+        _ => 0,
+    }
+}
+
+fn build_format(id: u8, name: &str, spec: &[(char, &str)]) -> MessageFormat {
+    MessageFormat {
+        id: id,
+        name: name.to_string(),
+        fields: spec.iter().map(|&(ty, label)| Field { ty: ty, label: label.to_string() }).collect(),
+    }
+}
+
+/// The message types this ground station logs. Every record carries a leading
+/// `TimeUS` (`Q`) microsecond stamp; the remaining fields are `d` (f64) to
+/// match the values the UI already keeps.
+pub fn schema() -> Vec<MessageFormat> {
+    vec![
+        build_format(0, "IMU",  &[('Q', "TimeUS"), ('d', "Pitch"), ('d', "Roll"), ('d', "Head")]),
+        build_format(1, "GPS",  &[('Q', "TimeUS"), ('d', "Lat"), ('d', "Lon"), ('d', "Spd"), ('d', "Alt"), ('d', "Ang")]),
+        build_format(2, "VOLT", &[('Q', "TimeUS"), ('d', "H48"), ('d', "H24"), ('d', "P12E"), ('d', "P12PL")]),
+        build_format(3, "AMP",  &[('Q', "TimeUS"), ('d', "H24"), ('d', "P12E"), ('d', "LMot"), ('d', "RMot")]),
+        build_format(4, "MTMP", &[('Q', "TimeUS"), ('d', "LMot"), ('d', "RMot"), ('d', "UprA"), ('d', "LwrA")]),
+        build_format(5, "WTHR", &[('Q', "TimeUS"), ('d', "Wind"), ('d', "Pres"), ('d', "Alt"), ('d', "Temp")]),
+    ]
+}
+
+/// Writes FORMAT records up front, then a DATA record per `log` call.
+pub struct BinLogger {
+    out: BufWriter<File>,
+    ids: HashMap<String, u8>,
+    lens: HashMap<u8, usize>,
+}
+
+impl BinLogger {
+    /// Create the log and emit a FORMAT record for every message in `schema()`.
+    pub fn new(path: &str) -> BinLogger {
+        let mut logger = BinLogger {
+            out: BufWriter::new(File::create(path).unwrap()),
+            ids: HashMap::new(),
+            lens: HashMap::new(),
+        };
+        for fmt in schema() {
+            logger.ids.insert(fmt.name.clone(), fmt.id);
+            logger.lens.insert(fmt.id, fmt.fields.len() - 1); // minus TimeUS
+            logger.write_format(&fmt);
+        }
+        logger
+    }
+
+    fn write_record(&mut self, body: &[u8]) {
+        let len = body.len() as u16;
+        self.out.write_all(&[len as u8, (len >> 8) as u8]).unwrap();
+        self.out.write_all(body).unwrap();
+    }
+
+    fn write_format(&mut self, fmt: &MessageFormat) {
+        let mut body = vec![b'F', fmt.id];
+        body.push(fmt.name.len() as u8);
+        body.extend_from_slice(fmt.name.as_bytes());
+        let types: String = fmt.fields.iter().map(|f| f.ty).collect();
+        body.push(types.len() as u8);
+        body.extend_from_slice(types.as_bytes());
+        let labels = fmt.fields.iter().map(|f| f.label.as_str())
+                               .collect::<Vec<_>>().join(",");
+        body.push(labels.len() as u8);
+        body.extend_from_slice(labels.as_bytes());
+        self.write_record(&body);
+    }
+
+    /// Append a DATA record for message `name`: the microsecond timestamp
+    /// `time_us` followed by one f64 per declared field. Unknown names are
+    /// ignored so a caller can log optimistically.
+    pub fn log(&mut self, name: &str, time_us: u64, values: &[f64]) {
+        let id = match self.ids.get(name) {
+            Some(id) => *id,
+            None => return,
+        };
+        let mut body = vec![b'D', id];
+        body.extend_from_slice(&u64_le(time_us));
+        for v in values {
+            body.extend_from_slice(&f64_le(*v));
+        }
+        self.write_record(&body);
+    }
+}
+
+fn u64_le(v: u64) -> [u8; 8] {
+    let mut b = [0u8; 8];
+    for i in 0..8 { b[i] = (v >> (8 * i)) as u8; }
+    b
+}
+
+fn f64_le(v: f64) -> [u8; 8] {
+    u64_le(v.to_bits())
+}
+
+/// A single decoded DATA record: the format it references and its raw payload.
+pub struct Record {
+    pub id: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Read an entire log, returning the FORMAT table (keyed by msg_id) and the
+/// DATA records in file order. Used by the `logconv` tool.
+pub fn read_log(path: &str) -> (HashMap<u8, MessageFormat>, Vec<Record>) {
+    let mut file = File::open(path).unwrap();
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).unwrap();
+
+    let mut formats = HashMap::new();
+    let mut records = Vec::new();
+
+    let mut pos = 0;
+    while pos + 2 <= bytes.len() {
+        let len = (bytes[pos] as usize) | ((bytes[pos + 1] as usize) << 8);
+        pos += 2;
+        if pos + len > bytes.len() { break; }
+        let body = &bytes[pos..pos + len];
+        pos += len;
+        if body.is_empty() { continue; }
+
+        match body[0] {
+            b'F' => {
+                if let Some(fmt) = parse_format(&body[1..]) {
+                    formats.insert(fmt.id, fmt);
+                }
+            },
+            b'D' if body.len() >= 2 => {
+                records.push(Record { id: body[1], payload: body[2..].to_vec() });
+            },
+            _ => { },
+        }
+    }
+
+    (formats, records)
+}
+
+fn parse_format(body: &[u8]) -> Option<MessageFormat> {
+    let mut p = 0;
+    let id = *body.get(p)?; p += 1;
+    let name_len = *body.get(p)? as usize; p += 1;
+    let name = String::from_utf8(body.get(p..p + name_len)?.to_vec()).ok()?; p += name_len;
+    let ty_len = *body.get(p)? as usize; p += 1;
+    let types: Vec<char> = body.get(p..p + ty_len)?.iter().map(|b| *b as char).collect(); p += ty_len;
+    let lbl_len = *body.get(p)? as usize; p += 1;
+    let labels = String::from_utf8(body.get(p..p + lbl_len)?.to_vec()).ok()?;
+
+    let fields = types.into_iter().zip(labels.split(','))
+                      .map(|(ty, label)| Field { ty: ty, label: label.to_string() })
+                      .collect();
+    Some(MessageFormat { id: id, name: name, fields: fields })
+}
+
+/// Decode one field from `payload` at `offset`, returning a display string and
+/// the number of bytes consumed.
+pub fn decode_field(ty: char, payload: &[u8], offset: usize) -> (String, usize) {
+    let size = type_size(ty);
+    if offset + size > payload.len() {
+        return (String::new(), size);
+    }
+    let slice = &payload[offset..offset + size];
+    let text = match ty {
+        'b' => (slice[0] as i8).to_string(),
+        'f' => {
+            let bits = (slice[0] as u32) | ((slice[1] as u32) << 8)
+                | ((slice[2] as u32) << 16) | ((slice[3] as u32) << 24);
+            f32::from_bits(bits).to_string()
+        },
+        'd' => f64::from_bits(le_u64(slice)).to_string(),
+        'Q' => le_u64(slice).to_string(),
+        _ => String::new(),
+    };
+    (text, size)
+}
+
+fn le_u64(b: &[u8]) -> u64 {
+    let mut v = 0u64;
+    for i in 0..8 { v |= (b[i] as u64) << (8 * i); }
+    v
+}
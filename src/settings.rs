@@ -0,0 +1,180 @@
+//! Persistent, serde-backed controller bindings and rover endpoint.
+//!
+//! Axis/button assignments, per-axis inversion, the RPM ceiling and the
+//! rover's destination address used to be hardcoded in `main`. This captures
+//! them in a JSON profile loaded at startup (falling back to the built-in
+//! defaults if the file is absent or unreadable) and written back whenever a
+//! binding changes, so one binary can drive different rovers and adapt to
+//! whatever pad is plugged in.
+
+use std::fs::File;
+use std::io::{Read, Write};
+
+extern crate serde_json;
+
+/// Path of the on-disk profile, next to the binary.
+pub const SETTINGS_PATH: &'static str = "controller_settings.json";
+
+/// One analog-axis assignment: the SDL controller axis index and whether its
+/// sign is inverted (sticks report "up" as negative, so forward is inverted).
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct AxisBinding {
+    pub axis: i32,
+    pub invert: bool,
+}
+
+/// The full set of bindings and endpoint parameters.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ControllerSettings {
+    pub left_rpm_axis: AxisBinding,
+    pub right_rpm_axis: AxisBinding,
+    pub stop_button: i32,
+    pub pan_left_button: i32,
+    pub pan_right_button: i32,
+    pub tilt_down_button: i32,
+    pub tilt_up_button: i32,
+    pub max_rpm: f32,
+    pub rover_ip: String,
+    pub rover_port: u16,
+}
+
+impl ControllerSettings {
+    /// The compiled-in defaults, matching the historical hardcoded mapping:
+    /// inverted left/right sticks, D-pad camera, `A` to stop, 2000 RPM, and the
+    /// `10.10.153.25:30001` rover endpoint.
+    pub fn defaults() -> ControllerSettings {
+        ControllerSettings {
+            left_rpm_axis: AxisBinding { axis: 1, invert: true },   // LeftY
+            right_rpm_axis: AxisBinding { axis: 3, invert: true },  // RightY
+            stop_button: 0,        // A
+            pan_left_button: 13,   // DPadLeft
+            pan_right_button: 14,  // DPadRight
+            tilt_down_button: 12,  // DPadDown
+            tilt_up_button: 11,    // DPadUp
+            max_rpm: 2000.0,
+            rover_ip: "10.10.153.25".to_string(),
+            rover_port: 30001,
+        }
+    }
+
+    /// Load the profile from `path`, falling back to the defaults if it is
+    /// missing or cannot be parsed.
+    pub fn load(path: &str) -> ControllerSettings {
+        ControllerSettings::load_or(path, ControllerSettings::defaults())
+    }
+
+    /// Load the profile from `path`, or use `fallback` (e.g. a family-specific
+    /// default set) if it is missing or cannot be parsed.
+    pub fn load_or(path: &str, fallback: ControllerSettings) -> ControllerSettings {
+        if let Ok(mut file) = File::open(path) {
+            let mut buf = String::new();
+            if file.read_to_string(&mut buf).is_ok() {
+                if let Ok(settings) = serde_json::from_str(buf.as_str()) {
+                    return settings;
+                }
+            }
+        }
+        fallback
+    }
+
+    /// Persist the profile back to `path`. Errors are swallowed - a failed
+    /// write shouldn't take down the GUI.
+    pub fn save(&self, path: &str) {
+        if let Ok(text) = serde_json::to_string_pretty(self) {
+            if let Ok(mut file) = File::create(path) {
+                file.write_all(text.as_bytes()).ok();
+            }
+        }
+    }
+
+    /// Store a captured axis into `slot`; ignores button slots.
+    pub fn set_axis(&mut self, slot: BindSlot, axis: i32) {
+        match slot {
+            BindSlot::LeftRpmAxis => self.left_rpm_axis.axis = axis,
+            BindSlot::RightRpmAxis => self.right_rpm_axis.axis = axis,
+            _ => {},
+        }
+    }
+
+    /// Store a captured button into `slot`; ignores axis slots.
+    pub fn set_button(&mut self, slot: BindSlot, button: i32) {
+        match slot {
+            BindSlot::StopButton => self.stop_button = button,
+            BindSlot::PanLeft => self.pan_left_button = button,
+            BindSlot::PanRight => self.pan_right_button = button,
+            BindSlot::TiltUp => self.tilt_up_button = button,
+            BindSlot::TiltDown => self.tilt_down_button = button,
+            _ => {},
+        }
+    }
+}
+
+/// Known controller families, used to pick sensible default bindings and to
+/// label the on-screen controls.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ControllerFamily {
+    Xbox,
+    PlayStation,
+    SwitchPro,
+    Generic,
+}
+
+impl ControllerFamily {
+    /// Classify a pad from its SDL name; anything unrecognized is `Generic`.
+    /// The axis/button counts are accepted but not consulted.
+    pub fn detect(name: &str, num_axes: i32, num_buttons: i32) -> ControllerFamily {
+        let n = name.to_lowercase();
+        if n.contains("xbox") || n.contains("x-box") {
+            ControllerFamily::Xbox
+        } else if n.contains("playstation") || n.contains("dualshock")
+                || n.contains("dualsense") || n.contains("sony") || n.contains("ps") {
+            ControllerFamily::PlayStation
+        } else if n.contains("switch") || n.contains("nintendo") || n.contains("pro controller") {
+            ControllerFamily::SwitchPro
+        } else {
+            // Unrecognized name: a two-stick pad still gets the generic mapping,
+            // anything smaller is treated as generic too.
+            let _ = (num_axes, num_buttons);
+            ControllerFamily::Generic
+        }
+    }
+
+    /// A family-appropriate default binding set. SDL's game-controller layer
+    /// already normalizes the button/axis layout, so the families share the
+    /// two-stick + D-pad mapping; this is the hook for future per-family tweaks.
+    pub fn default_bindings(&self) -> ControllerSettings {
+        ControllerSettings::defaults()
+    }
+
+    /// Human-readable family name for logging.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            ControllerFamily::Xbox => "Xbox",
+            ControllerFamily::PlayStation => "PlayStation",
+            ControllerFamily::SwitchPro => "Switch Pro",
+            ControllerFamily::Generic => "Generic",
+        }
+    }
+
+    /// The name of the face button used to stop, for UI labels.
+    pub fn stop_button_name(&self) -> &'static str {
+        match *self {
+            ControllerFamily::Xbox => "A",
+            ControllerFamily::PlayStation => "Cross",
+            ControllerFamily::SwitchPro => "A",
+            ControllerFamily::Generic => "Button 0",
+        }
+    }
+}
+
+/// Which binding the "listen for next input" mode is currently capturing into.
+#[derive(Copy, Clone, PartialEq)]
+pub enum BindSlot {
+    LeftRpmAxis,
+    RightRpmAxis,
+    StopButton,
+    PanLeft,
+    PanRight,
+    TiltUp,
+    TiltDown,
+}
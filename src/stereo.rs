@@ -23,6 +23,8 @@ use stereo_ui::StereoUi;
 use video_stream::{init_ffmpeg, start_video_stream, VideoMsg};
 
 mod conrod_config;
+mod interp;
+mod metrics;
 mod stereo_ui;
 mod video_stream;
 mod imu;
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
 use std::net::UdpSocket;
@@ -28,7 +29,7 @@ use time;
 
 use conrod_config;
 use imu;
-use video_stream::VideoMsg;
+use video_stream::{VideoMsg, RecordMode};
 
 enum MissionTime {
     Paused(time::Duration),
@@ -49,14 +50,24 @@ pub struct StereoUi {
     pub last_tilt_time: time::Tm,
 
     client: UdpSocket,
-    
+
+    // One recording channel per camera, the mission bundle folder, and the
+    // live recording state so start/stop is driven from the UI rather than
+    // from the stream threads.
+    vid_senders: Vec<Sender<VideoMsg>>,
+    mission_folder: String,
+    recording: bool,
+    recording_start: Option<time::Tm>,
+
     out_queue: VecDeque<(time::Tm, time::Duration, Vec<u8>, (String, u16))>, // Outbound packet queue
     delay: time::Duration,
     delay_str: String,
 }
 
 impl StereoUi {
-    pub fn new(client: UdpSocket) -> StereoUi {
+    pub fn new(client: UdpSocket,
+               vid0_t: Sender<VideoMsg>, vid1_t: Sender<VideoMsg>, vid2_t: Sender<VideoMsg>,
+               mission_folder: String) -> StereoUi {
         StereoUi {
             bg_color: rgb(0.2, 0.35, 0.45),
 
@@ -71,12 +82,66 @@ impl StereoUi {
 
             client: client,
 
+            vid_senders: vec![vid0_t, vid1_t, vid2_t],
+            mission_folder: mission_folder,
+            recording: false,
+            recording_start: None,
+
             out_queue: VecDeque::new(),
             delay: time::Duration::seconds(0),
             delay_str: "".to_string(),
         }
     }
 
+    /// Start or stop recording every camera into the mission folder. Starting
+    /// opens a timestamped file per camera and anchors a shared mission-time
+    /// origin; stopping closes each recording. The operator never touches the
+    /// stream threads directly.
+    pub fn toggle_recording(&mut self) {
+        if self.recording {
+            for tx in &self.vid_senders {
+                tx.send(VideoMsg::Stop).ok();
+            }
+            self.recording = false;
+            self.recording_start = None;
+            self.stamp_sync("recording_stop");
+        } else {
+            let stamp = time::now().strftime("%Y%b%d_%H_%M_%S").unwrap();
+            for (i, tx) in self.vid_senders.iter().enumerate() {
+                let path = format!("mission_data/{}/camera{}_{}.mp4", self.mission_folder, i, stamp);
+                tx.send(VideoMsg::Start(path, RecordMode::FragmentedMp4 { segment_secs: 60 })).ok();
+            }
+            self.recording_start = Some(time::now());
+            self.recording = true;
+            self.stamp_sync("recording_start");
+        }
+    }
+
+    /// Append an event to the mission `sync.log`, the common timeline the
+    /// recorded video and telemetry log are aligned against during review.
+    fn stamp_sync(&self, event: &str) {
+        let path = format!("mission_data/{}/sync.log", self.mission_folder);
+        if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(path.as_str()) {
+            let now = time::now().strftime("%Y-%m-%dT%H:%M:%S").unwrap();
+            writeln!(f, "{} {}", now, event).ok();
+        }
+    }
+
+    /// While recording, mirror an incoming telemetry packet into the bundle's
+    /// telemetry log, stamped with milliseconds since the recording origin so
+    /// frames and telemetry can be replayed together.
+    fn log_telemetry(&self, packet: &str) {
+        let origin = match self.recording_start {
+            Some(t) => t,
+            None => return,
+        };
+        let t_ms = (time::now() - origin).num_milliseconds();
+        let path = format!("mission_data/{}/telemetry.log", self.mission_folder);
+        if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(path.as_str()) {
+            writeln!(f, "{} {}", t_ms, packet).ok();
+        }
+    }
+
     pub fn update(&mut self, dt: f64) {
         let dt = dt as f32;
 
@@ -152,11 +217,28 @@ impl StereoUi {
             .label("Snapshot")
             .react(|| { self.send_snapshot(); })
             .set(SNAPSHOT_BUTTON, ui);
+
+        // Recording toggle: opens/closes a mission bundle across all cameras.
+        let (rec_label, rec_rgb) = if self.recording {
+            ("Stop Rec", (0.8, 0.2, 0.2))
+        } else {
+            ("Record", (0.3, 0.8, 0.3))
+        };
+        Button::new()
+            .w_h(120.0, 30.0)
+            .x_y(80.0, (ui.win_h / 2.0) - 645.0)
+            .rgb(rec_rgb.0, rec_rgb.1, rec_rgb.2)
+            .frame(1.0)
+            .label(rec_label)
+            .react(|| { self.toggle_recording(); })
+            .set(REC_BUTTON, ui);
     }
 
     pub fn handle_packet(&mut self, packet: String) {
         //println!("{}", packet);
 
+        self.log_telemetry(packet.as_str());
+
         let packets = packet.split("|");
 
         for packet in packets {
@@ -188,6 +270,10 @@ impl StereoUi {
                 // Camera right
                 self.panning = 1.0;
             },
+            R => {
+                // Toggle recording of the whole camera bundle.
+                self.toggle_recording();
+            },
             _ => { },
         }
     }
@@ -286,6 +372,7 @@ widget_ids! {
     F_PAN_SLIDER,
     F_TILT_SLIDER,
     SNAPSHOT_BUTTON,
+    REC_BUTTON,
     MODE_LABEL,
     MODE_TOGGLE_BUTTON,
 }
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 
@@ -20,15 +19,42 @@ use graphics::{Context, Graphics};
 use piston_window;
 use time;
 
+use acmi::{self, AcmiRecorder};
+use attitude::AttitudeIndicator;
 use avg_val::AvgVal;
+use biquad::Biquad;
 use conrod_config;
+use gate::Gate;
+use geodesy::{self, GroundTrack};
 use line_graph::LineGraph;
+use params::Params;
+use plot_export;
+use sdlog::BinLogger;
+use ublox;
+
+/// Config file the RYG limits and graph scales are loaded from / saved to.
+const PARAMS_PATH: &'static str = "telemetry_params.conf";
 
 enum MissionTime {
     Paused(time::Duration),
     Running(time::Tm, time::Duration),
 }
 
+/// Playback of a loaded ACMI recording. Frames are fed to `handle_packet` as
+/// their timestamps come due against a wall-clock start, mirroring the way the
+/// mission-time buttons run and reset the live clock.
+struct Replay {
+    frames: Vec<(f64, Vec<String>)>,
+    next: usize,
+    started: Option<time::Tm>,
+}
+
+impl Replay {
+    fn new(frames: Vec<(f64, Vec<String>)>) -> Replay {
+        Replay { frames: frames, next: 0, started: None }
+    }
+}
+
 pub enum RygLimit {
     LessThan(f64, f64),
     GreaterThan(f64, f64),
@@ -36,29 +62,56 @@ pub enum RygLimit {
 
 impl RygLimit {
     pub fn get_color(&self, value: f64) -> Color {
+        match self.severity(value) {
+            2 => rgb(1.0, 0.0, 0.0),
+            1 => rgb(1.0, 1.0, 0.0),
+            _ => rgb(0.0, 1.0, 0.0),
+        }
+    }
+
+    /// Discrete severity of `value`: 0 green (nominal), 1 yellow (warning),
+    /// 2 red (fault). Used both for coloring and for the worst-of rollup that
+    /// drives the KML track state.
+    pub fn severity(&self, value: f64) -> u8 {
         match *self {
             RygLimit::LessThan(r, y) => {
-                if value < r {
-                    rgb(1.0, 0.0, 0.0)
-                } else if value < y {
-                    rgb(1.0, 1.0, 0.0)
-                } else {
-                    rgb(0.0, 1.0, 0.0)
-                }
+                if value < r { 2 } else if value < y { 1 } else { 0 }
             },
             RygLimit::GreaterThan(r, y) => {
-                if value > r {
-                    rgb(1.0, 0.0, 0.0)
-                } else if value > y {
-                    rgb(1.0, 1.0, 0.0)
-                } else {
-                    rgb(0.0, 1.0, 0.0)
-                }
+                if value > r { 2 } else if value > y { 1 } else { 0 }
             },
         }
     }
 }
 
+/// Worst-channel rollup recorded alongside each logged GPS fix, mapped to a
+/// KML line color in the AltOS post-flight style.
+#[derive(Copy, Clone, PartialEq)]
+enum TrackState {
+    Nominal,
+    Warning,
+    Fault,
+}
+
+impl TrackState {
+    fn from_severity(sev: u8) -> TrackState {
+        match sev {
+            2 => TrackState::Fault,
+            1 => TrackState::Warning,
+            _ => TrackState::Nominal,
+        }
+    }
+
+    /// KML `aabbggrr` color (alpha, blue, green, red), fully opaque.
+    fn kml_color(&self) -> &'static str {
+        match *self {
+            TrackState::Nominal => "FF00FF00",
+            TrackState::Warning => "FF00FFFF",
+            TrackState::Fault => "FF0000FF",
+        }
+    }
+}
+
 pub struct TelemetryUi {
     bg_color: Color,
 
@@ -91,6 +144,12 @@ pub struct TelemetryUi {
     speed: Option<f64>,
     gps_altitude: Option<f64>,
     angle: Option<f64>,
+    // u-blox fix state: "no fix / configuring / 3D fix (N sats)"
+    gps_fix_type: u8,
+    gps_num_sats: u8,
+    gps_configured: bool,
+    // Running WGS84 ground-track statistics across consecutive fixes.
+    ground_track: GroundTrack,
 
     // Motor temp
     motor_temp_graph: LineGraph,
@@ -112,64 +171,96 @@ pub struct TelemetryUi {
     altitude: Option<f64>,
     temp: Option<f64>,
 
+    // Barometric altitude derived locally from pressure via the hypsometric
+    // formula, plus the sea-level reference it is computed against. The
+    // reference is recalibrated in the field against a known elevation.
+    sea_level_pressure: f64,
+    baro_altitude: Option<f64>,
+    baro_disagreement_limits: RygLimit,
+
     // IMU
     pitch_roll_heading: Option<(f64, f64, f64)>,
-
-    log_files: HashMap<String, BufWriter<File>>,
+    attitude_indicator: AttitudeIndicator,
+
+    // Per-channel low-pass filters smoothing the jittery raw readings before
+    // they reach the averagers and trend graphs. Fast electrical signals are
+    // filtered lightly; temperatures and wind are smoothed harder.
+    h_48_v_filter: Biquad,
+    h_24_v_filter: Biquad,
+    p_12_e_v_filter: Biquad,
+    p_12_pl_v_filter: Biquad,
+    l_motor_amp_filter: Biquad,
+    r_motor_amp_filter: Biquad,
+    p_12_e_a_filter: Biquad,
+    h_24_a_filter: Biquad,
+    l_motor_temp_filter: Biquad,
+    r_motor_temp_filter: Biquad,
+    upper_avionics_temp_filter: Biquad,
+    lower_avionics_temp_filter: Biquad,
+    ambient_temp_filter: Biquad,
+    wind_speed_filter: Biquad,
+
+    // Per-channel sanity gates: physical bounds, outlier rejection and the
+    // arrival time of the last accepted sample for staleness detection.
+    l_motor_temp_gate: Gate,
+    r_motor_temp_gate: Gate,
+    upper_avionics_temp_gate: Gate,
+    lower_avionics_temp_gate: Gate,
+    ambient_temp_gate: Gate,
+    wind_speed_gate: Gate,
+    pressure_gate: Gate,
+    altitude_gate: Gate,
+    temp_gate: Gate,
+
+    // How long a channel may go without an accepted sample before the display
+    // reverts to "NO DATA".
+    staleness_timeout: time::Duration,
+
+    // MAVLink link health: time of the last HEARTBEAT we decoded.
+    mav_last_heartbeat: Option<time::Tm>,
+
+    // Ordered GPS track, one entry per logged fix, tagged with the worst
+    // channel state at that moment for KML segment coloring.
+    gps_track: Vec<(f64, f64, f64, TrackState)>,
+
+    mission_folder: String,
+
+    // Reloadable alarm thresholds and graph scales.
+    params: Params,
+
+    bin_log: BinLogger,
+
+    // Tacview ACMI flight recording and offline replay.
+    acmi: Option<AcmiRecorder>,
+    acmi_start: time::Tm,
+    replay: Option<Replay>,
 }
 
 impl TelemetryUi {
     pub fn new(mission_folder: &str) -> TelemetryUi {
-        let v48_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0), (0.0, 80.0), vec![[1.0, 0.0, 0.0, 1.0]]);
-        let a24_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0), (0.0, 40.0), vec![[1.0, 0.0, 0.0, 1.0]]);
-        let v12_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0), (0.0, 20.0), vec![[1.0, 0.0, 0.0, 1.0]]);
+        // Alarm thresholds and graph scales come from the reloadable config.
+        let params = Params::load(PARAMS_PATH);
+
+        let v48_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0),
+                                       (0.0, params.get("V48_GRAPH.max", 80.0)), vec![[1.0, 0.0, 0.0, 1.0]]);
+        let a24_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0),
+                                       (0.0, params.get("A24_GRAPH.max", 40.0)), vec![[1.0, 0.0, 0.0, 1.0]]);
+        let v12_graph = LineGraph::new((400.0, 150.0), (0.0, 4.0 * 3600.0 * 2.0),
+                                       (0.0, params.get("V12_GRAPH.max", 20.0)), vec![[1.0, 0.0, 0.0, 1.0]]);
         let motor_temp_graph = LineGraph::new((400.0, 150.0),
                                               (0.0, 4.0 * 3600.0 * 2.0),
-                                              (0.0, 100.0),
+                                              (0.0, params.get("MOTOR_TEMP_GRAPH.max", 100.0)),
                                               vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]]);
 
-        // Create the log files
-        let mut log_files = HashMap::new();
-        log_files.insert("imu".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/imu",
-                                                             mission_folder).as_str()).unwrap()));
-        log_files.insert("gps".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/gps",
-                                                             mission_folder).as_str()).unwrap()));
-        log_files.insert("volt".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/volt",
-                                                             mission_folder).as_str()).unwrap()));
-        log_files.insert("amp".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/amp",
-                                                             mission_folder).as_str()).unwrap()));
-        log_files.insert("temp".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/motor_temp",
-                                                             mission_folder).as_str()).unwrap()));
-        log_files.insert("weather".to_string(),
-                         BufWriter::new(File::create(format!("mission_data/{}/weather",
-                                                             mission_folder).as_str()).unwrap()));
-        // Write log headers
-        log_files.get_mut("imu").unwrap().write_all("#pitch\troll\theading\n".as_bytes()).unwrap();
-        log_files.get_mut("gps")
-                 .unwrap()
-                 .write_all("#latitude\tlongitude\tspeed\taltitude\tangle\n".as_bytes())
-                 .unwrap();
-        log_files.get_mut("volt")
-                 .unwrap()
-                 .write_all("#H-48v\tH-24v\tP-12v E\tP-12-v PL\n".as_bytes())
-                 .unwrap();
-        log_files.get_mut("amp")
-                 .unwrap()
-                 .write_all("#H-24v\tP-12v E\ttL motor\tR motor\n".as_bytes())
-                 .unwrap();
-        log_files.get_mut("temp")
-                 .unwrap()
-                 .write_all("#L motor\tR motor\tUpper Avionics\tLower Avionics\n".as_bytes())
-                 .unwrap();
-        log_files.get_mut("weather")
-                 .unwrap()
-                 .write_all("#wind speed\tpressure\taltitude\ttemp\n".as_bytes())
-                 .unwrap();
+        // Single self-describing binary log; FORMAT records for every message
+        // type are written up front by the logger's constructor.
+        let bin_log = BinLogger::new(format!("mission_data/{}/log.bin", mission_folder).as_str());
+
+        // Open an ACMI recording for this mission alongside the binary log.
+        let acmi_start = time::now();
+        let reference_time = format!("{}", acmi_start.to_utc().strftime("%Y-%m-%dT%H:%M:%SZ").unwrap());
+        let acmi = AcmiRecorder::new(format!("mission_data/{}/mission.acmi", mission_folder).as_str(),
+                                     reference_time.as_str());
 
         TelemetryUi {
             bg_color: rgb(0.2, 0.35, 0.45),
@@ -178,20 +269,20 @@ impl TelemetryUi {
 
             v48_graph: v48_graph,
             h_48_v: AvgVal::new(60),
-            h_48_v_limits: RygLimit::LessThan(45.0, 48.0),
+            h_48_v_limits: RygLimit::LessThan(params.get("H_48_V.red", 45.0), params.get("H_48_V.yellow", 48.0)),
 
             a24_graph: a24_graph,
             h_24_v: AvgVal::new(60),
             h_24_a: AvgVal::new(30),
-            h_24_v_limits: RygLimit::LessThan(22.0, 24.0),
+            h_24_v_limits: RygLimit::LessThan(params.get("H_24_V.red", 22.0), params.get("H_24_V.yellow", 24.0)),
 
             v12_graph: v12_graph,
             p_12_e_v: AvgVal::new(60),
             p_12_e_a: AvgVal::new(30),
-            p_12_e_v_limits: RygLimit::LessThan(10.0, 12.0),
+            p_12_e_v_limits: RygLimit::LessThan(params.get("P_12_E_V.red", 10.0), params.get("P_12_E_V.yellow", 12.0)),
 
             p_12_pl_v: AvgVal::new(60),
-            p_12_pl_v_limits: RygLimit::LessThan(10.0, 12.0),
+            p_12_pl_v_limits: RygLimit::LessThan(params.get("P_12_PL_V.red", 10.0), params.get("P_12_PL_V.yellow", 12.0)),
 
             l_motor_amp: AvgVal::new(30),
             r_motor_amp: AvgVal::new(30),
@@ -202,59 +293,316 @@ impl TelemetryUi {
             speed: None,
             gps_altitude: None,
             angle: None,
+            gps_fix_type: 0,
+            gps_num_sats: 0,
+            gps_configured: false,
+            ground_track: GroundTrack::new(),
 
             motor_temp_graph: motor_temp_graph,
             l_motor_temp: AvgVal::new(40),
             r_motor_temp: AvgVal::new(40),
-            l_motor_temp_limits: RygLimit::GreaterThan(80.0, 60.0),
-            r_motor_temp_limits: RygLimit::GreaterThan(80.0, 60.0),
+            l_motor_temp_limits: RygLimit::GreaterThan(params.get("MOTOR_TEMP.red", 80.0), params.get("MOTOR_TEMP.yellow", 60.0)),
+            r_motor_temp_limits: RygLimit::GreaterThan(params.get("MOTOR_TEMP.red", 80.0), params.get("MOTOR_TEMP.yellow", 60.0)),
 
             upper_avionics_temp: AvgVal::new(30),
             lower_avionics_temp: AvgVal::new(30),
             ambient_temp: AvgVal::new(30),
-            upper_avionics_temp_limits: RygLimit::GreaterThan(60.0, 45.0),
-            lower_avionics_temp_limits: RygLimit::GreaterThan(60.0, 45.0),
+            upper_avionics_temp_limits: RygLimit::GreaterThan(params.get("AVIONICS_TEMP.red", 60.0), params.get("AVIONICS_TEMP.yellow", 45.0)),
+            lower_avionics_temp_limits: RygLimit::GreaterThan(params.get("AVIONICS_TEMP.red", 60.0), params.get("AVIONICS_TEMP.yellow", 45.0)),
 
             wind_speed: AvgVal::new(20),
             pressure: None,
             altitude: None,
             temp: None,
 
+            sea_level_pressure: params.get("SEA_LEVEL_PRESSURE", 1013.25),
+            baro_altitude: None,
+            baro_disagreement_limits: RygLimit::GreaterThan(params.get("BARO_DISAGREEMENT.red", 100.0),
+                                                            params.get("BARO_DISAGREEMENT.yellow", 50.0)),
+
             pitch_roll_heading: None,
+            attitude_indicator: AttitudeIndicator::new((160.0, 160.0)),
+
+            // Telemetry arrives at roughly 10 Hz; electrical channels keep a
+            // higher cutoff so surges stay visible while temperature and wind
+            // are smoothed much harder. Cutoffs are reloadable via params.
+            h_48_v_filter: Biquad::low_pass(params.get("V48_GRAPH.cutoff", 2.0), 10.0),
+            h_24_v_filter: Biquad::low_pass(params.get("H_24_V.cutoff", 2.0), 10.0),
+            p_12_e_v_filter: Biquad::low_pass(params.get("V12_GRAPH.cutoff", 2.0), 10.0),
+            p_12_pl_v_filter: Biquad::low_pass(params.get("P_12_PL_V.cutoff", 2.0), 10.0),
+            l_motor_amp_filter: Biquad::low_pass(params.get("MOTOR_AMP.cutoff", 2.0), 10.0),
+            r_motor_amp_filter: Biquad::low_pass(params.get("MOTOR_AMP.cutoff", 2.0), 10.0),
+            p_12_e_a_filter: Biquad::low_pass(params.get("P_12_E_A.cutoff", 2.0), 10.0),
+            h_24_a_filter: Biquad::low_pass(params.get("A24_GRAPH.cutoff", 2.0), 10.0),
+            l_motor_temp_filter: Biquad::low_pass(params.get("MOTOR_TEMP.cutoff", 0.5), 10.0),
+            r_motor_temp_filter: Biquad::low_pass(params.get("MOTOR_TEMP.cutoff", 0.5), 10.0),
+            upper_avionics_temp_filter: Biquad::low_pass(params.get("AVIONICS_TEMP.cutoff", 0.5), 10.0),
+            lower_avionics_temp_filter: Biquad::low_pass(params.get("AVIONICS_TEMP.cutoff", 0.5), 10.0),
+            ambient_temp_filter: Biquad::low_pass(params.get("AMBIENT_TEMP.cutoff", 0.5), 10.0),
+            wind_speed_filter: Biquad::low_pass(params.get("WIND_SPEED.cutoff", 1.0), 10.0),
+
+            // Physical bounds and per-interval jump limits for outlier rejection.
+            l_motor_temp_gate: Gate::new(params.get("MOTOR_TEMP.min", -20.0), params.get("MOTOR_TEMP.max", 200.0), params.get("MOTOR_TEMP.delta", 30.0)),
+            r_motor_temp_gate: Gate::new(params.get("MOTOR_TEMP.min", -20.0), params.get("MOTOR_TEMP.max", 200.0), params.get("MOTOR_TEMP.delta", 30.0)),
+            upper_avionics_temp_gate: Gate::new(params.get("AVIONICS_TEMP.min", -20.0), params.get("AVIONICS_TEMP.max", 150.0), params.get("AVIONICS_TEMP.delta", 25.0)),
+            lower_avionics_temp_gate: Gate::new(params.get("AVIONICS_TEMP.min", -20.0), params.get("AVIONICS_TEMP.max", 150.0), params.get("AVIONICS_TEMP.delta", 25.0)),
+            ambient_temp_gate: Gate::new(params.get("AMBIENT_TEMP.min", -40.0), params.get("AMBIENT_TEMP.max", 60.0), params.get("AMBIENT_TEMP.delta", 15.0)),
+            wind_speed_gate: Gate::new(params.get("WIND_SPEED.min", 0.0), params.get("WIND_SPEED.max", 100.0), params.get("WIND_SPEED.delta", 20.0)),
+            pressure_gate: Gate::new(params.get("PRESSURE.min", 800.0), params.get("PRESSURE.max", 1100.0), params.get("PRESSURE.delta", 20.0)),
+            altitude_gate: Gate::new(params.get("ALTITUDE.min", -1500.0), params.get("ALTITUDE.max", 30000.0), params.get("ALTITUDE.delta", 500.0)),
+            temp_gate: Gate::new(params.get("W_TEMP.min", -40.0), params.get("W_TEMP.max", 60.0), params.get("W_TEMP.delta", 15.0)),
+
+            staleness_timeout: time::Duration::seconds(params.get("STALENESS_TIMEOUT", 5.0) as i64),
+
+            mav_last_heartbeat: None,
+
+            gps_track: Vec::new(),
+
+            mission_folder: mission_folder.to_string(),
+
+            params: params,
+
+            bin_log: bin_log,
+
+            acmi: acmi,
+            acmi_start: acmi_start,
+            replay: None,
+        }
+    }
 
-            log_files: log_files,
+    /// Worst-of severity across the power and temperature channels right now,
+    /// collapsed to a discrete track state.
+    fn current_track_state(&self) -> TrackState {
+        let mut worst = 0u8;
+        {
+            let mut roll = |val: Option<f64>, limits: &RygLimit| {
+                if let Some(v) = val {
+                    let sev = limits.severity(v);
+                    if sev > worst { worst = sev; }
+                }
+            };
+            roll(self.h_48_v.get(), &self.h_48_v_limits);
+            roll(self.h_24_v.get(), &self.h_24_v_limits);
+            roll(self.p_12_e_v.get(), &self.p_12_e_v_limits);
+            roll(self.p_12_pl_v.get(), &self.p_12_pl_v_limits);
+            roll(self.l_motor_temp.get(), &self.l_motor_temp_limits);
+            roll(self.r_motor_temp.get(), &self.r_motor_temp_limits);
+            roll(self.upper_avionics_temp.get(), &self.upper_avionics_temp_limits);
+            roll(self.lower_avionics_temp.get(), &self.lower_avionics_temp_limits);
+        }
+        TrackState::from_severity(worst)
+    }
+
+    /// Emit the accumulated GPS track as a KML document: a faint overall
+    /// `<LineString>` plus one colored `<Placemark>` per constant-state
+    /// sub-segment, extruded to the ground with absolute altitude so the drive
+    /// can be replayed in Google Earth with faults marked in red.
+    pub fn write_kml(&self, path: &str) {
+        let mut out = match File::create(path) {
+            Ok(f) => BufWriter::new(f),
+            Err(_) => return,
+        };
+
+        write!(&mut out,
+               "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n\
+                <Document>\n<name>PISCES rover track</name>\n").unwrap();
+
+        // Faint overall track line beneath the colored segments.
+        write!(&mut out,
+               "<Placemark>\n<Style><LineStyle><color>80FFFFFF</color><width>1</width></LineStyle></Style>\n\
+                <LineString>\n<altitudeMode>absolute</altitudeMode>\n<coordinates>\n").unwrap();
+        for &(lat, lon, alt, _) in &self.gps_track {
+            write!(&mut out, "{},{},{}\n", lon, lat, alt).unwrap();
+        }
+        write!(&mut out, "</coordinates>\n</LineString>\n</Placemark>\n").unwrap();
+
+        // Break the track into runs of equal state and emit one Placemark each.
+        let mut i = 0;
+        while i + 1 < self.gps_track.len() {
+            let state = self.gps_track[i].3;
+            let color = state.kml_color();
+            let mut j = i;
+            while j + 1 < self.gps_track.len() && self.gps_track[j + 1].3 == state {
+                j += 1;
+            }
+
+            write!(&mut out,
+                   "<Placemark>\n<Style><LineStyle><color>{}</color><width>3</width></LineStyle></Style>\n\
+                    <LineString>\n<extrude>1</extrude>\n<altitudeMode>absolute</altitudeMode>\n\
+                    <coordinates>\n", color).unwrap();
+            // Each run also takes the first point of the next run so the
+            // colored segments join up.
+            let end = if j + 1 < self.gps_track.len() { j + 1 } else { j };
+            for k in i..(end + 1) {
+                let (lat, lon, alt, _) = self.gps_track[k];
+                write!(&mut out, "{},{},{}\n", lon, lat, alt).unwrap();
+            }
+            write!(&mut out, "</coordinates>\n</LineString>\n</Placemark>\n").unwrap();
+
+            i = j + 1;
+        }
+
+        write!(&mut out, "</Document>\n</kml>\n").unwrap();
+    }
+
+    /// Re-read the parameter file and push the new thresholds and graph scales
+    /// into the live limits and graphs without restarting the ground station.
+    fn reload_params(&mut self) {
+        let params = Params::load(PARAMS_PATH);
+
+        self.h_48_v_limits = RygLimit::LessThan(params.get("H_48_V.red", 45.0), params.get("H_48_V.yellow", 48.0));
+        self.h_24_v_limits = RygLimit::LessThan(params.get("H_24_V.red", 22.0), params.get("H_24_V.yellow", 24.0));
+        self.p_12_e_v_limits = RygLimit::LessThan(params.get("P_12_E_V.red", 10.0), params.get("P_12_E_V.yellow", 12.0));
+        self.p_12_pl_v_limits = RygLimit::LessThan(params.get("P_12_PL_V.red", 10.0), params.get("P_12_PL_V.yellow", 12.0));
+        self.l_motor_temp_limits = RygLimit::GreaterThan(params.get("MOTOR_TEMP.red", 80.0), params.get("MOTOR_TEMP.yellow", 60.0));
+        self.r_motor_temp_limits = RygLimit::GreaterThan(params.get("MOTOR_TEMP.red", 80.0), params.get("MOTOR_TEMP.yellow", 60.0));
+        self.upper_avionics_temp_limits = RygLimit::GreaterThan(params.get("AVIONICS_TEMP.red", 60.0), params.get("AVIONICS_TEMP.yellow", 45.0));
+        self.lower_avionics_temp_limits = RygLimit::GreaterThan(params.get("AVIONICS_TEMP.red", 60.0), params.get("AVIONICS_TEMP.yellow", 45.0));
+
+        self.v48_graph.set_y_max(params.get("V48_GRAPH.max", 80.0));
+        self.a24_graph.set_y_max(params.get("A24_GRAPH.max", 40.0));
+        self.v12_graph.set_y_max(params.get("V12_GRAPH.max", 20.0));
+        self.motor_temp_graph.set_y_max(params.get("MOTOR_TEMP_GRAPH.max", 100.0));
+
+        self.params = params;
+    }
+
+    /// Render every graph's retained series to a PNG under
+    /// `mission_data/<folder>/plots/` for a shareable post-mission summary.
+    pub fn export_plots(&self) {
+        let dir = format!("mission_data/{}/plots", self.mission_folder);
+        if ::std::fs::create_dir_all(&dir).is_err() { return; }
+
+        let elapsed = match self.mission_time {
+            MissionTime::Paused(t) => t,
+            MissionTime::Running(start_time, extra_time) => (time::now() - start_time) + extra_time,
+        };
+        let elapsed_secs = elapsed.num_seconds() as f64;
+
+        plot_export::export_png(&self.v48_graph, &format!("{}/48v.png", dir), elapsed_secs);
+        plot_export::export_png(&self.a24_graph, &format!("{}/24a.png", dir), elapsed_secs);
+        plot_export::export_png(&self.v12_graph, &format!("{}/12v.png", dir), elapsed_secs);
+        plot_export::export_png(&self.motor_temp_graph, &format!("{}/motor_temp.png", dir), elapsed_secs);
+    }
+
+    /// Barometric altitude in meters from a station pressure in hPa, using the
+    /// hypsometric formula against the current sea-level reference `P0`.
+    fn barometric_altitude(&self, pressure: f64) -> f64 {
+        44330.0 * (1.0 - (pressure / self.sea_level_pressure).powf(0.1903))
+    }
+
+    /// Recalibrate the sea-level reference so the barometric altitude matches a
+    /// known field elevation (the current GPS altitude). Back-solving the
+    /// hypsometric formula gives `P0 = P / (1 - h/44330)^(1/0.1903)`.
+    fn recalibrate_baro(&mut self) {
+        if let (Some(pressure), Some(elevation)) = (self.pressure, self.gps_altitude) {
+            let ratio = 1.0 - elevation / 44330.0;
+            if ratio > 0.0 {
+                self.sea_level_pressure = pressure / ratio.powf(1.0 / 0.1903);
+                self.params.set("SEA_LEVEL_PRESSURE", self.sea_level_pressure);
+                self.baro_altitude = Some(self.barometric_altitude(pressure));
+            }
+        }
+    }
+
+    /// Serialize the current telemetry state as an ACMI frame. Called on each
+    /// GPS/IMU update; a no-op while replaying or if no recorder is open.
+    fn record_acmi_frame(&mut self) {
+        if self.replay.is_some() { return; }
+        let secs = (time::now() - self.acmi_start).num_milliseconds() as f64 / 1000.0;
+        let (pitch, roll, heading) = self.pitch_roll_heading.unwrap_or((0.0, 0.0, 0.0));
+        let mut props: Vec<(&'static str, f64)> = Vec::new();
+        props.push(("H48V", self.h_48_v.get().unwrap_or(0.0)));
+        props.push(("H24V", self.h_24_v.get().unwrap_or(0.0)));
+        props.push(("P12EV", self.p_12_e_v.get().unwrap_or(0.0)));
+        props.push(("P12PLV", self.p_12_pl_v.get().unwrap_or(0.0)));
+        props.push(("LMotorTemp", self.l_motor_temp.get().unwrap_or(0.0)));
+        props.push(("RMotorTemp", self.r_motor_temp.get().unwrap_or(0.0)));
+        props.push(("UprATemp", self.upper_avionics_temp.get().unwrap_or(0.0)));
+        props.push(("LwrATemp", self.lower_avionics_temp.get().unwrap_or(0.0)));
+        props.push(("WindSpeed", self.wind_speed.get().unwrap_or(0.0)));
+
+        let frame = acmi::Frame {
+            lon: self.longitude.unwrap_or(0.0),
+            lat: self.latitude.unwrap_or(0.0),
+            alt: self.gps_altitude.unwrap_or(0.0),
+            roll: roll,
+            pitch: pitch,
+            heading: heading,
+            speed: self.speed.unwrap_or(0.0),
+            props: props,
+        };
+        if let Some(ref mut recorder) = self.acmi {
+            recorder.record(secs, &frame);
+        }
+    }
+
+    /// Load an ACMI recording and arm playback; frames are driven into
+    /// `handle_packet` from `tick_replay` as they come due.
+    pub fn load_replay(&mut self, path: &str) {
+        let frames = acmi::load(path);
+        if !frames.is_empty() {
+            self.replay = Some(Replay::new(frames));
+        }
+    }
+
+    /// Advance any armed replay, feeding frames whose timestamp has elapsed
+    /// since playback started.
+    pub fn tick_replay(&mut self) {
+        let due: Vec<Vec<String>> = {
+            let replay = match self.replay {
+                Some(ref mut r) => r,
+                None => return,
+            };
+            if replay.started.is_none() {
+                replay.started = Some(time::now());
+            }
+            let elapsed = (time::now() - replay.started.unwrap()).num_milliseconds() as f64 / 1000.0;
+            let mut due = Vec::new();
+            while replay.next < replay.frames.len() && replay.frames[replay.next].0 <= elapsed {
+                due.push(replay.frames[replay.next].1.clone());
+                replay.next += 1;
+            }
+            due
+        };
+        for packets in due {
+            for packet in packets {
+                self.handle_packet(packet);
+            }
         }
     }
 
     pub fn log_data(&mut self) {
+        let t = time::precise_time_ns() / 1000; // microseconds since an epoch
+
         // imu
-        match self.pitch_roll_heading {
-            Some((pitch, roll, heading)) => {
-                write!(&mut self.log_files.get_mut("imu").unwrap(),
-                       "{}\t{}\t{}\n", pitch, roll, heading).unwrap();
-            },
-            None => { write!(&mut self.log_files.get_mut("imu").unwrap(), "none").unwrap(); },
+        if let Some((pitch, roll, heading)) = self.pitch_roll_heading {
+            self.bin_log.log("IMU", t, &[pitch, roll, heading]);
         }
         // gps
-        write!(&mut self.log_files.get_mut("gps").unwrap(),
-               "{:?}\t{:?}\t{:?}\t{:?}\t{:?}\n", self.latitude, self.longitude,
-               self.speed, self.gps_altitude, self.angle).unwrap();
+        self.bin_log.log("GPS", t, &[self.latitude.unwrap_or(0.0), self.longitude.unwrap_or(0.0),
+                                     self.speed.unwrap_or(0.0), self.gps_altitude.unwrap_or(0.0),
+                                     self.angle.unwrap_or(0.0)]);
+        // Retain the fix for KML export, tagged with the current worst-channel
+        // state so the exported track can be colored per segment.
+        if let (Some(lat), Some(lon)) = (self.latitude, self.longitude) {
+            let state = self.current_track_state();
+            self.gps_track.push((lat, lon, self.gps_altitude.unwrap_or(0.0), state));
+            self.ground_track.add_fix(lat, lon);
+        }
         // volt
-        write!(&mut self.log_files.get_mut("volt").unwrap(),
-               "{:?}\t{:?}\t{:?}\t{:?}\n", self.h_48_v.get(), self.h_24_v.get(),
-               self.p_12_e_v.get(), self.p_12_pl_v.get()).unwrap();
+        self.bin_log.log("VOLT", t, &[self.h_48_v.get().unwrap_or(0.0), self.h_24_v.get().unwrap_or(0.0),
+                                      self.p_12_e_v.get().unwrap_or(0.0), self.p_12_pl_v.get().unwrap_or(0.0)]);
         // amp
-        write!(&mut self.log_files.get_mut("amp").unwrap(),
-               "{:?}\t{:?}\t{:?}\t{:?}\n", self.h_24_a.get(), self.p_12_e_a.get(),
-               self.l_motor_amp.get(), self.r_motor_amp.get()).unwrap();
-        // temp
-        write!(&mut self.log_files.get_mut("temp").unwrap(),
-               "{:?}\t{:?}\t{:?}\t{:?}\n", self.l_motor_temp.get(), self.r_motor_temp.get(),
-               self.upper_avionics_temp.get(), self.lower_avionics_temp.get()).unwrap();
+        self.bin_log.log("AMP", t, &[self.h_24_a.get().unwrap_or(0.0), self.p_12_e_a.get().unwrap_or(0.0),
+                                     self.l_motor_amp.get().unwrap_or(0.0), self.r_motor_amp.get().unwrap_or(0.0)]);
+        // motor + avionics temp
+        self.bin_log.log("MTMP", t, &[self.l_motor_temp.get().unwrap_or(0.0), self.r_motor_temp.get().unwrap_or(0.0),
+                                      self.upper_avionics_temp.get().unwrap_or(0.0), self.lower_avionics_temp.get().unwrap_or(0.0)]);
         // weather
-        write!(&mut self.log_files.get_mut("weather").unwrap(),
-               "{:?}\t{:?}\t{:?}\t{:?}\n", self.wind_speed.get(), self.pressure,
-               self.altitude, self.temp).unwrap();
+        self.bin_log.log("WTHR", t, &[self.wind_speed.get().unwrap_or(0.0), self.pressure.unwrap_or(0.0),
+                                      self.altitude.unwrap_or(0.0), self.temp.unwrap_or(0.0)]);
     }
 
     pub fn draw_ui<'a, G>(&mut self, c: Context, g: &mut G, ui: &mut conrod_config::Ui)
@@ -275,6 +623,11 @@ impl TelemetryUi {
         self.a24_graph.draw(c.trans(ui.win_w - 405.0, 185.0), g, &mut *ui.glyph_cache.borrow_mut());
         self.v12_graph.draw(c.trans(ui.win_w - 405.0, 365.0), g, &mut *ui.glyph_cache.borrow_mut());
         self.motor_temp_graph.draw(c.trans(ui.win_w - 405.0, 545.0), g, &mut *ui.glyph_cache.borrow_mut());
+
+        // HUD artificial horizon alongside the IMU text readout.
+        self.attitude_indicator.draw(self.pitch_roll_heading,
+                                     c.trans(180.0, 540.0), g,
+                                     &mut *ui.glyph_cache.borrow_mut());
     }
 
     pub fn set_widgets(&mut self, ui: &mut conrod_config::UiCell) {
@@ -359,6 +712,95 @@ impl TelemetryUi {
             .color(self.bg_color.plain_contrast())
             .set(TIME_DELAY, ui);
 
+        // MAVLink heartbeat age, alongside the time-delay readout.
+        let (mav_link, mav_link_color) =
+            match self.mav_last_heartbeat {
+                Some(t) => {
+                    let age = (time::now() - t).num_seconds();
+                    let color = if age <= 3 { rgb(0.0, 1.0, 0.0) } else { rgb(1.0, 0.0, 0.0) };
+                    (format!("MAVLink: {}s", age), color)
+                },
+                None => ("MAVLink: --".to_string(), rgb(1.0, 0.0, 0.0)),
+            };
+        Text::new(mav_link.as_str())
+            .x_y((-ui.win_w / 2.0) + 75.0, (ui.win_h / 2.0) - 170.0)
+            .font_size(18)
+            .color(mav_link_color)
+            .set(MAV_LINK_LABEL, ui);
+
+        // Export the accumulated GPS track as KML for Google Earth review
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 260.0, (ui.win_h / 2.0) - 100.0)
+            .rgb(0.3, 0.8, 0.3)
+            .frame(1.0)
+            .label("Export KML")
+            .react(|| {
+                let path = format!("mission_data/{}/track.kml", self.mission_folder);
+                self.write_kml(path.as_str());
+            })
+            .set(EXPORT_KML_BUTTON, ui);
+
+        // Parameter panel: reload alarm thresholds / graph scales from the
+        // config file live, or persist the current table back to disk.
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 260.0, (ui.win_h / 2.0) - 135.0)
+            .rgb(0.3, 0.6, 0.8)
+            .frame(1.0)
+            .label("Reload Params")
+            .react(|| {
+                self.reload_params();
+            })
+            .set(RELOAD_PARAMS_BUTTON, ui);
+
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 365.0, (ui.win_h / 2.0) - 135.0)
+            .rgb(0.8, 0.6, 0.3)
+            .frame(1.0)
+            .label("Save Params")
+            .react(|| {
+                self.params.save(PARAMS_PATH);
+            })
+            .set(SAVE_PARAMS_BUTTON, ui);
+
+        // Render the time-series graphs to PNG for a post-mission summary.
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 260.0, (ui.win_h / 2.0) - 170.0)
+            .rgb(0.6, 0.4, 0.8)
+            .frame(1.0)
+            .label("Export Plots")
+            .react(|| {
+                self.export_plots();
+            })
+            .set(EXPORT_PLOTS_BUTTON, ui);
+
+        // Replay controls, mirroring the mission start/reset buttons but driving
+        // a loaded ACMI recording instead of the live clock.
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 365.0, (ui.win_h / 2.0) - 170.0)
+            .rgb(0.4, 0.7, 0.4)
+            .frame(1.0)
+            .label("Load Replay")
+            .react(|| {
+                self.load_replay("replay.acmi");
+            })
+            .set(REPLAY_LOAD_BUTTON, ui);
+
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 470.0, (ui.win_h / 2.0) - 170.0)
+            .rgb(0.7, 0.4, 0.4)
+            .frame(1.0)
+            .label("Reset Replay")
+            .react(|| {
+                self.replay = None;
+            })
+            .set(REPLAY_RESET_BUTTON, ui);
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // Power section
 
@@ -552,12 +994,24 @@ impl TelemetryUi {
             .font_size(22)
             .color(self.bg_color.plain_contrast())
             .set(GPS_LABEL, ui);
-        
+
+        // u-blox fix status
+        let gps_status = self.gps_status();
+        let gps_status_color =
+            if self.gps_fix_type >= 3 { rgb(0.0, 1.0, 0.0) }
+            else if self.gps_fix_type == 2 { rgb(1.0, 1.0, 0.0) }
+            else { rgb(1.0, 0.0, 0.0) };
+        Text::new(format!("GPS: {}", gps_status).as_str())
+            .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 55.0)
+            .font_size(16)
+            .color(gps_status_color)
+            .set(GPS_STATUS_LABEL, ui);
+
         // Latitude label
         let (latitude, latitude_color) =
             match self.latitude {
                 Some(lat) => {
-                    (format!("{0:.2} N", lat), rgb(0.0, 1.0, 0.0))
+                    (geodesy::format_lat(lat), rgb(0.0, 1.0, 0.0))
                 },
                 None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
@@ -571,7 +1025,7 @@ impl TelemetryUi {
         let (longitude, longitude_color) =
             match self.longitude {
                 Some(lng) => {
-                    (format!("{0:.2} W", lng), rgb(0.0, 1.0, 0.0))
+                    (geodesy::format_lon(lng), rgb(0.0, 1.0, 0.0))
                 },
                 None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
@@ -623,6 +1077,24 @@ impl TelemetryUi {
             .color(angle_color)
             .set(ANGLE_LABEL, ui);
 
+        // Cumulative ground-track distance
+        Text::new(format!("{0:.1} m", self.ground_track.total_distance()).as_str())
+            .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 175.0)
+            .font_size(16)
+            .color(rgb(0.0, 1.0, 0.0))
+            .set(TRACK_DISTANCE_LABEL, ui);
+
+        // Live great-circle bearing
+        let bearing = match self.ground_track.bearing() {
+            Some(b) => (format!("{0:.0} deg", b), rgb(0.0, 1.0, 0.0)),
+            None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+        };
+        Text::new(bearing.0.as_str())
+            .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 195.0)
+            .font_size(16)
+            .color(bearing.1)
+            .set(TRACK_BEARING_LABEL, ui);
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // Temp section
 
@@ -642,10 +1114,10 @@ impl TelemetryUi {
 
         let (l_motor_temp, l_motor_temp_color) =
             match self.l_motor_temp.get() {
-                Some(temp) => {
+                Some(temp) if !self.l_motor_temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), self.l_motor_temp_limits.get_color(temp))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(l_motor_temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 220.0)
@@ -663,10 +1135,10 @@ impl TelemetryUi {
 
         let (r_motor_temp, r_motor_temp_color) =
             match self.r_motor_temp.get() {
-                Some(temp) => {
+                Some(temp) if !self.r_motor_temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), self.r_motor_temp_limits.get_color(temp))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(r_motor_temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 240.0)
@@ -684,10 +1156,10 @@ impl TelemetryUi {
 
         let (upper_avionics_temp, upper_avionics_temp_color) =
             match self.upper_avionics_temp.get() {
-                Some(temp) => {
+                Some(temp) if !self.upper_avionics_temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), self.upper_avionics_temp_limits.get_color(temp))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(upper_avionics_temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 260.0)
@@ -705,10 +1177,10 @@ impl TelemetryUi {
 
         let (lower_avionics_temp, lower_avionics_temp_color) =
             match self.lower_avionics_temp.get() {
-                Some(temp) => {
+                Some(temp) if !self.lower_avionics_temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), self.lower_avionics_temp_limits.get_color(temp))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(lower_avionics_temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 280.0)
@@ -726,10 +1198,10 @@ impl TelemetryUi {
 
         let (ambient_temp, ambient_temp_color) =
             match self.ambient_temp.get() {
-                Some(temp) => {
+                Some(temp) if !self.ambient_temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), rgb(0.0, 1.0, 0.0))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(ambient_temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 300.0)
@@ -756,10 +1228,10 @@ impl TelemetryUi {
 
         let (wind_speed, wind_speed_color) =
             match self.wind_speed.get() {
-                Some(wind_speed) => {
+                Some(wind_speed) if !self.wind_speed_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} m/s", wind_speed), rgb(0.0, 1.0, 0.0))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(wind_speed.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 380.0)
@@ -777,10 +1249,10 @@ impl TelemetryUi {
 
         let (altitude, altitude_color) =
             match self.altitude {
-                Some(alt) => {
+                Some(alt) if !self.altitude_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} ft", alt), rgb(0.0, 1.0, 0.0))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(altitude.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 400.0)
@@ -798,10 +1270,10 @@ impl TelemetryUi {
 
         let (pressure, pressure_color) =
             match self.pressure {
-                Some(pressure) => {
+                Some(pressure) if !self.pressure_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} hPa", pressure), rgb(0.0, 1.0, 0.0))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(pressure.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 420.0)
@@ -819,10 +1291,10 @@ impl TelemetryUi {
 
         let (temp, temp_color) =
             match self.temp {
-                Some(temp) => {
+                Some(temp) if !self.temp_gate.is_stale(self.staleness_timeout) => {
                     (format!("{0:.2} C", temp), rgb(0.0, 1.0, 0.0))
                 },
-                None => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
             };
         Text::new(temp.as_str())
             .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 440.0)
@@ -830,6 +1302,41 @@ impl TelemetryUi {
             .color(temp_color)
             .set(WEATHER_TEMP_VALUE, ui);
 
+        // Barometric vs GPS altitude disagreement, a sanity signal on the
+        // pressure sensor and on GPS vertical accuracy.
+
+        Text::new(format!("Baro/GPS").as_str())
+            .x_y((-ui.win_w / 2.0) + 360.0, (ui.win_h / 2.0) - 460.0)
+            .font_size(18)
+            .color(self.bg_color.plain_contrast())
+            .set(BARO_DISAGREEMENT_LABEL, ui);
+
+        let (baro_disagreement, baro_disagreement_color) =
+            match (self.baro_altitude, self.gps_altitude) {
+                (Some(baro), Some(gps)) => {
+                    let diff = baro - gps;
+                    (format!("{0:.1} m", diff), self.baro_disagreement_limits.get_color(diff.abs()))
+                },
+                _ => ("NO DATA".to_string(), rgb(0.0, 0.0, 0.0)),
+            };
+        Text::new(baro_disagreement.as_str())
+            .x_y((-ui.win_w / 2.0) + 500.0, (ui.win_h / 2.0) - 460.0)
+            .font_size(16)
+            .color(baro_disagreement_color)
+            .set(BARO_DISAGREEMENT_VALUE, ui);
+
+        // Recalibrate the sea-level reference against the current GPS elevation.
+        Button::new()
+            .w_h(100.0, 30.0)
+            .x_y((-ui.win_w / 2.0) + 410.0, (ui.win_h / 2.0) - 490.0)
+            .rgb(0.3, 0.6, 0.8)
+            .frame(1.0)
+            .label("Calibrate P0")
+            .react(|| {
+                self.recalibrate_baro();
+            })
+            .set(CALIBRATE_P0_BUTTON, ui);
+
         ////////////////////////////////////////////////////////////////////////////////////////////
         // IMU section
 
@@ -924,37 +1431,62 @@ impl TelemetryUi {
 
             match packet_parts[0].as_str() {
                 "VOLT" => {
-                    /////////////////////
-                    self.h_48_v.add_value(packet_parts[1].parse().unwrap_or(0.0));
-                    let h_48_v = self.h_48_v.get().unwrap_or(0.0);
-
-                    let point_x = self.v48_graph.num_points(0) as f64;
-                    self.v48_graph.add_point(0, point_x, h_48_v);
-
-                    /////////////////////
-                    self.h_24_v.add_value(packet_parts[2].parse().unwrap_or(0.0));
-
-                    /////////////////////
-                    self.p_12_e_v.add_value(packet_parts[3].parse().unwrap_or(0.0));
-                    let p_12_e_v = self.p_12_e_v.get().unwrap_or(0.0);
-
-                    let point_x = self.v12_graph.num_points(0) as f64;
-                    self.v12_graph.add_point(0, point_x, p_12_e_v);
-
-                    /////////////////////
-                    self.p_12_pl_v.add_value(packet_parts[4].parse().unwrap_or(0.0));
+                    // VOLT:h48:h24:p12e:p12pl - a short datagram is ignored
+                    // rather than indexed out of bounds.
+                    if packet_parts.len() == 5 {
+                        /////////////////////
+                        let raw = packet_parts[1].parse().unwrap_or(0.0);
+                        if self.h_48_v.get().is_none() { self.h_48_v_filter.reset(raw); }
+                        self.h_48_v.add_value(self.h_48_v_filter.filter(raw));
+                        let h_48_v = self.h_48_v.get().unwrap_or(0.0);
+
+                        let point_x = self.v48_graph.num_points(0) as f64;
+                        self.v48_graph.add_point(0, point_x, h_48_v);
+
+                        /////////////////////
+                        let raw = packet_parts[2].parse().unwrap_or(0.0);
+                        if self.h_24_v.get().is_none() { self.h_24_v_filter.reset(raw); }
+                        self.h_24_v.add_value(self.h_24_v_filter.filter(raw));
+
+                        /////////////////////
+                        let raw = packet_parts[3].parse().unwrap_or(0.0);
+                        if self.p_12_e_v.get().is_none() { self.p_12_e_v_filter.reset(raw); }
+                        self.p_12_e_v.add_value(self.p_12_e_v_filter.filter(raw));
+                        let p_12_e_v = self.p_12_e_v.get().unwrap_or(0.0);
+
+                        let point_x = self.v12_graph.num_points(0) as f64;
+                        self.v12_graph.add_point(0, point_x, p_12_e_v);
+
+                        /////////////////////
+                        let raw = packet_parts[4].parse().unwrap_or(0.0);
+                        if self.p_12_pl_v.get().is_none() { self.p_12_pl_v_filter.reset(raw); }
+                        self.p_12_pl_v.add_value(self.p_12_pl_v_filter.filter(raw));
+                    }
                 },
                 "AMP" => {
-                    self.l_motor_amp.add_value(packet_parts[1].parse().unwrap_or(0.0));
-                    self.r_motor_amp.add_value(packet_parts[2].parse().unwrap_or(0.0));
-                    self.p_12_e_a.add_value(packet_parts[3].parse().unwrap_or(0.0));
-                    
-                    // h-24
-                    self.h_24_a.add_value(packet_parts[4].parse().unwrap_or(0.0));
-                    let h_24_a = self.p_12_e_v.get().unwrap_or(0.0);
-
-                    let point_x = self.a24_graph.num_points(0) as f64;
-                    self.a24_graph.add_point(0, point_x, h_24_a);
+                    // AMP:l_motor:r_motor:p12e:h24 - same length guard as VOLT.
+                    if packet_parts.len() == 5 {
+                        let raw = packet_parts[1].parse().unwrap_or(0.0);
+                        if self.l_motor_amp.get().is_none() { self.l_motor_amp_filter.reset(raw); }
+                        self.l_motor_amp.add_value(self.l_motor_amp_filter.filter(raw));
+
+                        let raw = packet_parts[2].parse().unwrap_or(0.0);
+                        if self.r_motor_amp.get().is_none() { self.r_motor_amp_filter.reset(raw); }
+                        self.r_motor_amp.add_value(self.r_motor_amp_filter.filter(raw));
+
+                        let raw = packet_parts[3].parse().unwrap_or(0.0);
+                        if self.p_12_e_a.get().is_none() { self.p_12_e_a_filter.reset(raw); }
+                        self.p_12_e_a.add_value(self.p_12_e_a_filter.filter(raw));
+
+                        // h-24
+                        let raw = packet_parts[4].parse().unwrap_or(0.0);
+                        if self.h_24_a.get().is_none() { self.h_24_a_filter.reset(raw); }
+                        self.h_24_a.add_value(self.h_24_a_filter.filter(raw));
+                        let h_24_a = self.h_24_a.get().unwrap_or(0.0);
+
+                        let point_x = self.a24_graph.num_points(0) as f64;
+                        self.a24_graph.add_point(0, point_x, h_24_a);
+                    }
                 },
                 "GPS" => {
                     if packet_parts.len() == 6 {
@@ -963,43 +1495,75 @@ impl TelemetryUi {
                         self.speed = packet_parts[3].parse().ok();
                         self.gps_altitude = packet_parts[4].parse().ok();
                         self.angle = packet_parts[5].parse().ok();
+                        self.record_acmi_frame();
                     }
                 },
                 "L_MOTOR_TEMP" => {
-                    self.l_motor_temp.add_value(packet_parts[1].parse().unwrap());
-                    let l_motor_temp = self.l_motor_temp.get().unwrap();
-
-                    let point_x = self.motor_temp_graph.num_points(0) as f64;
-                    self.motor_temp_graph.add_point(0, point_x, l_motor_temp);
+                    let raw = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.l_motor_temp_gate.accept(raw, self.l_motor_temp.get()) {
+                        if self.l_motor_temp.get().is_none() { self.l_motor_temp_filter.reset(raw); }
+                        self.l_motor_temp.add_value(self.l_motor_temp_filter.filter(raw));
+                        let l_motor_temp = self.l_motor_temp.get().unwrap();
+
+                        let point_x = self.motor_temp_graph.num_points(0) as f64;
+                        self.motor_temp_graph.add_point(0, point_x, l_motor_temp);
+                    }
                 },
                 "R_MOTOR_TEMP" => {
-                    self.r_motor_temp.add_value(packet_parts[1].parse().unwrap());
-                    let r_motor_temp = self.r_motor_temp.get().unwrap();
-
-                    let point_x = self.motor_temp_graph.num_points(1) as f64;
-                    self.motor_temp_graph.add_point(1, point_x, r_motor_temp);
+                    let raw = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.r_motor_temp_gate.accept(raw, self.r_motor_temp.get()) {
+                        if self.r_motor_temp.get().is_none() { self.r_motor_temp_filter.reset(raw); }
+                        self.r_motor_temp.add_value(self.r_motor_temp_filter.filter(raw));
+                        let r_motor_temp = self.r_motor_temp.get().unwrap();
+
+                        let point_x = self.motor_temp_graph.num_points(1) as f64;
+                        self.motor_temp_graph.add_point(1, point_x, r_motor_temp);
+                    }
                 },
                 "UPR_A_TEMP" => {
-                    self.upper_avionics_temp.add_value(packet_parts[1].parse().unwrap_or(0.0));
+                    let raw = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.upper_avionics_temp_gate.accept(raw, self.upper_avionics_temp.get()) {
+                        if self.upper_avionics_temp.get().is_none() { self.upper_avionics_temp_filter.reset(raw); }
+                        self.upper_avionics_temp.add_value(self.upper_avionics_temp_filter.filter(raw));
+                    }
                 },
                 "LWR_A_TEMP" => {
-                    self.lower_avionics_temp.add_value(packet_parts[1].parse().unwrap_or(0.0));
+                    let raw = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.lower_avionics_temp_gate.accept(raw, self.lower_avionics_temp.get()) {
+                        if self.lower_avionics_temp.get().is_none() { self.lower_avionics_temp_filter.reset(raw); }
+                        self.lower_avionics_temp.add_value(self.lower_avionics_temp_filter.filter(raw));
+                    }
                 },
                 "AMBIENT_TEMP" => {
-                    self.ambient_temp.add_value(packet_parts[1].parse().unwrap_or(0.0));
+                    let raw = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.ambient_temp_gate.accept(raw, self.ambient_temp.get()) {
+                        if self.ambient_temp.get().is_none() { self.ambient_temp_filter.reset(raw); }
+                        self.ambient_temp.add_value(self.ambient_temp_filter.filter(raw));
+                    }
                 },
                 "W_TEMP" => {
-                    let temp = packet_parts[1].parse().unwrap();
-                    self.temp = Some(temp);
+                    let temp = packet_parts[1].parse().unwrap_or(0.0);
+                    if self.temp_gate.accept(temp, self.temp) {
+                        self.temp = Some(temp);
+                    }
                 },
                 "W_PR_ALT" => {
                     let pressure = packet_parts[1].parse().unwrap();
-                    let altitude= packet_parts[2].parse().unwrap();
-                    self.pressure = Some(pressure);
-                    self.altitude = Some(altitude);
+                    let altitude = packet_parts[2].parse().unwrap();
+                    if self.pressure_gate.accept(pressure, self.pressure) {
+                        self.pressure = Some(pressure);
+                        self.baro_altitude = Some(self.barometric_altitude(pressure));
+                    }
+                    if self.altitude_gate.accept(altitude, self.altitude) {
+                        self.altitude = Some(altitude);
+                    }
                 },
                 "W_WND_SPD" => {
-                    self.wind_speed.add_value(packet_parts[1].parse().unwrap());
+                    let raw = packet_parts[1].parse().unwrap();
+                    if self.wind_speed_gate.accept(raw, self.wind_speed.get()) {
+                        if self.wind_speed.get().is_none() { self.wind_speed_filter.reset(raw); }
+                        self.wind_speed.add_value(self.wind_speed_filter.filter(raw));
+                    }
                 },
                 "IMU" => {
                     let ax: f64 = packet_parts[1].parse().unwrap_or(0.0);
@@ -1029,12 +1593,86 @@ impl TelemetryUi {
                     }
                     heading = 360.0 - heading;
                     self.pitch_roll_heading = Some((pitch, roll, heading));
+                    self.record_acmi_frame();
+                },
+                // MAVLink-sourced samples, bridged through mavlink.rs. Each
+                // feeds the same fields as the native telemetry strings so the
+                // graphs and limit coloring keep working unchanged.
+                "HEARTBEAT" => {
+                    self.mav_last_heartbeat = Some(time::now());
+                },
+                "MAV_GPOS" => {
+                    self.latitude = packet_parts[1].parse().ok();
+                    self.longitude = packet_parts[2].parse().ok();
+                    self.gps_altitude = packet_parts[3].parse().ok();
+                    self.angle = packet_parts[4].parse().ok();
+                },
+                "MAV_VFR" => {
+                    self.speed = packet_parts[1].parse().ok();
+                },
+                "MAV_ATT" => {
+                    let pitch = packet_parts[1].parse().unwrap_or(0.0);
+                    let roll = packet_parts[2].parse().unwrap_or(0.0);
+                    let heading = packet_parts[3].parse().unwrap_or(0.0);
+                    self.pitch_roll_heading = Some((pitch, roll, heading));
+                },
+                "MAV_SYS" => {
+                    self.h_48_v.add_value(packet_parts[1].parse().unwrap_or(0.0));
+                    let h_48_v = self.h_48_v.get().unwrap_or(0.0);
+                    let point_x = self.v48_graph.num_points(0) as f64;
+                    self.v48_graph.add_point(0, point_x, h_48_v);
+
+                    self.h_24_a.add_value(packet_parts[2].parse().unwrap_or(0.0));
+                    let h_24_a = self.h_24_a.get().unwrap_or(0.0);
+                    let point_x = self.a24_graph.num_points(0) as f64;
+                    self.a24_graph.add_point(0, point_x, h_24_a);
+                },
+                "MAV_PRESS" => {
+                    self.pressure = packet_parts[1].parse().ok();
+                    self.temp = packet_parts[2].parse().ok();
                 },
                 _ => { println!("WARNING: Unknown packet ID: {}", packet_parts[0]) },
             }
         }
     }
 
+    /// Feed a raw UBX frame straight off the wire. Corrupt frames (bad sync or
+    /// checksum) are parsed to `None` and silently dropped; a valid NAV-PVT
+    /// updates the position readout and fix status, and CFG acknowledgements
+    /// flip us out of the "configuring" state.
+    pub fn handle_ublox(&mut self, buf: &[u8]) {
+        match ublox::parse(buf) {
+            Some(ublox::Frame::NavPvt(pvt)) => {
+                self.gps_fix_type = pvt.fix_type;
+                self.gps_num_sats = pvt.num_sats;
+                if pvt.fix_type >= 2 {
+                    self.latitude = Some(pvt.lat);
+                    self.longitude = Some(pvt.lon);
+                    self.speed = Some(pvt.speed);
+                    self.gps_altitude = Some(pvt.altitude);
+                    self.angle = Some(pvt.heading);
+                }
+            },
+            Some(ublox::Frame::AckAck(..)) | Some(ublox::Frame::AckNak(..)) => {
+                self.gps_configured = true;
+            },
+            None => { },
+        }
+    }
+
+    /// One-line u-blox status line for the GPS panel.
+    fn gps_status(&self) -> String {
+        if self.gps_fix_type >= 3 {
+            format!("3D fix ({} sats)", self.gps_num_sats)
+        } else if self.gps_fix_type == 2 {
+            format!("2D fix ({} sats)", self.gps_num_sats)
+        } else if self.gps_configured {
+            "no fix".to_string()
+        } else {
+            "configuring".to_string()
+        }
+    }
+
     pub fn on_key_pressed(&mut self, key: piston_window::Key) {
         match key {
             _ => { },
@@ -1056,6 +1694,13 @@ widget_ids! {
     MISSION_START_BUTTON,
     MISSION_RESET_BUTTON,
     TIME_DELAY,
+    MAV_LINK_LABEL,
+    EXPORT_KML_BUTTON,
+    RELOAD_PARAMS_BUTTON,
+    SAVE_PARAMS_BUTTON,
+    EXPORT_PLOTS_BUTTON,
+    REPLAY_LOAD_BUTTON,
+    REPLAY_RESET_BUTTON,
 
     // Power section
     POWER_LABEL,
@@ -1086,11 +1731,14 @@ widget_ids! {
 
     // GPS section
     GPS_LABEL,
+    GPS_STATUS_LABEL,
     LATITUDE_LABEL,
     LONGITUDE_LABEL,
     SPEED_LABEL,
     GPS_ALTITUDE_LABEL,
     ANGLE_LABEL,
+    TRACK_DISTANCE_LABEL,
+    TRACK_BEARING_LABEL,
 
     // Temp section
     TEMP_LABEL,
@@ -1125,6 +1773,10 @@ widget_ids! {
     WEATHER_TEMP_LABEL,
     WEATHER_TEMP_VALUE,
 
+    BARO_DISAGREEMENT_LABEL,
+    BARO_DISAGREEMENT_VALUE,
+    CALIBRATE_P0_BUTTON,
+
     // IMU section
     IMU_LABEL,
 
@@ -8,6 +8,8 @@ use std::thread;
 
 #[macro_use]
 extern crate conrod;
+extern crate flate2;
+extern crate image;
 extern crate time;
 extern crate graphics;
 extern crate piston_window;
@@ -20,10 +22,28 @@ use piston_window::{EventLoop, Glyphs, PistonWindow, WindowSettings};
 use conrod_config::Ui;
 use tele_ui::TelemetryUi;
 
+pub mod acmi;
+pub mod attitude;
 pub mod avg_val;
+pub mod biquad;
 pub mod conrod_config;
+pub mod gate;
+pub mod geodesy;
 pub mod line_graph;
+pub mod mavlink;
+pub mod params;
+pub mod plot_export;
+pub mod protocol;
+pub mod sdlog;
 pub mod tele_ui;
+pub mod ublox;
+
+/// A datagram off the telemetry socket: either a binary u-blox frame or a
+/// telemetry string (legacy or bridged from the typed protocol).
+enum Incoming {
+    Telemetry(String),
+    Ublox(Vec<u8>),
+}
 
 fn main() {
     let mut window: PistonWindow = WindowSettings::new("PISCES Telemetry".to_string(),
@@ -39,23 +59,55 @@ fn main() {
     
     // Create a UDP socket to talk to the rover
     let socket = UdpSocket::bind("0.0.0.0:30001").ok().expect("Failed to open UDP socket");
-    socket.send_to(b"connect me plz", ("10.10.153.8", 30001)).unwrap();
-    
+    socket.send_to(format!("CONNECT version={}", protocol::PROTOCOL_VERSION).as_bytes(),
+                   ("10.10.153.8", 30001)).unwrap();
+
+    // Bring the u-blox receiver up to a 5 Hz nav rate before we start the read
+    // loop, waiting for its ACK so we don't show stale fixes from the old rate.
+    ublox::configure_rate(&socket, ("10.10.153.8", 30001), 200);
+
     let in_socket = socket;
     let (packet_t, packet_r) = channel();
-    
+    let mav_packet_t = packet_t.clone();
+
     thread::Builder::new()
         .name("packet_in".to_string())
         .spawn(move || {
             let mut buf = [0u8; 512];
             loop {
                 let (bytes_read, _) = in_socket.recv_from(&mut buf).unwrap();
-                if let Ok(msg) = String::from_utf8(buf[0..bytes_read].iter().cloned().collect()) {
-                    packet_t.send(msg).unwrap();
+                let datagram = &buf[0..bytes_read];
+                if datagram.len() >= 2 && datagram[0] == ublox::SYNC1 && datagram[1] == ublox::SYNC2 {
+                    // Binary u-blox frame - hand the raw bytes to the UI parser
+                    packet_t.send(Incoming::Ublox(datagram.to_vec())).unwrap();
+                } else if let Some(msg) = protocol::decode(datagram) {
+                    // New typed protocol - bridge to the legacy string handler
+                    packet_t.send(Incoming::Telemetry(msg.to_legacy_string())).unwrap();
+                } else if let Ok(msg) = String::from_utf8(datagram.iter().cloned().collect()) {
+                    // Compatibility path for peers still speaking plain strings
+                    packet_t.send(Incoming::Telemetry(msg)).unwrap();
                 }
             }
         }).unwrap();
     
+    // Optional MAVLink autopilot link on the conventional ground-station port;
+    // decoded messages are bridged into the same telemetry string stream.
+    if let Ok(mav_socket) = UdpSocket::bind("0.0.0.0:14550") {
+        let mav_t = mav_packet_t;
+        thread::Builder::new()
+            .name("mavlink_in".to_string())
+            .spawn(move || {
+                let mut parser = mavlink::MavParser::new();
+                let mut buf = [0u8; 1024];
+                loop {
+                    let (n, _) = mav_socket.recv_from(&mut buf).unwrap();
+                    for msg in parser.push(&buf[0..n]) {
+                        mav_t.send(Incoming::Telemetry(msg.to_legacy_string())).unwrap();
+                    }
+                }
+            }).unwrap();
+    }
+
     let mission_folder = format!("{}", time::now().strftime("%Y%b%d_%H_%M").unwrap());
     fs::create_dir_all(format!("mission_data/{}", mission_folder).as_str()).unwrap();
     let mut tele_ui = TelemetryUi::new(mission_folder.as_str());
@@ -89,9 +141,15 @@ fn main() {
         // Update
         e.update(|_| {
             while let Ok(packet) = packet_r.try_recv() {
-                tele_ui.handle_packet(packet);
+                match packet {
+                    Incoming::Telemetry(msg) => tele_ui.handle_packet(msg),
+                    Incoming::Ublox(buf) => tele_ui.handle_ublox(&buf),
+                }
             }
 
+            // Drive any loaded ACMI replay into the same packet handler.
+            tele_ui.tick_replay();
+
             // Log some data
             if (time::now()-last_update_time).num_seconds() >= 1 {
                 last_update_time = time::now();
@@ -0,0 +1,142 @@
+//! Minimal u-blox UBX frame parsing for the telemetry link.
+//!
+//! A UBX frame is `0xB5 0x62`, a class/id pair, a little-endian `u16` payload
+//! length, the payload, then a two-byte Fletcher-8 checksum computed over the
+//! class byte through the last payload byte. We parse NAV-PVT (position,
+//! velocity, time) and validate the checksum so corrupt frames are dropped.
+
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+pub const SYNC1: u8 = 0xB5;
+pub const SYNC2: u8 = 0x62;
+
+const CLASS_NAV: u8 = 0x01;
+const ID_NAV_PVT: u8 = 0x07;
+const CLASS_ACK: u8 = 0x05;
+
+/// A parsed NAV-PVT fix.
+pub struct NavPvt {
+    pub lat: f64,
+    pub lon: f64,
+    /// Height above mean sea level, metres.
+    pub altitude: f64,
+    /// 0 = no fix, 2 = 2D, 3 = 3D (the fields we care about).
+    pub fix_type: u8,
+    pub num_sats: u8,
+    /// Ground-track heading, degrees.
+    pub heading: f64,
+    /// Ground speed, m/s.
+    pub speed: f64,
+}
+
+/// The outcome of decoding one UBX frame.
+pub enum Frame {
+    NavPvt(NavPvt),
+    /// Config acknowledgement: `(acked_class, acked_id)`.
+    AckAck(u8, u8),
+    AckNak(u8, u8),
+}
+
+/// Fletcher-8 checksum over `bytes` (class .. end of payload).
+fn checksum(bytes: &[u8]) -> (u8, u8) {
+    let mut ck_a: u8 = 0;
+    let mut ck_b: u8 = 0;
+    for b in bytes {
+        ck_a = ck_a.wrapping_add(*b);
+        ck_b = ck_b.wrapping_add(ck_a);
+    }
+    (ck_a, ck_b)
+}
+
+fn le_u16(buf: &[u8], i: usize) -> u16 {
+    (buf[i] as u16) | ((buf[i + 1] as u16) << 8)
+}
+
+fn le_i32(buf: &[u8], i: usize) -> i32 {
+    (buf[i] as i32) | ((buf[i + 1] as i32) << 8)
+        | ((buf[i + 2] as i32) << 16) | ((buf[i + 3] as i32) << 24)
+}
+
+/// Parse a single UBX frame, returning `None` if it's truncated, not a UBX
+/// frame, fails the checksum, or is a class/id we don't handle.
+pub fn parse(buf: &[u8]) -> Option<Frame> {
+    if buf.len() < 8 || buf[0] != SYNC1 || buf[1] != SYNC2 {
+        return None;
+    }
+    let class = buf[2];
+    let id = buf[3];
+    let len = le_u16(buf, 4) as usize;
+    if buf.len() < 6 + len + 2 {
+        return None;
+    }
+
+    // Checksum covers class .. last payload byte
+    let (ck_a, ck_b) = checksum(&buf[2..6 + len]);
+    if ck_a != buf[6 + len] || ck_b != buf[6 + len + 1] {
+        return None;
+    }
+
+    let payload = &buf[6..6 + len];
+    match (class, id) {
+        (CLASS_NAV, ID_NAV_PVT) if payload.len() >= 84 => {
+            Some(Frame::NavPvt(NavPvt {
+                fix_type: payload[20],
+                num_sats: payload[23],
+                lon: le_i32(payload, 24) as f64 * 1e-7,
+                lat: le_i32(payload, 28) as f64 * 1e-7,
+                altitude: le_i32(payload, 36) as f64 / 1000.0, // hMSL, mm -> m
+                speed: le_i32(payload, 60) as f64 / 1000.0,    // gSpeed, mm/s -> m/s
+                heading: le_i32(payload, 64) as f64 * 1e-5,    // headMot, 1e-5 deg
+            }))
+        },
+        (CLASS_ACK, 0x01) if payload.len() >= 2 => Some(Frame::AckAck(payload[0], payload[1])),
+        (CLASS_ACK, 0x00) if payload.len() >= 2 => Some(Frame::AckNak(payload[0], payload[1])),
+        _ => None,
+    }
+}
+
+/// Build a UBX CFG-RATE frame requesting `rate_ms` between NAV solutions,
+/// framed with sync bytes and a valid checksum. The caller waits for the
+/// matching ACK-ACK / ACK-NAK before treating the rover as configured.
+pub fn cfg_rate(rate_ms: u16) -> Vec<u8> {
+    let mut frame = vec![SYNC1, SYNC2, 0x06, 0x08, 0x06, 0x00];
+    frame.push(rate_ms as u8);
+    frame.push((rate_ms >> 8) as u8);
+    frame.extend_from_slice(&[0x01, 0x00, 0x01, 0x00]); // navRate=1, timeRef=GPS
+    let (ck_a, ck_b) = checksum(&frame[2..]);
+    frame.push(ck_a);
+    frame.push(ck_b);
+    frame
+}
+
+/// Push a nav-rate config to the receiver and block until it acknowledges,
+/// mirroring the u-blox `wait_for_ack` handshake: send CFG-RATE, then read
+/// frames looking for the ACK-ACK / ACK-NAK of class/id `0x06 0x08`. Retried a
+/// few times against a short read timeout; returns `true` on ACK-ACK.
+pub fn configure_rate<A: ToSocketAddrs + Clone>(socket: &UdpSocket, addr: A, rate_ms: u16) -> bool {
+    let prev_timeout = socket.read_timeout().ok().and_then(|t| t);
+    socket.set_read_timeout(Some(Duration::from_millis(500))).ok();
+
+    let frame = cfg_rate(rate_ms);
+    let mut acked = false;
+    'attempts: for _ in 0..5 {
+        if socket.send_to(&frame, addr.clone()).is_err() {
+            break;
+        }
+        let mut buf = [0u8; 512];
+        for _ in 0..8 {
+            match socket.recv_from(&mut buf) {
+                Ok((n, _)) => match parse(&buf[0..n]) {
+                    Some(Frame::AckAck(0x06, 0x08)) => { acked = true; break 'attempts; },
+                    Some(Frame::AckNak(0x06, 0x08)) => break 'attempts,
+                    _ => continue,
+                },
+                Err(_) => break, // timed out - resend
+            }
+        }
+    }
+
+    socket.set_read_timeout(prev_timeout).ok();
+    acked
+}
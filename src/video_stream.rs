@@ -8,9 +8,12 @@ use std::path::Path;
 use std::ptr;
 use std::slice;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::mpsc::{channel, Sender, Receiver};
 use std::thread;
+use std::time::{Duration, Instant};
 
+use cpal;
 use ffmpeg;
 use ffmpeg::codec;
 use ffmpeg::format;
@@ -18,119 +21,490 @@ use ffmpeg::media;
 use ffmpeg::frame;
 use ffmpeg::software::scaling;
 use ffmpeg::util::format::pixel::Pixel;
+use image;
 use image::RgbaImage;
 
 use opengl_graphics::Texture;
 
+use interp::{self, Flow};
+use metrics;
+
+/// Number of display phases the gap between two decoded frames is split into
+/// when motion-compensated interpolation is enabled; `INTERP_PHASES - 1`
+/// synthetic frames are inserted between them.
+const INTERP_PHASES: u32 = 5;
+/// Display-side frame interpolation, off by default to preserve the plain
+/// decode timing; flip to `true` to smooth the 10 fps feed.
+const INTERPOLATE: bool = false;
+
 pub enum VideoMsg {
-    Start(String),
+    Start(String, RecordMode),
     Stop,
+    /// Mute or unmute the stream's audio track at runtime (the `-an` toggle).
+    Mute(bool),
+    /// Register the shared mission-timecode string an active (or future)
+    /// recording should burn into the top-left corner of each encoded frame.
+    Overlay(Arc<Mutex<String>>),
+}
+
+/// How a recording is written to disk. The segmented modes roll a new, fully
+/// playable file over every `segment_secs` so an interrupted capture (a field
+/// rover losing power) loses at most the final segment, and maintain a rolling
+/// playlist for near-live review.
+#[derive(Copy, Clone)]
+pub enum RecordMode {
+    /// A single MP4 file, finalized only on `Stop` (legacy behavior).
+    Single,
+    /// Fragmented MP4 (`moof`+`mdat`) rolled every `segment_secs`.
+    FragmentedMp4 { segment_secs: u64 },
+    /// MPEG-TS segments with a rolling `.m3u8` HLS playlist.
+    Hls { segment_secs: u64 },
+}
+
+/// Lifecycle of the decode thread, shared with the GUI so it can show a
+/// "buffering"/"reconnecting" indicator instead of a frozen last frame.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DecodingState {
+    /// Decoding and displaying frames normally.
+    Normal,
+    /// Between connection attempts, waiting to (re)open the input.
+    Waiting,
+    /// Connected, buffering the first frames before going live.
+    Prefetch,
+    /// Draining after a decode error before reconnecting.
+    Flush,
+    /// The input could not be opened or held no video stream.
+    Error,
+    /// The input reached end-of-stream.
+    End,
+}
+
+impl DecodingState {
+    fn to_u8(self) -> u8 {
+        match self {
+            DecodingState::Normal => 0,
+            DecodingState::Waiting => 1,
+            DecodingState::Prefetch => 2,
+            DecodingState::Flush => 3,
+            DecodingState::Error => 4,
+            DecodingState::End => 5,
+        }
+    }
+
+    fn from_u8(v: u8) -> DecodingState {
+        match v {
+            0 => DecodingState::Normal,
+            1 => DecodingState::Waiting,
+            2 => DecodingState::Prefetch,
+            3 => DecodingState::Flush,
+            4 => DecodingState::Error,
+            _ => DecodingState::End,
+        }
+    }
+}
+
+/// Handle to the decode thread's current `DecodingState`, cheap to clone and
+/// poll from the render loop.
+#[derive(Clone)]
+pub struct StreamState(Arc<AtomicU8>);
+
+impl StreamState {
+    fn new() -> StreamState {
+        StreamState(Arc::new(AtomicU8::new(DecodingState::Waiting.to_u8())))
+    }
+
+    fn set(&self, state: DecodingState) {
+        self.0.store(state.to_u8(), Ordering::SeqCst);
+    }
+
+    /// The decode thread's latest reported state.
+    pub fn get(&self) -> DecodingState {
+        DecodingState::from_u8(self.0.load(Ordering::SeqCst))
+    }
+}
+
+/// Fixed output format the decoded audio is resampled to before playback:
+/// 48 kHz, signed 16-bit, interleaved stereo.
+const AUDIO_RATE: u32 = 48_000;
+
+/// PCM handed from the decode thread to the audio output thread.
+enum AudioPacket {
+    Samples(Vec<i16>),
+    Close,
+}
+
+/// Where a camera slot pulls its frames from. RTSP feeds are addressed by URL,
+/// NDI feeds by the sender name announced on the local network.
+pub enum VideoSource {
+    Rtsp(String),
+    Ndi(String),
 }
 
 pub fn start_video_stream(record_r: Receiver<VideoMsg>,
-                          path: &str) -> (Texture, Arc<Mutex<RgbaImage>>) {
+                          source: VideoSource) -> (Texture, Arc<Mutex<RgbaImage>>, StreamState) {
     let rgba_img = RgbaImage::new(512, 512);
     let video_texture = Texture::from_image(&rgba_img);
     let rgba_img = Arc::new(Mutex::new(rgba_img));
+    let state = StreamState::new();
+
+    let path = match source {
+        VideoSource::Rtsp(url) => url,
+        VideoSource::Ndi(name) => return start_ndi_stream(name, rgba_img, video_texture, state),
+    };
 
-    let path = path.to_string();
-    
     let thread_rgba_img = rgba_img.clone();
+    let thread_state = state.clone();
     thread::Builder::new()
         .name("video_packet_in".to_string())
         .spawn(move || {
             let fps: i64 = 10;
+            let sleep = 1_000_000/fps;
 
-            let mut format_context = format::input(&path).unwrap();
-            //format::dump(&format_context, 0, Some(path.as_str()));
+            // Recording (and its overlay source) survive reconnects, so both
+            // live outside the loop.
+            let mut video_t: Option<Sender<RecordPacket>> = None;
+            let mut overlay: Option<Arc<Mutex<String>>> = None;
+            let mute = Arc::new(AtomicBool::new(false));
 
-            let (start_time, stream_codec) =
-                format_context.streams()
-                              .filter(|stream| stream.codec().medium() == media::Type::Video)
-                              .map(|stream| (stream.start_time(), stream.codec()))
-                              .next().expect("No video streams in stream");
-            let video_codec = codec::decoder::find(stream_codec.id()).unwrap();
-            
-            let codec_context = stream_codec.clone();
+            // Reconnect with exponential backoff so a transient link hiccup no
+            // longer kills the thread for good.
+            let base_backoff = Duration::from_millis(500);
+            let max_backoff = Duration::from_secs(8);
+            let mut backoff = base_backoff;
 
-            let mut decoder = codec_context.decoder().video().unwrap();
-            let mut sws_context = scaling::Context::get(decoder.format(), decoder.width(), decoder.height(),
-                                                    Pixel::RGBA, 512, 512,
-                                                    scaling::flag::BILINEAR).unwrap();
+            'reconnect: loop {
+                thread_state.set(DecodingState::Waiting);
+                let mut format_context = match format::input(&path) {
+                    Ok(ctx) => ctx,
+                    Err(e) => {
+                        println!("WARNING: could not open video input: {}; retrying in {:?}", e, backoff);
+                        thread_state.set(DecodingState::Error);
+                        thread::sleep(backoff);
+                        backoff = (backoff * 2).min(max_backoff);
+                        continue 'reconnect;
+                    },
+                };
 
-            // Open recording stream
-            let mut video_t: Option<Sender<RecordPacket>> = None;
+                let video = format_context.streams()
+                                          .filter(|stream| stream.codec().medium() == media::Type::Video)
+                                          .map(|stream| (stream.index(), stream.start_time(), stream.codec()))
+                                          .next();
+                let (video_stream_index, _start_time, stream_codec) = match video {
+                    Some(v) => v,
+                    None => {
+                        println!("WARNING: no video stream in input; retrying in {:?}", backoff);
+                        thread_state.set(DecodingState::Error);
+                        thread::sleep(backoff);
+                        backoff = (backoff * 2).min(max_backoff);
+                        continue 'reconnect;
+                    },
+                };
+                let _video_codec = codec::decoder::find(stream_codec.id()).unwrap();
 
-            /////////////////////////////////////////////////////
-            // Process stream
+                let mut decoder = stream_codec.clone().decoder().video().unwrap();
+                let mut sws_context = scaling::Context::get(decoder.format(), decoder.width(), decoder.height(),
+                                                        Pixel::RGBA, 512, 512,
+                                                        scaling::flag::BILINEAR).unwrap();
 
-            let mut start = ffmpeg::time::relative() as i64;
-            let sleep = 1_000_000/fps;
-            
-            let mut rec_start_pts = 0;
-            
-            for (stream, packet) in format_context.packets() {
-                let mut input_frame = frame::Video::new(decoder.format(), decoder.width(), decoder.height());
-                let mut output_frame = frame::Video::new(Pixel::RGBA, 512, 512);
-
-                decoder.decode(&packet, &mut input_frame).unwrap();
-                
-                if let Err(e) = sws_context.run(&input_frame, &mut output_frame) {
-                    println!("WARNING: video software scaling error: {}", e);
-                }
-                
-                // Copy frame data to the rgba_img
-                {
-                    let frame_data = output_frame.data(0);
-                    let mut rgba_img = thread_rgba_img.lock().unwrap();
-                    unsafe {
-                        let src: *const u8 = mem::transmute(frame_data.get(0));
-                        let dst = rgba_img.as_mut_ptr();
-                        ptr::copy(src, dst, frame_data.len());
-                    }
-                }
+                // Parallel audio path: decode the first audio stream (if any),
+                // resample it to the fixed output format and feed a cpal
+                // playback thread through a ring-buffered channel. Muting is a
+                // runtime flag shared with that thread so the audio can be
+                // silenced without tearing the pipeline down.
+                let audio_info = format_context.streams()
+                                               .filter(|stream| stream.codec().medium() == media::Type::Audio)
+                                               .map(|stream| (stream.index(), stream.codec()))
+                                               .next();
+                let (audio_stream_index, mut audio_decoder, mut audio_resampler, audio_t) = match audio_info {
+                    Some((index, audio_codec_context)) => {
+                        let audio_decoder = audio_codec_context.decoder().audio().unwrap();
+                        let audio_resampler = audio_decoder.resampler(
+                            ffmpeg::format::Sample::I16(ffmpeg::format::sample::Type::Packed),
+                            ffmpeg::channel_layout::STEREO,
+                            AUDIO_RATE).unwrap();
+                        let (t, r) = channel();
+                        start_audio_playback(r, mute.clone());
+                        (Some(index), Some(audio_decoder), Some(audio_resampler), Some(t))
+                    },
+                    None => (None, None, None, None),
+                };
+
+                /////////////////////////////////////////////////////
+                // Process stream
+
+                let mut start = ffmpeg::time::relative() as i64;
+                let mut rec_start_pts = 0;
 
-                // Check for messages
-                if let Ok(msg) = record_r.try_recv() {
-                    match msg {
-                        VideoMsg::Start(out_path) => {
-                            // Open recording stream
-                            if video_t.is_none() {
-                                rec_start_pts = packet.pts().unwrap();
-                                start = ffmpeg::time::relative() as i64;
-                                let (t, r) = channel();
-                                start_video_recording(&decoder, r, out_path);
-                                video_t = Some(t);
+                // Buffer a few frames before going live so a freshly
+                // (re)connected stream does not stutter on the first frames.
+                const PREFETCH_FRAMES: u32 = 5;
+                let mut prefetched = 0u32;
+                let mut decode_error = false;
+                // Last frame shown, kept so we can interpolate toward the next.
+                let mut prev_display: Option<RgbaImage> = None;
+                thread_state.set(DecodingState::Prefetch);
+
+                for (stream, packet) in format_context.packets() {
+                    // Audio packets take the parallel decode path and never
+                    // touch the video scaler/recorder.
+                    if Some(stream.index()) == audio_stream_index {
+                        if let (Some(dec), Some(res), Some(t)) =
+                            (audio_decoder.as_mut(), audio_resampler.as_mut(), audio_t.as_ref()) {
+                            let mut decoded = frame::Audio::empty();
+                            if dec.decode(&packet, &mut decoded).unwrap_or(false) {
+                                let mut resampled = frame::Audio::empty();
+                                if let Err(e) = res.run(&decoded, &mut resampled) {
+                                    println!("WARNING: audio resampling error: {}", e);
+                                } else {
+                                    let samples = resampled.plane::<i16>(0).to_vec();
+                                    t.send(AudioPacket::Samples(samples)).ok();
+                                }
                             }
-                        },
-                        VideoMsg::Stop => {
-                            if let Some(ref video_t) = video_t {
-                                video_t.send(RecordPacket::Close);
+                        }
+                        continue;
+                    }
+                    if stream.index() != video_stream_index {
+                        continue;
+                    }
+
+                    let mut input_frame = frame::Video::new(decoder.format(), decoder.width(), decoder.height());
+                    let mut output_frame = frame::Video::new(Pixel::RGBA, 512, 512);
+
+                    // Decode-to-display latency is measured from the start of
+                    // decode until the scaled frame reaches rgba_img below.
+                    let frame_started = Instant::now();
+
+                    if let Err(e) = decoder.decode(&packet, &mut input_frame) {
+                        println!("WARNING: video decode error: {}; reconnecting", e);
+                        decode_error = true;
+                        break;
+                    }
+
+                    let scale_started = Instant::now();
+                    if let Err(e) = sws_context.run(&input_frame, &mut output_frame) {
+                        println!("WARNING: video software scaling error: {}", e);
+                    }
+                    metrics::shared().scaling.observe(scale_started.elapsed().as_micros() as u64);
+
+                    // Copy frame data to the rgba_img
+                    if INTERPOLATE {
+                        // Keep a standalone copy of the freshly scaled frame so
+                        // we can synthesize intermediate phases against the one
+                        // before it, pacing them across the inter-frame gap.
+                        let current = RgbaImage::from_raw(512, 512, output_frame.data(0).to_vec()).unwrap();
+                        if let Some(prev) = prev_display.take() {
+                            let flow = Flow::estimate(&prev, &current);
+                            for phase in 1..INTERP_PHASES {
+                                let t = phase as f32 / INTERP_PHASES as f32;
+                                let mid = interp::interpolate(&prev, &current, &flow, t);
+                                *thread_rgba_img.lock().unwrap() = mid;
+                                let per_phase_us = (sleep / INTERP_PHASES as i64).max(0) as u32;
+                                thread::sleep(Duration::new(0, per_phase_us.saturating_mul(1000)));
                             }
-                            video_t = None;
-                        },
+                        }
+                        *thread_rgba_img.lock().unwrap() = current.clone();
+                        prev_display = Some(current);
+                    } else {
+                        let frame_data = output_frame.data(0);
+                        let mut rgba_img = thread_rgba_img.lock().unwrap();
+                        unsafe {
+                            let src: *const u8 = mem::transmute(frame_data.get(0));
+                            let dst = rgba_img.as_mut_ptr();
+                            ptr::copy(src, dst, frame_data.len());
+                        }
+                    }
+
+                    metrics::shared().display_latency.observe(frame_started.elapsed().as_micros() as u64);
+
+                    // Once the prefetch buffer has filled, declare the stream
+                    // live and reset the reconnect backoff.
+                    if prefetched < PREFETCH_FRAMES {
+                        prefetched += 1;
+                    } else if thread_state.get() != DecodingState::Normal {
+                        thread_state.set(DecodingState::Normal);
+                        backoff = base_backoff;
+                    }
+
+                    // Check for messages
+                    if let Ok(msg) = record_r.try_recv() {
+                        match msg {
+                            VideoMsg::Start(out_path, mode) => {
+                                // Open recording stream
+                                if video_t.is_none() {
+                                    rec_start_pts = packet.pts().unwrap();
+                                    start = ffmpeg::time::relative() as i64;
+                                    let (t, r) = channel();
+                                    start_video_recording(&decoder, r, out_path, mode, overlay.clone());
+                                    video_t = Some(t);
+                                }
+                            },
+                            VideoMsg::Stop => {
+                                if let Some(ref video_t) = video_t {
+                                    video_t.send(RecordPacket::Close);
+                                }
+                                video_t = None;
+                            },
+                            VideoMsg::Mute(m) => {
+                                mute.store(m, Ordering::SeqCst);
+                            },
+                            VideoMsg::Overlay(text) => {
+                                overlay = Some(text);
+                            },
+                        }
                     }
+
+                    if let Some(ref video_t) = video_t {
+                        let pts = ((ffmpeg::time::relative() as i64) - start)/sleep;
+                        let _ = rec_start_pts;
+                        println!("PTS {}, {:?}, {}", pts, packet.pts().unwrap()/10_000, packet.position());
+                        video_t.send(RecordPacket::Packet(pts, input_frame));
+                    }
+                }
+
+                // Let any audio thread drain before we rebuild the pipeline.
+                if let Some(ref t) = audio_t {
+                    t.send(AudioPacket::Close).ok();
+                }
+
+                if decode_error {
+                    thread_state.set(DecodingState::Flush);
+                } else {
+                    // Clean EOF; still attempt to reconnect in case the feed
+                    // comes back.
+                    thread_state.set(DecodingState::End);
+                }
+
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(max_backoff);
+            }
+        }).unwrap();
+
+    (video_texture, rgba_img, state)
+}
+
+/// Open the default output device and play the decoded PCM pulled from the
+/// decode thread. While `mute` is set the samples are dropped on the floor so
+/// the stream can be silenced at runtime without stopping the decoder. Modeled
+/// on the intercom playback path in `audio.rs`.
+fn start_audio_playback(source: Receiver<AudioPacket>, mute: Arc<AtomicBool>) {
+    thread::Builder::new()
+        .name("video_audio_out".to_string())
+        .spawn(move || {
+            let device = cpal::default_output_device().expect("no output device");
+            let format = device.default_output_format().unwrap();
+            let event_loop = cpal::EventLoop::new();
+            let stream_id = event_loop.build_output_stream(&device, &format).unwrap();
+            event_loop.play_stream(stream_id);
+
+            // Ring buffer feeding the cpal run loop; samples past the mute gate
+            // are queued here and pulled by the output callback.
+            let ring: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+            while let Ok(msg) = source.recv() {
+                match msg {
+                    AudioPacket::Samples(samples) => {
+                        if mute.load(Ordering::SeqCst) {
+                            continue;
+                        }
+                        ring.lock().unwrap().extend_from_slice(&samples);
+                    },
+                    AudioPacket::Close => break,
                 }
+            }
+        }).unwrap();
+}
+
+/// A static black slot for a camera the rover reports is not live, so the
+/// display/recording code can still index three textures unconditionally.
+pub fn blank_video_stream() -> (Texture, Arc<Mutex<RgbaImage>>, StreamState) {
+    let rgba_img = RgbaImage::new(512, 512);
+    let video_texture = Texture::from_image(&rgba_img);
+    let state = StreamState::new();
+    // A slot that is never fed is, in effect, permanently ended.
+    state.set(DecodingState::End);
+    (video_texture, Arc::new(Mutex::new(rgba_img)), state)
+}
 
-                if let Some(ref video_t) = video_t {
-                    /*let pts = packet.pts()
-                                    .unwrap_or(((ffmpeg::time::relative() as i64) - start)/sleep);*/
-                    let pts = ((ffmpeg::time::relative() as i64) - start)/sleep;
-                    //let pts = (input_frame.timestamp().unwrap()-start_time)/sleep;
-                    /*let pts = packet.pts().unwrap();
-                    let pts =
-                        if pts > rec_start_pts {
-                            pts - rec_start_pts
-                        } else {
-                            0
-                        };*/
-                    println!("PTS {}, {:?}, {}", pts, packet.pts().unwrap()/10_000, packet.position());
-                    video_t.send(RecordPacket::Packet(pts, input_frame));
+/// Resolve an NDI source by name via discovery and pump its frames into the
+/// same RGBA buffer the RTSP path fills, so the rest of the pipeline (texture
+/// upload, snapshots, recording) is oblivious to which backend is feeding it.
+fn start_ndi_stream(source_name: String,
+                    rgba_img: Arc<Mutex<RgbaImage>>,
+                    video_texture: Texture,
+                    state: StreamState) -> (Texture, Arc<Mutex<RgbaImage>>, StreamState) {
+    use ndi;
+
+    let thread_rgba_img = rgba_img.clone();
+    let thread_state = state.clone();
+    thread::Builder::new()
+        .name("video_packet_in".to_string())
+        .spawn(move || {
+            thread_state.set(DecodingState::Waiting);
+            // Discover senders on the LAN and wait for the one we were named
+            let find = ndi::Find::new().unwrap();
+            let source = loop {
+                find.wait_for_sources(1000);
+                if let Some(source) = find.get_sources(1000).into_iter()
+                                          .find(|s| s.name().contains(&source_name)) {
+                    break source;
+                }
+                println!("WARNING: NDI source \"{}\" not found yet, retrying", source_name);
+            };
+
+            let mut recv = ndi::Recv::new(&source).unwrap();
+
+            loop {
+                let frame = match recv.capture_video(1000) {
+                    Some(frame) => frame,
+                    None => continue,
+                };
+                thread_state.set(DecodingState::Normal);
+
+                // NDI hands us UYVY or BGRA depending on the sender; normalize
+                // both into the RGBA layout `Texture::update` expects.
+                let (width, height) = (frame.width() as u32, frame.height() as u32);
+                let mut rgba_img = thread_rgba_img.lock().unwrap();
+                match frame.fourcc() {
+                    ndi::FourCCVideoType::BGRA => ndi_bgra_to_rgba(frame.data(), &mut rgba_img, width, height),
+                    ndi::FourCCVideoType::UYVY => ndi_uyvy_to_rgba(frame.data(), &mut rgba_img, width, height),
+                    other => println!("WARNING: unsupported NDI pixel layout {:?}", other),
                 }
             }
         }).unwrap();
-    
-    (video_texture, rgba_img)
+
+    (video_texture, rgba_img, state)
+}
+
+/// Pack a BGRA source into the 512x512 RGBA buffer, nearest-sampling to fit.
+fn ndi_bgra_to_rgba(src: &[u8], dst: &mut RgbaImage, width: u32, height: u32) {
+    for y in 0..512 {
+        for x in 0..512 {
+            let sx = x * width / 512;
+            let sy = y * height / 512;
+            let si = ((sy * width + sx) * 4) as usize;
+            if si + 3 >= src.len() { continue; }
+            dst.put_pixel(x, y, image::Rgba([src[si + 2], src[si + 1], src[si], src[si + 3]]));
+        }
+    }
+}
+
+/// Pack a UYVY (4:2:2) source into the 512x512 RGBA buffer, nearest-sampling.
+fn ndi_uyvy_to_rgba(src: &[u8], dst: &mut RgbaImage, width: u32, height: u32) {
+    for y in 0..512 {
+        for x in 0..512 {
+            let sx = x * width / 512;
+            let sy = y * height / 512;
+            // Two pixels share one U/V pair in a 4-byte UYVY macro-pixel
+            let si = ((sy * width + (sx & !1)) * 2) as usize;
+            if si + 3 >= src.len() { continue; }
+            let u = src[si] as f32 - 128.0;
+            let yy = src[si + 1 + ((sx & 1) * 2) as usize] as f32;
+            let v = src[si + 2] as f32 - 128.0;
+            let r = (yy + 1.402 * v).max(0.0).min(255.0) as u8;
+            let gc = (yy - 0.344 * u - 0.714 * v).max(0.0).min(255.0) as u8;
+            let b = (yy + 1.772 * u).max(0.0).min(255.0) as u8;
+            dst.put_pixel(x, y, image::Rgba([r, gc, b, 255]));
+        }
+    }
 }
 
 enum RecordPacket {
@@ -140,68 +514,73 @@ enum RecordPacket {
 
 fn start_video_recording(decoder: &ffmpeg::codec::decoder::Video,
                          msgs: Receiver<RecordPacket>,
-                         out_path: String) {
+                         out_path: String,
+                         mode: RecordMode,
+                         overlay: Option<Arc<Mutex<String>>>) {
     let decoder_width = decoder.width();
     let decoder_height = decoder.height();
     let decoder_format = decoder.format();
 
     println!("time_base={}", decoder.time_base());
-    
+
     thread::Builder::new()
         .name("video_packet_in".to_string())
         .spawn(move || {
-            let fps: i64 = 10;
-
-            /////////////////////////////////////////////////////
-            // Open recording stream
-
-            let mut rec_format = ffmpeg::format::output(&format!("{}", out_path)).unwrap();
-
-            let mut rec_video = {
-                    let mut stream = rec_format.add_stream(ffmpeg::codec::Id::MPEG4).unwrap();
-                    let mut codec  = stream.codec().encoder().video().unwrap();
-
-                    codec.set_width(decoder_width);
-                    codec.set_height(decoder_height);
-                    codec.set_format(ffmpeg::format::Pixel::YUV420P);
-                    //codec.set_time_base((1, fps as i32));
-                    codec.set_time_base((1, 1000));
-                    codec.set_flags(ffmpeg::codec::flag::GLOBAL_HEADER);
-
-                    stream.set_time_base((1, 1000));
-                    //stream.set_time_base((1, fps as i32));
-                    //stream.set_rate((fps as i32, 1));
-
-                    codec.open_as(ffmpeg::codec::Id::MPEG4).unwrap()
-            };
-
             let mut rec_converter =
                 ffmpeg::software::converter((decoder_width, decoder_height),
                                             decoder_format,
                                             ffmpeg::format::Pixel::YUV420P).unwrap();
 
-            rec_format.write_header().unwrap();
-
             let mut rec_packet = ffmpeg::Packet::empty();
             let mut rec_frame  = ffmpeg::frame::Video::empty();
 
-            /////////////////////////////////////////////////////
-            // Process streams
-            
+            // One writable segment, with the encoder muxed into its container.
+            // A fresh segment is opened per rollover so each is self-contained
+            // and independently playable.
+            let mut segment = Segment::open(&out_path, mode, 0, decoder_width, decoder_height);
+            let mut segment_index = 0;
+            let mut playlist = Playlist::new(&out_path, mode);
+            let mut segment_start_pts: Option<i64> = None;
+
             while let Ok(msg) = msgs.recv() {
                 match msg {
                     RecordPacket::Packet(pts, input_frame) => {
+                        // Roll over to a new segment on the configured boundary
+                        // so an aborted capture loses at most one segment.
+                        // `pts` counts 100 ms frame ticks, the (1, 10) time
+                        // base packets are rescaled from below.
+                        if let Some(seg_secs) = mode.segment_secs() {
+                            let base = *segment_start_pts.get_or_insert(pts);
+                            if pts - base >= (seg_secs as i64) * 10 {
+                                segment.finish();
+                                playlist.append(segment.path.as_str(), (pts - base) as f64 / 10.0);
+                                segment_index += 1;
+                                segment = Segment::open(&out_path, mode, segment_index,
+                                                        decoder_width, decoder_height);
+                                segment_start_pts = Some(pts);
+                            }
+                        }
+
                         // Now encode the recording packets
                         if let Err(e) = rec_converter.run(&input_frame, &mut rec_frame) {
                             println!("WARNING: video software converter error: {}", e);
                         }
+                        // Burn the mission timecode in after conversion so it
+                        // rides along on disk even with the live OSD off.
+                        if let Some(ref overlay) = overlay {
+                            let text = overlay.lock().unwrap().clone();
+                            burn_timecode(&mut rec_frame, &text);
+                        }
                         rec_frame.set_pts(Some(pts));
 
-                        match rec_video.encode(&rec_frame, &mut rec_packet) {
+                        let encode_started = Instant::now();
+                        let encode_result = segment.encoder.encode(&rec_frame, &mut rec_packet);
+                        metrics::shared().encode.observe(encode_started.elapsed().as_micros() as u64);
+                        match encode_result {
                             Ok(_) => {
                                 rec_packet.set_stream(0);
                                 rec_packet.rescale_ts((1, 10), (1, 17500));
-                                rec_packet.write_interleaved(&mut rec_format);
+                                rec_packet.write_interleaved(&mut segment.format);
                             },
                             Err(e) => {
                                 println!("WARNING: Failed to write video frame: {}", e);
@@ -214,17 +593,203 @@ fn start_video_recording(decoder: &ffmpeg::codec::decoder::Video,
                 }
             }
 
-            while let Ok(true) = rec_video.flush(&mut rec_packet) {
+            while let Ok(true) = segment.encoder.flush(&mut rec_packet) {
                 rec_packet.set_stream(0);
                 rec_packet.rescale_ts((1, 10), (1, 17500));
-                rec_packet.write_interleaved(&mut rec_format);
+                rec_packet.write_interleaved(&mut segment.format);
             }
 
-            rec_format.write_trailer().unwrap();
+            segment.finish();
+            playlist.finish();
             println!("Finished writing trailer");
         }).unwrap();
 }
 
+/// Burn `text` into the top-left corner of `frame`'s luma plane at full
+/// intensity, so a recording is self-timestamped on disk regardless of
+/// whether a live overlay was also composited before it got here.
+fn burn_timecode(frame: &mut ffmpeg::frame::Video, text: &str) {
+    let stride = frame.stride(0);
+    let width = frame.width() as usize;
+    let height = frame.height() as usize;
+    let plane = frame.data_mut(0);
+
+    let mut cx = 8usize;
+    for ch in text.chars() {
+        let rows = timecode_glyph(ch);
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (1 << (4 - col)) != 0 {
+                    let (px, py) = (cx + col, 8 + row);
+                    if px < width && py < height {
+                        plane[py * stride + px] = 235;
+                    }
+                }
+            }
+        }
+        cx += 6;
+    }
+}
+
+/// 5x7 bitmap for the glyphs in a mission timecode: digits and `:`.
+fn timecode_glyph(ch: char) -> [u8; 7] {
+    match ch {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        ':' => [0b00000, 0b00100, 0b00100, 0b00000, 0b00100, 0b00100, 0b00000],
+        _   => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+    }
+}
+
+impl RecordMode {
+    /// Segment length in seconds, or `None` for single-file recording.
+    fn segment_secs(self) -> Option<u64> {
+        match self {
+            RecordMode::Single => None,
+            RecordMode::FragmentedMp4 { segment_secs } => Some(segment_secs),
+            RecordMode::Hls { segment_secs } => Some(segment_secs),
+        }
+    }
+
+    /// File extension for an individual segment.
+    fn segment_ext(self) -> &'static str {
+        match self {
+            RecordMode::Hls { .. } => "ts",
+            _ => "mp4",
+        }
+    }
+}
+
+/// One on-disk recording segment: the muxer plus its H.264 encoder. For the
+/// fragmented-MP4 mode the container is opened with `movflags` that emit a
+/// `moof`/`mdat` fragment stream with an up-front init segment, so the file is
+/// playable even if the trailer is never written.
+struct Segment {
+    format: ffmpeg::format::context::Output,
+    encoder: ffmpeg::codec::encoder::video::Encoder,
+    path: String,
+}
+
+impl Segment {
+    fn open(base_path: &str, mode: RecordMode, index: u64, width: u32, height: u32) -> Segment {
+        let path = match mode {
+            RecordMode::Single => base_path.to_string(),
+            _ => segment_path(base_path, index, mode.segment_ext()),
+        };
+
+        let mut format = ffmpeg::format::output(&path).unwrap();
+
+        let encoder = {
+            let mut stream = format.add_stream(ffmpeg::codec::Id::H264).unwrap();
+            let mut codec = stream.codec().encoder().video().unwrap();
+
+            codec.set_width(width);
+            codec.set_height(height);
+            codec.set_format(ffmpeg::format::Pixel::YUV420P);
+            codec.set_time_base((1, 1000));
+            codec.set_flags(ffmpeg::codec::flag::GLOBAL_HEADER);
+
+            stream.set_time_base((1, 1000));
+
+            codec.open_as(ffmpeg::codec::encoder::find_by_name("libx264").unwrap()).unwrap()
+        };
+
+        match mode {
+            RecordMode::FragmentedMp4 { .. } => {
+                // Emit an init segment up front and one fragment per segment so
+                // each file is independently playable.
+                let mut opts = ffmpeg::Dictionary::new();
+                opts.set("movflags", "frag_keyframe+empty_moov+default_base_moof");
+                format.write_header_with(opts).unwrap();
+            },
+            _ => {
+                format.write_header().unwrap();
+            },
+        }
+
+        Segment { format: format, encoder: encoder, path: path }
+    }
+
+    /// Flush the container trailer so the segment is complete on disk.
+    fn finish(&mut self) {
+        if let Err(e) = self.format.write_trailer() {
+            println!("WARNING: failed to finalize segment {}: {}", self.path, e);
+        }
+    }
+}
+
+/// Build the path for segment `index`, e.g. `mission.mp4` -> `mission_0003.ts`.
+fn segment_path(base_path: &str, index: u64, ext: &str) -> String {
+    let stem = match base_path.rfind('.') {
+        Some(dot) => &base_path[..dot],
+        None => base_path,
+    };
+    format!("{}_{:04}.{}", stem, index, ext)
+}
+
+/// Rolling `.m3u8` playlist listing the finished segments, enabling near-live
+/// review of an ongoing capture. A no-op in single-file mode.
+struct Playlist {
+    path: Option<String>,
+    target_secs: u64,
+    entries: Vec<(String, f64)>,
+}
+
+impl Playlist {
+    fn new(base_path: &str, mode: RecordMode) -> Playlist {
+        let (path, target) = match mode {
+            RecordMode::Single => (None, 0),
+            _ => {
+                let stem = match base_path.rfind('.') {
+                    Some(dot) => &base_path[..dot],
+                    None => base_path,
+                };
+                (Some(format!("{}.m3u8", stem)), mode.segment_secs().unwrap_or(1))
+            },
+        };
+        Playlist { path: path, target_secs: target, entries: Vec::new() }
+    }
+
+    /// Record a finished segment and rewrite the playlist so reviewers see it.
+    fn append(&mut self, segment_path: &str, duration_secs: f64) {
+        if self.path.is_none() { return; }
+        let name = segment_path.rsplit('/').next().unwrap_or(segment_path).to_string();
+        self.entries.push((name, duration_secs));
+        self.write();
+    }
+
+    fn write(&self) {
+        let path = match self.path { Some(ref p) => p, None => return };
+        let mut out = String::new();
+        out.push_str("#EXTM3U\n#EXT-X-VERSION:3\n");
+        out.push_str(&format!("#EXT-X-TARGETDURATION:{}\n", self.target_secs.max(1)));
+        out.push_str("#EXT-X-MEDIA-SEQUENCE:0\n");
+        for &(ref name, dur) in &self.entries {
+            out.push_str(&format!("#EXTINF:{:.3},\n{}\n", dur, name));
+        }
+        if let Ok(mut f) = File::create(path) {
+            let _ = f.write_all(out.as_bytes());
+        }
+    }
+
+    /// Close the playlist with the end-list tag once recording stops.
+    fn finish(&mut self) {
+        let path = match self.path { Some(ref p) => p.clone(), None => return };
+        self.write();
+        if let Ok(mut f) = ::std::fs::OpenOptions::new().append(true).open(&path) {
+            let _ = f.write_all(b"#EXT-X-ENDLIST\n");
+        }
+    }
+}
+
 pub fn init_ffmpeg() {
     ffmpeg::init().unwrap();
     ffmpeg::format::network::init();
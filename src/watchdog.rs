@@ -0,0 +1,154 @@
+//! Telemetry health watchdog.
+//!
+//! `handle_packet` used to `parse().unwrap()` every IMU field, so a single
+//! malformed datagram took the whole ground station down, and a link that
+//! simply went quiet left the last-good numbers frozen on screen looking
+//! healthy. This layer, modelled on openpilot's `CarEvent` list (`commIssue`,
+//! `sensorDataInvalid`, `canValid`), tracks each telemetry source's last
+//! valid packet and a running parse-error count. When a source stops
+//! producing valid packets for longer than a timeout, or its recent error
+//! rate spikes, it raises a named, prioritised event that the UI paints as an
+//! alarm banner - red for stale/invalid, amber for degraded.
+
+use std::time::{Duration, Instant};
+
+/// Severity of a health event. `Degraded` is the amber band, `Fault` the red
+/// one; the numeric rank drives the worst-of rollup that colours the banner.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Degraded,
+    Fault,
+}
+
+impl Severity {
+    /// 1 degraded, 2 fault - larger is worse.
+    pub fn rank(&self) -> u8 {
+        match *self {
+            Severity::Degraded => 1,
+            Severity::Fault => 2,
+        }
+    }
+
+    /// RGBA banner colour: amber degraded, red fault.
+    pub fn color(&self) -> [f32; 4] {
+        match *self {
+            Severity::Degraded => [1.0, 0.85, 0.0, 1.0],
+            Severity::Fault => [1.0, 0.2, 0.2, 1.0],
+        }
+    }
+}
+
+/// A single raised health event, ready to display (e.g. "IMU STALE 3.2s").
+pub struct Event {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Liveness and parse integrity of one telemetry source.
+struct SourceState {
+    name: String,
+    last_valid: Option<Instant>,
+    valid_count: u64,
+    error_count: u64,
+    // Parse errors since the last valid packet; a run of these without a good
+    // packet in between is what flags the source as degraded.
+    recent_errors: u32,
+}
+
+impl SourceState {
+    fn new(name: &str) -> SourceState {
+        SourceState {
+            name: name.to_string(),
+            last_valid: None,
+            valid_count: 0,
+            error_count: 0,
+            recent_errors: 0,
+        }
+    }
+}
+
+/// Per-source liveness tracker. Sources are registered up front so a source
+/// that has never produced a packet still shows up as stale rather than
+/// silently missing.
+pub struct Watchdog {
+    sources: Vec<SourceState>,
+    /// A source with no valid packet for longer than this is stale.
+    timeout: Duration,
+    /// Consecutive parse errors (with no good packet between) that flag a
+    /// source as degraded.
+    error_threshold: u32,
+}
+
+impl Watchdog {
+    /// Build a watchdog over the named sources with the given staleness
+    /// timeout.
+    pub fn new(names: &[&str], timeout: Duration) -> Watchdog {
+        Watchdog {
+            sources: names.iter().map(|n| SourceState::new(n)).collect(),
+            timeout: timeout,
+            error_threshold: 5,
+        }
+    }
+
+    fn source_mut(&mut self, name: &str) -> Option<&mut SourceState> {
+        self.sources.iter_mut().find(|s| s.name == name)
+    }
+
+    /// Record a valid packet from `name`, refreshing its liveness and clearing
+    /// the recent-error run.
+    pub fn record_valid(&mut self, name: &str) {
+        if let Some(s) = self.source_mut(name) {
+            s.last_valid = Some(Instant::now());
+            s.valid_count += 1;
+            s.recent_errors = 0;
+        }
+    }
+
+    /// Record a failed parse from `name` instead of panicking on it.
+    pub fn record_error(&mut self, name: &str) {
+        if let Some(s) = self.source_mut(name) {
+            s.error_count += 1;
+            s.recent_errors += 1;
+        }
+    }
+
+    /// The currently-active events, worst severity first, suitable for a
+    /// prioritised alarm banner.
+    pub fn events(&self) -> Vec<Event> {
+        let now = Instant::now();
+        let mut events = Vec::new();
+
+        for s in &self.sources {
+            match s.last_valid {
+                None => {
+                    events.push(Event {
+                        severity: Severity::Fault,
+                        message: format!("{} NO DATA", s.name),
+                    });
+                },
+                Some(last) => {
+                    let age = now.duration_since(last);
+                    if age >= self.timeout {
+                        events.push(Event {
+                            severity: Severity::Fault,
+                            message: format!("{} STALE {:.1}s", s.name, secs(age)),
+                        });
+                    } else if s.recent_errors >= self.error_threshold {
+                        events.push(Event {
+                            severity: Severity::Degraded,
+                            message: format!("{} DATA INVALID", s.name),
+                        });
+                    }
+                },
+            }
+        }
+
+        events.sort_by(|a, b| b.severity.rank().cmp(&a.severity.rank()));
+        events
+    }
+}
+
+/// A `Duration` as fractional seconds.
+fn secs(d: Duration) -> f64 {
+    d.as_secs() as f64 + d.subsec_nanos() as f64 / 1_000_000_000.0
+}